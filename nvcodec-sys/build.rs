@@ -1,6 +1,23 @@
 use std::env;
+use std::fs;
 use std::path::PathBuf;
 
+/// SDK releases the `nvcodec_sdk_*` cfg flags are emitted for, here and in
+/// dependents.
+const KNOWN_VERSIONS: [(u32, u32); 6] = [(11, 0), (11, 1), (12, 0), (12, 1), (12, 2), (13, 0)];
+
+/// SDK releases with bindings in `src/bindings`, `sdk_<major>_<minor>.rs`
+/// for decoding and `nvenc_<major>_<minor>.rs` for the nvenc feature.
+#[cfg(not(feature = "bindgen"))]
+const BUNDLED_VERSIONS: [(u32, u32); 2] = [(12, 2), (13, 0)];
+
+/// SDK releases of `BUNDLED_VERSIONS` that also have NVENC bindings. 13.0
+/// changed the layouts of `NV_ENC_CONFIG`, `NV_ENC_INITIALIZE_PARAMS` and
+/// `NV_ENC_PIC_PARAMS`, so its bindings must be generated from the header.
+#[cfg(not(feature = "bindgen"))]
+const BUNDLED_NVENC_VERSIONS: [(u32, u32); 1] = [(12, 2)];
+
+#[cfg(feature = "bindgen")]
 fn find_dir(env_key: &'static str, candidates: Vec<&'static str>) -> Option<PathBuf> {
     match env::var_os(env_key) {
         Some(val) => Some(PathBuf::from(&val)),
@@ -17,7 +34,167 @@ fn find_dir(env_key: &'static str, candidates: Vec<&'static str>) -> Option<Path
     }
 }
 
-fn main() {
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let (major, minor) = version.trim().split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+fn requested_version() -> Option<(u32, u32)> {
+    let version = env::var("NVCODEC_SDK_VERSION").ok()?;
+    Some(parse_version(&version).unwrap_or_else(|| {
+        panic!("NVCODEC_SDK_VERSION should look like 12.2, got {}", version)
+    }))
+}
+
+/// Reads the SDK version from `nvEncodeAPI.h`, which ships next to
+/// `nvcuvid.h`; the decode headers carry none.
+#[cfg(feature = "bindgen")]
+fn header_version(include: &std::path::Path) -> Option<(u32, u32)> {
+    let header = fs::read_to_string(include.join("nvEncodeAPI.h")).ok()?;
+    let define = |name: &str| {
+        header.lines().find_map(|line| {
+            let value = line.trim().strip_prefix("#define")?.trim().strip_prefix(name)?;
+            value.trim().parse().ok()
+        })
+    };
+
+    Some((define("NVENCAPI_MAJOR_VERSION")?, define("NVENCAPI_MINOR_VERSION")?))
+}
+
+/// Evaluates the right-hand side of a `*_VER` define, which is built from
+/// `NVENCAPI_STRUCT_VERSION(n)`, integers, `|` and `<<`.
+#[cfg(feature = "bindgen")]
+struct VersionExpr<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+    api_version: u64,
+    known: &'a [(String, u64)],
+}
+
+#[cfg(feature = "bindgen")]
+impl<'a> VersionExpr<'a> {
+    fn tokenize(expr: &'a str) -> Vec<&'a str> {
+        let mut tokens = vec![];
+        let mut rest = expr.trim_start();
+        while !rest.is_empty() {
+            let len = if rest.starts_with("<<") {
+                2
+            } else if rest.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_') {
+                rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(rest.len())
+            } else {
+                rest.chars().next().unwrap().len_utf8()
+            };
+            tokens.push(&rest[..len]);
+            rest = rest[len..].trim_start();
+        }
+        tokens
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.tokens.get(self.pos).copied();
+        self.pos += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn or(&mut self) -> Option<u64> {
+        let mut value = self.shift()?;
+        while self.peek() == Some("|") {
+            self.pos += 1;
+            value |= self.shift()?;
+        }
+        Some(value)
+    }
+
+    fn shift(&mut self) -> Option<u64> {
+        let mut value = self.atom()?;
+        while self.peek() == Some("<<") {
+            self.pos += 1;
+            value <<= self.atom()?;
+        }
+        Some(value)
+    }
+
+    fn atom(&mut self) -> Option<u64> {
+        match self.next()? {
+            "(" => {
+                let value = self.or()?;
+                (self.next()? == ")").then_some(value)
+            }
+            "NVENCAPI_STRUCT_VERSION" => {
+                let ver = self.atom()?;
+                Some(self.api_version | (ver << 16) | (0x7 << 28))
+            }
+            "NVENCAPI_VERSION" => Some(self.api_version),
+            token => {
+                let digits = token.trim_end_matches(['u', 'U', 'l', 'L']);
+                let value = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+                    Some(hex) => u64::from_str_radix(hex, 16).ok(),
+                    None => digits.parse().ok(),
+                };
+                value.or_else(|| {
+                    self.known.iter().find(|(name, _)| name == token).map(|(_, value)| *value)
+                })
+            }
+        }
+    }
+}
+
+/// The API version and every `*_VER` struct version of `nvEncodeAPI.h`, as
+/// Rust constants. They are function-like macros bindgen cannot expand, and
+/// must match the header the types came from.
+#[cfg(feature = "bindgen")]
+fn nvenc_versions(include: &std::path::Path) -> String {
+    let source = fs::read_to_string(include.join("nvEncodeAPI.h")).expect("Couldn't read nvEncodeAPI.h");
+    let (major, minor) = header_version(include).expect("No NVENCAPI version in nvEncodeAPI.h");
+    let api_version = major as u64 | ((minor as u64) << 24);
+
+    let mut known: Vec<(String, u64)> = vec![];
+    for line in source.lines() {
+        let line = line.split("//").next().unwrap().split("/*").next().unwrap();
+        let Some(define) = line.trim().strip_prefix("#define") else {
+            continue;
+        };
+        let Some((name, expr)) = define.trim().split_once(char::is_whitespace) else {
+            continue;
+        };
+        if !name.ends_with("_VER") || !name.starts_with("NV_ENC") {
+            continue;
+        }
+
+        let mut parser = VersionExpr {
+            tokens: VersionExpr::tokenize(expr),
+            pos: 0,
+            api_version,
+            known: &known,
+        };
+        let value = parser.or().filter(|_| parser.peek().is_none());
+        match value {
+            Some(value) => known.push((name.to_string(), value & 0xffff_ffff)),
+            None => println!("cargo:warning=Skipping {}, could not evaluate {}", name, expr.trim()),
+        }
+    }
+
+    let mut out = format!("pub const NVENCAPI_VERSION: u32 = {:#x};\n", api_version);
+    for (name, value) in &known {
+        out.push_str(&format!("pub const {}: u32 = {:#x};\n", name, value));
+    }
+    out
+}
+
+/// Paths of the decode bindings and, with the nvenc feature, the encode
+/// bindings, and the SDK release they come from.
+struct Bindings {
+    nvcuvid: PathBuf,
+    nvenc: Option<PathBuf>,
+    version: (u32, u32),
+}
+
+#[cfg(feature = "bindgen")]
+fn generate(version: Option<(u32, u32)>) -> Bindings {
     let cuda_include = find_dir(
         "CUDA_INCLUDE_PATH",
         vec!["/opt/cuda/include", "/usr/local/cuda/include"],
@@ -28,24 +205,134 @@ fn main() {
         vec!["/opt/nvidia-video-codec/include", "/usr/local/nvidia-video-codec/include"],
     ).expect("Could not find Nvidia Video Codec SDK include path");
 
-    println!("cargo:rustc-link-lib=dylib=cuda");
-    println!("cargo:rustc-link-lib=dylib=nvcuvid");
+    let version = version
+        .or_else(|| header_version(&nvcodec_include))
+        .expect("Could not tell the Video Codec SDK version, set NVCODEC_SDK_VERSION");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let bundled_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("src/bindings");
+    let update = env::var_os("NVCODEC_UPDATE_BINDINGS").is_some();
 
+    // Types only, matching the files in `src/bindings`; functions are
+    // declared in `src/functions.rs`.
     let bindings = bindgen::Builder::default()
         .clang_arg(format!("-I{}", nvcodec_include.to_string_lossy()))
         .clang_arg(format!("-I{}", cuda_include.to_string_lossy()))
         .header(nvcodec_include.join("nvcuvid.h").to_string_lossy())
-        .blocklist_function("strtold")
-        .blocklist_function("qecvt")
-        .blocklist_function("qfcvt")
-        .blocklist_function("qgcvt")
-        .blocklist_function("qecvt_r")
-        .blocklist_function("qfcvt_r")
+        .allowlist_type("_?CUVID.*|CUvideo.*|cudaVideo.*|cuvidDecodeStatus.*|PFNVID.*")
+        .allowlist_type("CUDA_MEMCPY(2|3)D.*|CUmemorytype.*|CUevent_flags.*|cudaError_enum|CUresult|CUhostFn")
+        // Handles only the functions take, which nothing above pulls in.
+        .allowlist_type("CUdevice|CUdeviceptr|CUcontext|CUstream|CUevent|CUmodule|CUfunction")
+        .blocklist_type("CUVIDSOURCEPARAMS|CUvideosource|PFNVIDSOURCECALLBACK")
+        .layout_tests(false)
         .generate()
         .expect("Unable to generate bindings");
 
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let nvcuvid = out_dir.join("bindings.rs");
     bindings
-        .write_to_file(out_path.join("bindings.rs"))
+        .write_to_file(&nvcuvid)
         .expect("Couldn't write bindings!");
+    if update {
+        let bundled = bundled_dir.join(format!("sdk_{}_{}.rs", version.0, version.1));
+        fs::copy(&nvcuvid, bundled).expect("Couldn't update bundled bindings!");
+    }
+
+    let nvenc = env::var_os("CARGO_FEATURE_NVENC").map(|_| {
+        // The GUIDs are `static const` in the header and defined in
+        // `src/nvenc.rs` instead.
+        let bindings = bindgen::Builder::default()
+            .clang_arg(format!("-I{}", nvcodec_include.to_string_lossy()))
+            .header(nvcodec_include.join("nvEncodeAPI.h").to_string_lossy())
+            .allowlist_type("_?NV_?ENC.*|PNVENC.*")
+            .allowlist_var("NVENCAPI_(MAJOR|MINOR)_VERSION|NVENC_INFINITE_GOPLENGTH")
+            .layout_tests(false)
+            .generate()
+            .expect("Unable to generate NVENC bindings");
+
+        let path = out_dir.join("nvenc_bindings.rs");
+        let source = bindings.to_string() + &nvenc_versions(&nvcodec_include);
+        fs::write(&path, source).expect("Couldn't write NVENC bindings!");
+        if update {
+            let bundled = bundled_dir.join(format!("nvenc_{}_{}.rs", version.0, version.1));
+            fs::copy(&path, bundled).expect("Couldn't update bundled NVENC bindings!");
+        }
+        path
+    });
+
+    Bindings { nvcuvid, nvenc, version }
+}
+
+#[cfg(not(feature = "bindgen"))]
+fn generate(version: Option<(u32, u32)>) -> Bindings {
+    let with_nvenc = env::var_os("CARGO_FEATURE_NVENC").is_some();
+    let bundled: &[(u32, u32)] = if with_nvenc { &BUNDLED_NVENC_VERSIONS } else { &BUNDLED_VERSIONS };
+
+    let version = version.unwrap_or(bundled[bundled.len() - 1]);
+    if !bundled.contains(&version) {
+        panic!(
+            "No bundled {}bindings for Video Codec SDK {}.{}, enable the bindgen feature",
+            if with_nvenc { "NVENC " } else { "" },
+            version.0, version.1
+        );
+    }
+
+    let dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("src/bindings");
+    let nvcuvid = dir.join(format!("sdk_{}_{}.rs", version.0, version.1));
+    let nvenc = with_nvenc.then(|| dir.join(format!("nvenc_{}_{}.rs", version.0, version.1)));
+
+    Bindings { nvcuvid, nvenc, version }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/bindings");
+    println!("cargo:rerun-if-env-changed=NVCODEC_SDK_VERSION");
+    println!("cargo:rerun-if-env-changed=CUDA_INCLUDE_PATH");
+    println!("cargo:rerun-if-env-changed=NVIDIA_VIDEO_CODEC_INCLUDE_PATH");
+
+    if env::var_os("CARGO_FEATURE_DYNAMIC_LOADING").is_none() {
+        println!("cargo:rustc-link-lib=dylib=cuda");
+        println!("cargo:rustc-link-lib=dylib=nvcuvid");
+        if env::var_os("CARGO_FEATURE_NVENC").is_some() {
+            println!("cargo:rustc-link-lib=dylib=nvidia-encode");
+        }
+    }
+
+    let Bindings { nvcuvid, nvenc, version } = generate(requested_version());
+    println!("cargo:rustc-env=NVCODEC_SYS_BINDINGS={}", nvcuvid.display());
+    if let Some(nvenc) = nvenc {
+        println!("cargo:rustc-env=NVCODEC_SYS_NVENC_BINDINGS={}", nvenc.display());
+    }
+
+    // The C compiler's view of the NVENC types, written by
+    // `tools/nvenc_layout.c`, for `src/layout.rs` to check the bindings against.
+    let layout = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join(format!("src/bindings/nvenc_{}_{}.layout", version.0, version.1));
+    println!("cargo:rustc-check-cfg=cfg(nvcodec_nvenc_layout)");
+    if layout.exists() {
+        println!("cargo:rustc-cfg=nvcodec_nvenc_layout");
+        println!("cargo:rustc-env=NVCODEC_SYS_NVENC_LAYOUT={}", layout.display());
+    }
+
+    // Cumulative, so `cfg(nvcodec_sdk_12_0)` holds on every SDK since 12.0.
+    for (major, minor) in KNOWN_VERSIONS {
+        println!("cargo:rustc-check-cfg=cfg(nvcodec_sdk_{}_{})", major, minor);
+        if (major, minor) <= version {
+            println!("cargo:rustc-cfg=nvcodec_sdk_{}_{}", major, minor);
+        }
+    }
+    // Read by dependents' build scripts as `DEP_NVCUVID_SDK_VERSION` and
+    // `DEP_NVCUVID_KNOWN_VERSIONS`.
+    println!("cargo:sdk_version={}.{}", version.0, version.1);
+    let known: Vec<_> = KNOWN_VERSIONS
+        .iter()
+        .map(|(major, minor)| format!("{}.{}", major, minor))
+        .collect();
+    println!("cargo:known_versions={}", known.join(","));
+
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(
+        out_path.join("sdk_version.rs"),
+        format!("({}, {})", version.0, version.1),
+    ).expect("Couldn't write SDK version!");
 }
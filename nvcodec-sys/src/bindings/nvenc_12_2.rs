@@ -0,0 +1,2060 @@
+// NVIDIA Video Codec SDK 12.2 encode API (nvEncodeAPI.h), in bindgen's layout.
+// Functions are declared in `functions.rs` and the GUIDs in `nvenc.rs`.
+//
+// Only the items `nvcodec`'s encoder uses are written out. Members it does not
+// touch are folded into the reserved storage that follows them, unused entries
+// of the function list and pointers to unused structs are `void *`, and the
+// unions keep one reserved member the size and alignment of the largest.
+// Regenerate with `NVCODEC_UPDATE_BINDINGS=1 cargo build --features
+// bindgen,nvenc`, which writes out the whole header; `src/layout.rs` checks
+// the layouts either way.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct __BindgenBitfieldUnit<Storage> {
+    storage: Storage,
+}
+impl<Storage> __BindgenBitfieldUnit<Storage> {
+    #[inline]
+    pub const fn new(storage: Storage) -> Self {
+        Self { storage }
+    }
+}
+impl<Storage> __BindgenBitfieldUnit<Storage>
+where
+    Storage: AsRef<[u8]> + AsMut<[u8]>,
+{
+    #[inline]
+    pub fn get_bit(&self, index: usize) -> bool {
+        debug_assert!(index / 8 < self.storage.as_ref().len());
+        let byte_index = index / 8;
+        let byte = self.storage.as_ref()[byte_index];
+        let bit_index = if cfg!(target_endian = "big") {
+            7 - (index % 8)
+        } else {
+            index % 8
+        };
+        let mask = 1 << bit_index;
+        byte & mask == mask
+    }
+    #[inline]
+    pub fn set_bit(&mut self, index: usize, val: bool) {
+        debug_assert!(index / 8 < self.storage.as_ref().len());
+        let byte_index = index / 8;
+        let byte = &mut self.storage.as_mut()[byte_index];
+        let bit_index = if cfg!(target_endian = "big") {
+            7 - (index % 8)
+        } else {
+            index % 8
+        };
+        let mask = 1 << bit_index;
+        if val {
+            *byte |= mask;
+        } else {
+            *byte &= !mask;
+        }
+    }
+    #[inline]
+    pub fn get(&self, bit_offset: usize, bit_width: u8) -> u64 {
+        debug_assert!(bit_width <= 64);
+        debug_assert!(bit_offset / 8 < self.storage.as_ref().len());
+        debug_assert!((bit_offset + (bit_width as usize)) / 8 <= self.storage.as_ref().len());
+        let mut val = 0;
+        for i in 0..(bit_width as usize) {
+            if self.get_bit(i + bit_offset) {
+                let index = if cfg!(target_endian = "big") {
+                    bit_width as usize - 1 - i
+                } else {
+                    i
+                };
+                val |= 1 << index;
+            }
+        }
+        val
+    }
+    #[inline]
+    pub fn set(&mut self, bit_offset: usize, bit_width: u8, val: u64) {
+        debug_assert!(bit_width <= 64);
+        debug_assert!(bit_offset / 8 < self.storage.as_ref().len());
+        debug_assert!((bit_offset + (bit_width as usize)) / 8 <= self.storage.as_ref().len());
+        for i in 0..(bit_width as usize) {
+            let mask = 1 << i;
+            let val_bit_is_set = val & mask == mask;
+            let index = if cfg!(target_endian = "big") {
+                bit_width as usize - 1 - i
+            } else {
+                i
+            };
+            self.set_bit(index + bit_offset, val_bit_is_set);
+        }
+    }
+}
+pub const NVENCAPI_MAJOR_VERSION: u32 = 12;
+pub const NVENCAPI_MINOR_VERSION: u32 = 2;
+pub const NVENC_INFINITE_GOPLENGTH: u32 = 4294967295;
+pub const NVENCAPI_VERSION: u32 = 0x200000c;
+pub const NV_ENCODE_API_FUNCTION_LIST_VER: u32 = 0x7202000c;
+pub const NV_ENC_OPEN_ENCODE_SESSION_EX_PARAMS_VER: u32 = 0x7201000c;
+pub const NV_ENC_PRESET_CONFIG_VER: u32 = 0xf204000c;
+pub const NV_ENC_RC_PARAMS_VER: u32 = 0x7201000c;
+pub const NV_ENC_CONFIG_VER: u32 = 0xf208000c;
+pub const NV_ENC_INITIALIZE_PARAMS_VER: u32 = 0xf206000c;
+pub const NV_ENC_CREATE_BITSTREAM_BUFFER_VER: u32 = 0x7201000c;
+pub const NV_ENC_PIC_PARAMS_VER: u32 = 0xf206000c;
+pub const NV_ENC_LOCK_BITSTREAM_VER: u32 = 0xf202000c;
+pub const NV_ENC_SEQUENCE_PARAM_PAYLOAD_VER: u32 = 0x7201000c;
+pub const NV_ENC_MAP_INPUT_RESOURCE_VER: u32 = 0x7204000c;
+pub const NV_ENC_REGISTER_RESOURCE_VER: u32 = 0x7204000c;
+pub const _NVENCSTATUS_NV_ENC_SUCCESS: _NVENCSTATUS = 0;
+pub const _NVENCSTATUS_NV_ENC_ERR_NO_ENCODE_DEVICE: _NVENCSTATUS = 1;
+pub const _NVENCSTATUS_NV_ENC_ERR_UNSUPPORTED_DEVICE: _NVENCSTATUS = 2;
+pub const _NVENCSTATUS_NV_ENC_ERR_INVALID_ENCODERDEVICE: _NVENCSTATUS = 3;
+pub const _NVENCSTATUS_NV_ENC_ERR_INVALID_DEVICE: _NVENCSTATUS = 4;
+pub const _NVENCSTATUS_NV_ENC_ERR_DEVICE_NOT_EXIST: _NVENCSTATUS = 5;
+pub const _NVENCSTATUS_NV_ENC_ERR_INVALID_PTR: _NVENCSTATUS = 6;
+pub const _NVENCSTATUS_NV_ENC_ERR_INVALID_EVENT: _NVENCSTATUS = 7;
+pub const _NVENCSTATUS_NV_ENC_ERR_INVALID_PARAM: _NVENCSTATUS = 8;
+pub const _NVENCSTATUS_NV_ENC_ERR_INVALID_CALL: _NVENCSTATUS = 9;
+pub const _NVENCSTATUS_NV_ENC_ERR_OUT_OF_MEMORY: _NVENCSTATUS = 10;
+pub const _NVENCSTATUS_NV_ENC_ERR_ENCODER_NOT_INITIALIZED: _NVENCSTATUS = 11;
+pub const _NVENCSTATUS_NV_ENC_ERR_UNSUPPORTED_PARAM: _NVENCSTATUS = 12;
+pub const _NVENCSTATUS_NV_ENC_ERR_LOCK_BUSY: _NVENCSTATUS = 13;
+pub const _NVENCSTATUS_NV_ENC_ERR_NOT_ENOUGH_BUFFER: _NVENCSTATUS = 14;
+pub const _NVENCSTATUS_NV_ENC_ERR_INVALID_VERSION: _NVENCSTATUS = 15;
+pub const _NVENCSTATUS_NV_ENC_ERR_MAP_FAILED: _NVENCSTATUS = 16;
+pub const _NVENCSTATUS_NV_ENC_ERR_NEED_MORE_INPUT: _NVENCSTATUS = 17;
+pub const _NVENCSTATUS_NV_ENC_ERR_ENCODER_BUSY: _NVENCSTATUS = 18;
+pub const _NVENCSTATUS_NV_ENC_ERR_EVENT_NOT_REGISTERD: _NVENCSTATUS = 19;
+pub const _NVENCSTATUS_NV_ENC_ERR_GENERIC: _NVENCSTATUS = 20;
+pub const _NVENCSTATUS_NV_ENC_ERR_INCOMPATIBLE_CLIENT_KEY: _NVENCSTATUS = 21;
+pub const _NVENCSTATUS_NV_ENC_ERR_UNIMPLEMENTED: _NVENCSTATUS = 22;
+pub const _NVENCSTATUS_NV_ENC_ERR_RESOURCE_REGISTER_FAILED: _NVENCSTATUS = 23;
+pub const _NVENCSTATUS_NV_ENC_ERR_RESOURCE_NOT_REGISTERED: _NVENCSTATUS = 24;
+pub const _NVENCSTATUS_NV_ENC_ERR_RESOURCE_NOT_MAPPED: _NVENCSTATUS = 25;
+pub const _NVENCSTATUS_NV_ENC_ERR_NEED_MORE_OUTPUT: _NVENCSTATUS = 26;
+pub type _NVENCSTATUS = ::std::os::raw::c_uint;
+pub use self::_NVENCSTATUS as NVENCSTATUS;
+pub const _NV_ENC_PARAMS_FRAME_FIELD_MODE_NV_ENC_PARAMS_FRAME_FIELD_MODE_FRAME: _NV_ENC_PARAMS_FRAME_FIELD_MODE = 1;
+pub const _NV_ENC_PARAMS_FRAME_FIELD_MODE_NV_ENC_PARAMS_FRAME_FIELD_MODE_FIELD: _NV_ENC_PARAMS_FRAME_FIELD_MODE = 2;
+pub const _NV_ENC_PARAMS_FRAME_FIELD_MODE_NV_ENC_PARAMS_FRAME_FIELD_MODE_MBAFF: _NV_ENC_PARAMS_FRAME_FIELD_MODE = 3;
+pub type _NV_ENC_PARAMS_FRAME_FIELD_MODE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_PARAMS_FRAME_FIELD_MODE as NV_ENC_PARAMS_FRAME_FIELD_MODE;
+pub const _NV_ENC_MV_PRECISION_NV_ENC_MV_PRECISION_DEFAULT: _NV_ENC_MV_PRECISION = 0;
+pub const _NV_ENC_MV_PRECISION_NV_ENC_MV_PRECISION_FULL_PEL: _NV_ENC_MV_PRECISION = 1;
+pub const _NV_ENC_MV_PRECISION_NV_ENC_MV_PRECISION_HALF_PEL: _NV_ENC_MV_PRECISION = 2;
+pub const _NV_ENC_MV_PRECISION_NV_ENC_MV_PRECISION_QUARTER_PEL: _NV_ENC_MV_PRECISION = 3;
+pub type _NV_ENC_MV_PRECISION = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_MV_PRECISION as NV_ENC_MV_PRECISION;
+pub const _NV_ENC_PARAMS_RC_MODE_NV_ENC_PARAMS_RC_CONSTQP: _NV_ENC_PARAMS_RC_MODE = 0;
+pub const _NV_ENC_PARAMS_RC_MODE_NV_ENC_PARAMS_RC_VBR: _NV_ENC_PARAMS_RC_MODE = 1;
+pub const _NV_ENC_PARAMS_RC_MODE_NV_ENC_PARAMS_RC_CBR: _NV_ENC_PARAMS_RC_MODE = 2;
+pub type _NV_ENC_PARAMS_RC_MODE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_PARAMS_RC_MODE as NV_ENC_PARAMS_RC_MODE;
+pub const _NV_ENC_PIC_STRUCT_NV_ENC_PIC_STRUCT_FRAME: _NV_ENC_PIC_STRUCT = 1;
+pub const _NV_ENC_PIC_STRUCT_NV_ENC_PIC_STRUCT_FIELD_TOP_BOTTOM: _NV_ENC_PIC_STRUCT = 2;
+pub const _NV_ENC_PIC_STRUCT_NV_ENC_PIC_STRUCT_FIELD_BOTTOM_TOP: _NV_ENC_PIC_STRUCT = 3;
+pub type _NV_ENC_PIC_STRUCT = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_PIC_STRUCT as NV_ENC_PIC_STRUCT;
+pub const _NV_ENC_PIC_TYPE_NV_ENC_PIC_TYPE_P: _NV_ENC_PIC_TYPE = 0;
+pub const _NV_ENC_PIC_TYPE_NV_ENC_PIC_TYPE_B: _NV_ENC_PIC_TYPE = 1;
+pub const _NV_ENC_PIC_TYPE_NV_ENC_PIC_TYPE_I: _NV_ENC_PIC_TYPE = 2;
+pub const _NV_ENC_PIC_TYPE_NV_ENC_PIC_TYPE_IDR: _NV_ENC_PIC_TYPE = 3;
+pub const _NV_ENC_PIC_TYPE_NV_ENC_PIC_TYPE_BI: _NV_ENC_PIC_TYPE = 4;
+pub const _NV_ENC_PIC_TYPE_NV_ENC_PIC_TYPE_SKIPPED: _NV_ENC_PIC_TYPE = 5;
+pub const _NV_ENC_PIC_TYPE_NV_ENC_PIC_TYPE_INTRA_REFRESH: _NV_ENC_PIC_TYPE = 6;
+pub const _NV_ENC_PIC_TYPE_NV_ENC_PIC_TYPE_NONREF_P: _NV_ENC_PIC_TYPE = 7;
+pub const _NV_ENC_PIC_TYPE_NV_ENC_PIC_TYPE_UNKNOWN: _NV_ENC_PIC_TYPE = 255;
+pub type _NV_ENC_PIC_TYPE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_PIC_TYPE as NV_ENC_PIC_TYPE;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_UNDEFINED: _NV_ENC_BUFFER_FORMAT = 0;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_NV12: _NV_ENC_BUFFER_FORMAT = 1;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_YV12: _NV_ENC_BUFFER_FORMAT = 16;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_IYUV: _NV_ENC_BUFFER_FORMAT = 256;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_YUV444: _NV_ENC_BUFFER_FORMAT = 4096;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_YUV420_10BIT: _NV_ENC_BUFFER_FORMAT = 65536;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_YUV444_10BIT: _NV_ENC_BUFFER_FORMAT = 1048576;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_ARGB: _NV_ENC_BUFFER_FORMAT = 16777216;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_ARGB10: _NV_ENC_BUFFER_FORMAT = 33554432;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_AYUV: _NV_ENC_BUFFER_FORMAT = 67108864;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_ABGR: _NV_ENC_BUFFER_FORMAT = 268435456;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_ABGR10: _NV_ENC_BUFFER_FORMAT = 536870912;
+pub const _NV_ENC_BUFFER_FORMAT_NV_ENC_BUFFER_FORMAT_U8: _NV_ENC_BUFFER_FORMAT = 1073741824;
+pub type _NV_ENC_BUFFER_FORMAT = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_BUFFER_FORMAT as NV_ENC_BUFFER_FORMAT;
+pub const _NV_ENC_PIC_FLAGS_NV_ENC_PIC_FLAG_FORCEINTRA: _NV_ENC_PIC_FLAGS = 1;
+pub const _NV_ENC_PIC_FLAGS_NV_ENC_PIC_FLAG_FORCEIDR: _NV_ENC_PIC_FLAGS = 2;
+pub const _NV_ENC_PIC_FLAGS_NV_ENC_PIC_FLAG_OUTPUT_SPSPPS: _NV_ENC_PIC_FLAGS = 4;
+pub const _NV_ENC_PIC_FLAGS_NV_ENC_PIC_FLAG_EOS: _NV_ENC_PIC_FLAGS = 8;
+pub type _NV_ENC_PIC_FLAGS = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_PIC_FLAGS as NV_ENC_PIC_FLAGS;
+pub const _NV_ENC_MEMORY_HEAP_NV_ENC_MEMORY_HEAP_AUTOSELECT: _NV_ENC_MEMORY_HEAP = 0;
+pub const _NV_ENC_MEMORY_HEAP_NV_ENC_MEMORY_HEAP_VID: _NV_ENC_MEMORY_HEAP = 1;
+pub const _NV_ENC_MEMORY_HEAP_NV_ENC_MEMORY_HEAP_SYSMEM_CACHED: _NV_ENC_MEMORY_HEAP = 2;
+pub const _NV_ENC_MEMORY_HEAP_NV_ENC_MEMORY_HEAP_SYSMEM_UNCACHED: _NV_ENC_MEMORY_HEAP = 3;
+pub type _NV_ENC_MEMORY_HEAP = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_MEMORY_HEAP as NV_ENC_MEMORY_HEAP;
+pub const _NV_ENC_BFRAME_REF_MODE_NV_ENC_BFRAME_REF_MODE_DISABLED: _NV_ENC_BFRAME_REF_MODE = 0;
+pub const _NV_ENC_BFRAME_REF_MODE_NV_ENC_BFRAME_REF_MODE_EACH: _NV_ENC_BFRAME_REF_MODE = 1;
+pub const _NV_ENC_BFRAME_REF_MODE_NV_ENC_BFRAME_REF_MODE_MIDDLE: _NV_ENC_BFRAME_REF_MODE = 2;
+pub type _NV_ENC_BFRAME_REF_MODE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_BFRAME_REF_MODE as NV_ENC_BFRAME_REF_MODE;
+pub const _NV_ENC_H264_ADAPTIVE_TRANSFORM_MODE_NV_ENC_H264_ADAPTIVE_TRANSFORM_AUTOSELECT: _NV_ENC_H264_ADAPTIVE_TRANSFORM_MODE = 0;
+pub const _NV_ENC_H264_ADAPTIVE_TRANSFORM_MODE_NV_ENC_H264_ADAPTIVE_TRANSFORM_DISABLE: _NV_ENC_H264_ADAPTIVE_TRANSFORM_MODE = 1;
+pub const _NV_ENC_H264_ADAPTIVE_TRANSFORM_MODE_NV_ENC_H264_ADAPTIVE_TRANSFORM_ENABLE: _NV_ENC_H264_ADAPTIVE_TRANSFORM_MODE = 2;
+pub type _NV_ENC_H264_ADAPTIVE_TRANSFORM_MODE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_H264_ADAPTIVE_TRANSFORM_MODE as NV_ENC_H264_ADAPTIVE_TRANSFORM_MODE;
+pub const _NV_ENC_H264_FMO_MODE_NV_ENC_H264_FMO_AUTOSELECT: _NV_ENC_H264_FMO_MODE = 0;
+pub const _NV_ENC_H264_FMO_MODE_NV_ENC_H264_FMO_ENABLE: _NV_ENC_H264_FMO_MODE = 1;
+pub const _NV_ENC_H264_FMO_MODE_NV_ENC_H264_FMO_DISABLE: _NV_ENC_H264_FMO_MODE = 2;
+pub type _NV_ENC_H264_FMO_MODE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_H264_FMO_MODE as NV_ENC_H264_FMO_MODE;
+pub const _NV_ENC_H264_BDIRECT_MODE_NV_ENC_H264_BDIRECT_MODE_AUTOSELECT: _NV_ENC_H264_BDIRECT_MODE = 0;
+pub const _NV_ENC_H264_BDIRECT_MODE_NV_ENC_H264_BDIRECT_MODE_DISABLE: _NV_ENC_H264_BDIRECT_MODE = 1;
+pub const _NV_ENC_H264_BDIRECT_MODE_NV_ENC_H264_BDIRECT_MODE_TEMPORAL: _NV_ENC_H264_BDIRECT_MODE = 2;
+pub const _NV_ENC_H264_BDIRECT_MODE_NV_ENC_H264_BDIRECT_MODE_SPATIAL: _NV_ENC_H264_BDIRECT_MODE = 3;
+pub type _NV_ENC_H264_BDIRECT_MODE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_H264_BDIRECT_MODE as NV_ENC_H264_BDIRECT_MODE;
+pub const _NV_ENC_H264_ENTROPY_CODING_MODE_NV_ENC_H264_ENTROPY_CODING_MODE_AUTOSELECT: _NV_ENC_H264_ENTROPY_CODING_MODE = 0;
+pub const _NV_ENC_H264_ENTROPY_CODING_MODE_NV_ENC_H264_ENTROPY_CODING_MODE_CABAC: _NV_ENC_H264_ENTROPY_CODING_MODE = 1;
+pub const _NV_ENC_H264_ENTROPY_CODING_MODE_NV_ENC_H264_ENTROPY_CODING_MODE_CAVLC: _NV_ENC_H264_ENTROPY_CODING_MODE = 2;
+pub type _NV_ENC_H264_ENTROPY_CODING_MODE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_H264_ENTROPY_CODING_MODE as NV_ENC_H264_ENTROPY_CODING_MODE;
+pub const _NV_ENC_STEREO_PACKING_MODE_NV_ENC_STEREO_PACKING_MODE_NONE: _NV_ENC_STEREO_PACKING_MODE = 0;
+pub const _NV_ENC_STEREO_PACKING_MODE_NV_ENC_STEREO_PACKING_MODE_CHECKERBOARD: _NV_ENC_STEREO_PACKING_MODE = 1;
+pub const _NV_ENC_STEREO_PACKING_MODE_NV_ENC_STEREO_PACKING_MODE_COLINTERLEAVE: _NV_ENC_STEREO_PACKING_MODE = 2;
+pub const _NV_ENC_STEREO_PACKING_MODE_NV_ENC_STEREO_PACKING_MODE_ROWINTERLEAVE: _NV_ENC_STEREO_PACKING_MODE = 3;
+pub const _NV_ENC_STEREO_PACKING_MODE_NV_ENC_STEREO_PACKING_MODE_SIDEBYSIDE: _NV_ENC_STEREO_PACKING_MODE = 4;
+pub const _NV_ENC_STEREO_PACKING_MODE_NV_ENC_STEREO_PACKING_MODE_TOPBOTTOM: _NV_ENC_STEREO_PACKING_MODE = 5;
+pub const _NV_ENC_STEREO_PACKING_MODE_NV_ENC_STEREO_PACKING_MODE_FRAMESEQ: _NV_ENC_STEREO_PACKING_MODE = 6;
+pub type _NV_ENC_STEREO_PACKING_MODE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_STEREO_PACKING_MODE as NV_ENC_STEREO_PACKING_MODE;
+pub const _NV_ENC_HEVC_CUSIZE_NV_ENC_HEVC_CUSIZE_AUTOSELECT: _NV_ENC_HEVC_CUSIZE = 0;
+pub const _NV_ENC_HEVC_CUSIZE_NV_ENC_HEVC_CUSIZE_8x8: _NV_ENC_HEVC_CUSIZE = 1;
+pub const _NV_ENC_HEVC_CUSIZE_NV_ENC_HEVC_CUSIZE_16x16: _NV_ENC_HEVC_CUSIZE = 2;
+pub const _NV_ENC_HEVC_CUSIZE_NV_ENC_HEVC_CUSIZE_32x32: _NV_ENC_HEVC_CUSIZE = 3;
+pub const _NV_ENC_HEVC_CUSIZE_NV_ENC_HEVC_CUSIZE_64x64: _NV_ENC_HEVC_CUSIZE = 4;
+pub type _NV_ENC_HEVC_CUSIZE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_HEVC_CUSIZE as NV_ENC_HEVC_CUSIZE;
+pub const _NV_ENC_AV1_PART_SIZE_NV_ENC_AV1_PART_SIZE_AUTOSELECT: _NV_ENC_AV1_PART_SIZE = 0;
+pub const _NV_ENC_AV1_PART_SIZE_NV_ENC_AV1_PART_SIZE_4x4: _NV_ENC_AV1_PART_SIZE = 1;
+pub const _NV_ENC_AV1_PART_SIZE_NV_ENC_AV1_PART_SIZE_8x8: _NV_ENC_AV1_PART_SIZE = 2;
+pub const _NV_ENC_AV1_PART_SIZE_NV_ENC_AV1_PART_SIZE_16x16: _NV_ENC_AV1_PART_SIZE = 3;
+pub const _NV_ENC_AV1_PART_SIZE_NV_ENC_AV1_PART_SIZE_32x32: _NV_ENC_AV1_PART_SIZE = 4;
+pub const _NV_ENC_AV1_PART_SIZE_NV_ENC_AV1_PART_SIZE_64x64: _NV_ENC_AV1_PART_SIZE = 5;
+pub type _NV_ENC_AV1_PART_SIZE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_AV1_PART_SIZE as NV_ENC_AV1_PART_SIZE;
+pub const _NV_ENC_NUM_REF_FRAMES_NV_ENC_NUM_REF_FRAMES_AUTOSELECT: _NV_ENC_NUM_REF_FRAMES = 0;
+pub const _NV_ENC_NUM_REF_FRAMES_NV_ENC_NUM_REF_FRAMES_1: _NV_ENC_NUM_REF_FRAMES = 1;
+pub const _NV_ENC_NUM_REF_FRAMES_NV_ENC_NUM_REF_FRAMES_2: _NV_ENC_NUM_REF_FRAMES = 2;
+pub const _NV_ENC_NUM_REF_FRAMES_NV_ENC_NUM_REF_FRAMES_3: _NV_ENC_NUM_REF_FRAMES = 3;
+pub const _NV_ENC_NUM_REF_FRAMES_NV_ENC_NUM_REF_FRAMES_4: _NV_ENC_NUM_REF_FRAMES = 4;
+pub const _NV_ENC_NUM_REF_FRAMES_NV_ENC_NUM_REF_FRAMES_5: _NV_ENC_NUM_REF_FRAMES = 5;
+pub const _NV_ENC_NUM_REF_FRAMES_NV_ENC_NUM_REF_FRAMES_6: _NV_ENC_NUM_REF_FRAMES = 6;
+pub const _NV_ENC_NUM_REF_FRAMES_NV_ENC_NUM_REF_FRAMES_7: _NV_ENC_NUM_REF_FRAMES = 7;
+pub type _NV_ENC_NUM_REF_FRAMES = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_NUM_REF_FRAMES as NV_ENC_NUM_REF_FRAMES;
+pub const _NV_ENC_TEMPORAL_FILTER_LEVEL_NV_ENC_TEMPORAL_FILTER_LEVEL_0: _NV_ENC_TEMPORAL_FILTER_LEVEL = 0;
+pub const _NV_ENC_TEMPORAL_FILTER_LEVEL_NV_ENC_TEMPORAL_FILTER_LEVEL_4: _NV_ENC_TEMPORAL_FILTER_LEVEL = 4;
+pub type _NV_ENC_TEMPORAL_FILTER_LEVEL = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_TEMPORAL_FILTER_LEVEL as NV_ENC_TEMPORAL_FILTER_LEVEL;
+pub const _NV_ENC_BIT_DEPTH_NV_ENC_BIT_DEPTH_INVALID: _NV_ENC_BIT_DEPTH = 0;
+pub const _NV_ENC_BIT_DEPTH_NV_ENC_BIT_DEPTH_8: _NV_ENC_BIT_DEPTH = 8;
+pub const _NV_ENC_BIT_DEPTH_NV_ENC_BIT_DEPTH_10: _NV_ENC_BIT_DEPTH = 10;
+pub type _NV_ENC_BIT_DEPTH = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_BIT_DEPTH as NV_ENC_BIT_DEPTH;
+pub const _NV_ENC_DEVICE_TYPE_NV_ENC_DEVICE_TYPE_DIRECTX: _NV_ENC_DEVICE_TYPE = 0;
+pub const _NV_ENC_DEVICE_TYPE_NV_ENC_DEVICE_TYPE_CUDA: _NV_ENC_DEVICE_TYPE = 1;
+pub const _NV_ENC_DEVICE_TYPE_NV_ENC_DEVICE_TYPE_OPENGL: _NV_ENC_DEVICE_TYPE = 2;
+pub type _NV_ENC_DEVICE_TYPE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_DEVICE_TYPE as NV_ENC_DEVICE_TYPE;
+pub const _NV_ENC_INPUT_RESOURCE_TYPE_NV_ENC_INPUT_RESOURCE_TYPE_DIRECTX: _NV_ENC_INPUT_RESOURCE_TYPE = 0;
+pub const _NV_ENC_INPUT_RESOURCE_TYPE_NV_ENC_INPUT_RESOURCE_TYPE_CUDADEVICEPTR: _NV_ENC_INPUT_RESOURCE_TYPE = 1;
+pub const _NV_ENC_INPUT_RESOURCE_TYPE_NV_ENC_INPUT_RESOURCE_TYPE_CUDAARRAY: _NV_ENC_INPUT_RESOURCE_TYPE = 2;
+pub const _NV_ENC_INPUT_RESOURCE_TYPE_NV_ENC_INPUT_RESOURCE_TYPE_OPENGL_TEX: _NV_ENC_INPUT_RESOURCE_TYPE = 3;
+pub type _NV_ENC_INPUT_RESOURCE_TYPE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_INPUT_RESOURCE_TYPE as NV_ENC_INPUT_RESOURCE_TYPE;
+pub const _NV_ENC_BUFFER_USAGE_NV_ENC_INPUT_IMAGE: _NV_ENC_BUFFER_USAGE = 0;
+pub const _NV_ENC_BUFFER_USAGE_NV_ENC_OUTPUT_MOTION_VECTOR: _NV_ENC_BUFFER_USAGE = 1;
+pub const _NV_ENC_BUFFER_USAGE_NV_ENC_OUTPUT_BITSTREAM: _NV_ENC_BUFFER_USAGE = 2;
+pub type _NV_ENC_BUFFER_USAGE = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_BUFFER_USAGE as NV_ENC_BUFFER_USAGE;
+pub const _NV_ENC_TUNING_INFO_NV_ENC_TUNING_INFO_UNDEFINED: _NV_ENC_TUNING_INFO = 0;
+pub const _NV_ENC_TUNING_INFO_NV_ENC_TUNING_INFO_HIGH_QUALITY: _NV_ENC_TUNING_INFO = 1;
+pub const _NV_ENC_TUNING_INFO_NV_ENC_TUNING_INFO_LOW_LATENCY: _NV_ENC_TUNING_INFO = 2;
+pub const _NV_ENC_TUNING_INFO_NV_ENC_TUNING_INFO_ULTRA_LOW_LATENCY: _NV_ENC_TUNING_INFO = 3;
+pub const _NV_ENC_TUNING_INFO_NV_ENC_TUNING_INFO_LOSSLESS: _NV_ENC_TUNING_INFO = 4;
+pub type _NV_ENC_TUNING_INFO = ::std::os::raw::c_uint;
+pub use self::_NV_ENC_TUNING_INFO as NV_ENC_TUNING_INFO;
+pub type NV_ENC_INPUT_PTR = *mut ::std::os::raw::c_void;
+pub type NV_ENC_OUTPUT_PTR = *mut ::std::os::raw::c_void;
+pub type NV_ENC_REGISTERED_PTR = *mut ::std::os::raw::c_void;
+pub type NV_ENC_CUSTREAM_PTR = *mut ::std::os::raw::c_void;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _GUID {
+    pub Data1: u32,
+    pub Data2: u16,
+    pub Data3: u16,
+    pub Data4: [u8; 8usize],
+}
+pub type GUID = _GUID;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_QP {
+    pub qpInterP: u32,
+    pub qpInterB: u32,
+    pub qpIntra: u32,
+}
+pub type NV_ENC_QP = _NV_ENC_QP;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_RC_PARAMS {
+    pub version: u32,
+    pub rateControlMode: NV_ENC_PARAMS_RC_MODE,
+    pub constQP: NV_ENC_QP,
+    pub averageBitRate: u32,
+    pub maxBitRate: u32,
+    pub vbvBufferSize: u32,
+    pub vbvInitialDelay: u32,
+    pub _bitfield_align_1: [u32; 0],
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize]>,
+    pub minQP: NV_ENC_QP,
+    pub maxQP: NV_ENC_QP,
+    pub initialRCQP: NV_ENC_QP,
+    pub temporallayerIdxMask: u32,
+    pub temporalLayerQP: [u8; 8usize],
+    pub targetQuality: u8,
+    pub targetQualityLSB: u8,
+    pub lookaheadDepth: u16,
+    pub lowDelayKeyFrameScale: u8,
+    pub yDcQPIndexOffset: i8,
+    pub uDcQPIndexOffset: i8,
+    pub vDcQPIndexOffset: i8,
+    pub reserved: [u32; 8usize],
+}
+impl _NV_ENC_RC_PARAMS {
+    #[inline]
+    pub fn enableMinQP(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableMinQP(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableMaxQP(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(1usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableMaxQP(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(1usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableInitialRCQP(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(2usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableInitialRCQP(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(2usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableAQ(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(3usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableAQ(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(3usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn reservedBitField1(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(4usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_reservedBitField1(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(4usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableLookahead(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(5usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableLookahead(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(5usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn disableIadapt(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(6usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_disableIadapt(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(6usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn disableBadapt(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(7usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_disableBadapt(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(7usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableTemporalAQ(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(8usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableTemporalAQ(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(8usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn zeroReorderDelay(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(9usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_zeroReorderDelay(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(9usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableNonRefP(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(10usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableNonRefP(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(10usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn strictGOPTarget(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(11usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_strictGOPTarget(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(11usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn aqStrength(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(12usize, 4u8) as u32) }
+    }
+    #[inline]
+    pub fn set_aqStrength(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(12usize, 4u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn reservedBitFields(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(16usize, 16u8) as u32) }
+    }
+    #[inline]
+    pub fn set_reservedBitFields(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(16usize, 16u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(enableMinQP: u32, enableMaxQP: u32, enableInitialRCQP: u32, enableAQ: u32, reservedBitField1: u32, enableLookahead: u32, disableIadapt: u32, disableBadapt: u32, enableTemporalAQ: u32, zeroReorderDelay: u32, enableNonRefP: u32, strictGOPTarget: u32, aqStrength: u32, reservedBitFields: u32) -> __BindgenBitfieldUnit<[u8; 4usize]> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize]> = Default::default();
+        __bindgen_bitfield_unit.set(0usize, 1u8, {
+            let enableMinQP: u32 = unsafe { ::std::mem::transmute(enableMinQP) };
+            enableMinQP as u64
+        });
+        __bindgen_bitfield_unit.set(1usize, 1u8, {
+            let enableMaxQP: u32 = unsafe { ::std::mem::transmute(enableMaxQP) };
+            enableMaxQP as u64
+        });
+        __bindgen_bitfield_unit.set(2usize, 1u8, {
+            let enableInitialRCQP: u32 = unsafe { ::std::mem::transmute(enableInitialRCQP) };
+            enableInitialRCQP as u64
+        });
+        __bindgen_bitfield_unit.set(3usize, 1u8, {
+            let enableAQ: u32 = unsafe { ::std::mem::transmute(enableAQ) };
+            enableAQ as u64
+        });
+        __bindgen_bitfield_unit.set(4usize, 1u8, {
+            let reservedBitField1: u32 = unsafe { ::std::mem::transmute(reservedBitField1) };
+            reservedBitField1 as u64
+        });
+        __bindgen_bitfield_unit.set(5usize, 1u8, {
+            let enableLookahead: u32 = unsafe { ::std::mem::transmute(enableLookahead) };
+            enableLookahead as u64
+        });
+        __bindgen_bitfield_unit.set(6usize, 1u8, {
+            let disableIadapt: u32 = unsafe { ::std::mem::transmute(disableIadapt) };
+            disableIadapt as u64
+        });
+        __bindgen_bitfield_unit.set(7usize, 1u8, {
+            let disableBadapt: u32 = unsafe { ::std::mem::transmute(disableBadapt) };
+            disableBadapt as u64
+        });
+        __bindgen_bitfield_unit.set(8usize, 1u8, {
+            let enableTemporalAQ: u32 = unsafe { ::std::mem::transmute(enableTemporalAQ) };
+            enableTemporalAQ as u64
+        });
+        __bindgen_bitfield_unit.set(9usize, 1u8, {
+            let zeroReorderDelay: u32 = unsafe { ::std::mem::transmute(zeroReorderDelay) };
+            zeroReorderDelay as u64
+        });
+        __bindgen_bitfield_unit.set(10usize, 1u8, {
+            let enableNonRefP: u32 = unsafe { ::std::mem::transmute(enableNonRefP) };
+            enableNonRefP as u64
+        });
+        __bindgen_bitfield_unit.set(11usize, 1u8, {
+            let strictGOPTarget: u32 = unsafe { ::std::mem::transmute(strictGOPTarget) };
+            strictGOPTarget as u64
+        });
+        __bindgen_bitfield_unit.set(12usize, 4u8, {
+            let aqStrength: u32 = unsafe { ::std::mem::transmute(aqStrength) };
+            aqStrength as u64
+        });
+        __bindgen_bitfield_unit.set(16usize, 16u8, {
+            let reservedBitFields: u32 = unsafe { ::std::mem::transmute(reservedBitFields) };
+            reservedBitFields as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+pub type NV_ENC_RC_PARAMS = _NV_ENC_RC_PARAMS;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_CONFIG_H264_VUI_PARAMETERS {
+    pub overscanInfoPresentFlag: u32,
+    pub overscanInfo: u32,
+    pub videoSignalTypePresentFlag: u32,
+    pub videoFormat: u32,
+    pub videoFullRangeFlag: u32,
+    pub colourDescriptionPresentFlag: u32,
+    pub colourPrimaries: u32,
+    pub transferCharacteristics: u32,
+    pub colourMatrix: u32,
+    pub chromaSampleLocationFlag: u32,
+    pub chromaSampleLocationTop: u32,
+    pub chromaSampleLocationBot: u32,
+    pub bitstreamRestrictionFlag: u32,
+    pub reserved: [u32; 15usize],
+}
+pub type NV_ENC_CONFIG_H264_VUI_PARAMETERS = _NV_ENC_CONFIG_H264_VUI_PARAMETERS;
+pub type NV_ENC_CONFIG_HEVC_VUI_PARAMETERS = NV_ENC_CONFIG_H264_VUI_PARAMETERS;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_CONFIG_H264 {
+    pub _bitfield_align_1: [u32; 0],
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize]>,
+    pub level: u32,
+    pub idrPeriod: u32,
+    pub separateColourPlaneFlag: u32,
+    pub disableDeblockingFilterIDC: u32,
+    pub numTemporalLayers: u32,
+    pub spsId: u32,
+    pub ppsId: u32,
+    pub adaptiveTransformMode: NV_ENC_H264_ADAPTIVE_TRANSFORM_MODE,
+    pub fmoMode: NV_ENC_H264_FMO_MODE,
+    pub bdirectMode: NV_ENC_H264_BDIRECT_MODE,
+    pub entropyCodingMode: NV_ENC_H264_ENTROPY_CODING_MODE,
+    pub stereoMode: NV_ENC_STEREO_PACKING_MODE,
+    pub intraRefreshPeriod: u32,
+    pub intraRefreshCnt: u32,
+    pub maxNumRefFrames: u32,
+    pub sliceMode: u32,
+    pub sliceModeData: u32,
+    pub h264VUIParameters: NV_ENC_CONFIG_H264_VUI_PARAMETERS,
+    pub ltrNumFrames: u32,
+    pub ltrTrustMode: u32,
+    pub chromaFormatIDC: u32,
+    pub maxTemporalLayers: u32,
+    pub useBFramesAsRef: NV_ENC_BFRAME_REF_MODE,
+    pub numRefL0: NV_ENC_NUM_REF_FRAMES,
+    pub numRefL1: NV_ENC_NUM_REF_FRAMES,
+    pub reserved1: [u32; 267usize],
+    pub reserved2: [*mut ::std::os::raw::c_void; 64usize],
+}
+impl _NV_ENC_CONFIG_H264 {
+    #[inline]
+    pub fn reserved(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_reserved(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableStereoMVC(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(1usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableStereoMVC(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(1usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn hierarchicalPFrames(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(2usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_hierarchicalPFrames(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(2usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn hierarchicalBFrames(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(3usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_hierarchicalBFrames(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(3usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn outputBufferingPeriodSEI(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(4usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_outputBufferingPeriodSEI(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(4usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn outputPictureTimingSEI(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(5usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_outputPictureTimingSEI(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(5usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn outputAUD(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(6usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_outputAUD(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(6usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn disableSPSPPS(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(7usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_disableSPSPPS(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(7usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn outputFramePackingSEI(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(8usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_outputFramePackingSEI(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(8usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn outputRecoveryPointSEI(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(9usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_outputRecoveryPointSEI(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(9usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableIntraRefresh(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(10usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableIntraRefresh(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(10usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableConstrainedEncoding(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(11usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableConstrainedEncoding(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(11usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn repeatSPSPPS(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(12usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_repeatSPSPPS(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(12usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableVFR(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(13usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableVFR(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(13usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableLTR(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(14usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableLTR(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(14usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn qpPrimeYZeroTransformBypassFlag(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(15usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_qpPrimeYZeroTransformBypassFlag(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(15usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn useConstrainedIntraPred(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(16usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_useConstrainedIntraPred(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(16usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableFillerDataInsertion(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(17usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableFillerDataInsertion(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(17usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn disableSVCPrefixNalu(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(18usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_disableSVCPrefixNalu(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(18usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableScalabilityInfoSEI(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(19usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableScalabilityInfoSEI(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(19usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn singleSliceIntraRefresh(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(20usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_singleSliceIntraRefresh(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(20usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableTimeCode(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(21usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableTimeCode(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(21usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn reservedBitFields(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(22usize, 10u8) as u32) }
+    }
+    #[inline]
+    pub fn set_reservedBitFields(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(22usize, 10u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(reserved: u32, enableStereoMVC: u32, hierarchicalPFrames: u32, hierarchicalBFrames: u32, outputBufferingPeriodSEI: u32, outputPictureTimingSEI: u32, outputAUD: u32, disableSPSPPS: u32, outputFramePackingSEI: u32, outputRecoveryPointSEI: u32, enableIntraRefresh: u32, enableConstrainedEncoding: u32, repeatSPSPPS: u32, enableVFR: u32, enableLTR: u32, qpPrimeYZeroTransformBypassFlag: u32, useConstrainedIntraPred: u32, enableFillerDataInsertion: u32, disableSVCPrefixNalu: u32, enableScalabilityInfoSEI: u32, singleSliceIntraRefresh: u32, enableTimeCode: u32, reservedBitFields: u32) -> __BindgenBitfieldUnit<[u8; 4usize]> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize]> = Default::default();
+        __bindgen_bitfield_unit.set(0usize, 1u8, {
+            let reserved: u32 = unsafe { ::std::mem::transmute(reserved) };
+            reserved as u64
+        });
+        __bindgen_bitfield_unit.set(1usize, 1u8, {
+            let enableStereoMVC: u32 = unsafe { ::std::mem::transmute(enableStereoMVC) };
+            enableStereoMVC as u64
+        });
+        __bindgen_bitfield_unit.set(2usize, 1u8, {
+            let hierarchicalPFrames: u32 = unsafe { ::std::mem::transmute(hierarchicalPFrames) };
+            hierarchicalPFrames as u64
+        });
+        __bindgen_bitfield_unit.set(3usize, 1u8, {
+            let hierarchicalBFrames: u32 = unsafe { ::std::mem::transmute(hierarchicalBFrames) };
+            hierarchicalBFrames as u64
+        });
+        __bindgen_bitfield_unit.set(4usize, 1u8, {
+            let outputBufferingPeriodSEI: u32 = unsafe { ::std::mem::transmute(outputBufferingPeriodSEI) };
+            outputBufferingPeriodSEI as u64
+        });
+        __bindgen_bitfield_unit.set(5usize, 1u8, {
+            let outputPictureTimingSEI: u32 = unsafe { ::std::mem::transmute(outputPictureTimingSEI) };
+            outputPictureTimingSEI as u64
+        });
+        __bindgen_bitfield_unit.set(6usize, 1u8, {
+            let outputAUD: u32 = unsafe { ::std::mem::transmute(outputAUD) };
+            outputAUD as u64
+        });
+        __bindgen_bitfield_unit.set(7usize, 1u8, {
+            let disableSPSPPS: u32 = unsafe { ::std::mem::transmute(disableSPSPPS) };
+            disableSPSPPS as u64
+        });
+        __bindgen_bitfield_unit.set(8usize, 1u8, {
+            let outputFramePackingSEI: u32 = unsafe { ::std::mem::transmute(outputFramePackingSEI) };
+            outputFramePackingSEI as u64
+        });
+        __bindgen_bitfield_unit.set(9usize, 1u8, {
+            let outputRecoveryPointSEI: u32 = unsafe { ::std::mem::transmute(outputRecoveryPointSEI) };
+            outputRecoveryPointSEI as u64
+        });
+        __bindgen_bitfield_unit.set(10usize, 1u8, {
+            let enableIntraRefresh: u32 = unsafe { ::std::mem::transmute(enableIntraRefresh) };
+            enableIntraRefresh as u64
+        });
+        __bindgen_bitfield_unit.set(11usize, 1u8, {
+            let enableConstrainedEncoding: u32 = unsafe { ::std::mem::transmute(enableConstrainedEncoding) };
+            enableConstrainedEncoding as u64
+        });
+        __bindgen_bitfield_unit.set(12usize, 1u8, {
+            let repeatSPSPPS: u32 = unsafe { ::std::mem::transmute(repeatSPSPPS) };
+            repeatSPSPPS as u64
+        });
+        __bindgen_bitfield_unit.set(13usize, 1u8, {
+            let enableVFR: u32 = unsafe { ::std::mem::transmute(enableVFR) };
+            enableVFR as u64
+        });
+        __bindgen_bitfield_unit.set(14usize, 1u8, {
+            let enableLTR: u32 = unsafe { ::std::mem::transmute(enableLTR) };
+            enableLTR as u64
+        });
+        __bindgen_bitfield_unit.set(15usize, 1u8, {
+            let qpPrimeYZeroTransformBypassFlag: u32 = unsafe { ::std::mem::transmute(qpPrimeYZeroTransformBypassFlag) };
+            qpPrimeYZeroTransformBypassFlag as u64
+        });
+        __bindgen_bitfield_unit.set(16usize, 1u8, {
+            let useConstrainedIntraPred: u32 = unsafe { ::std::mem::transmute(useConstrainedIntraPred) };
+            useConstrainedIntraPred as u64
+        });
+        __bindgen_bitfield_unit.set(17usize, 1u8, {
+            let enableFillerDataInsertion: u32 = unsafe { ::std::mem::transmute(enableFillerDataInsertion) };
+            enableFillerDataInsertion as u64
+        });
+        __bindgen_bitfield_unit.set(18usize, 1u8, {
+            let disableSVCPrefixNalu: u32 = unsafe { ::std::mem::transmute(disableSVCPrefixNalu) };
+            disableSVCPrefixNalu as u64
+        });
+        __bindgen_bitfield_unit.set(19usize, 1u8, {
+            let enableScalabilityInfoSEI: u32 = unsafe { ::std::mem::transmute(enableScalabilityInfoSEI) };
+            enableScalabilityInfoSEI as u64
+        });
+        __bindgen_bitfield_unit.set(20usize, 1u8, {
+            let singleSliceIntraRefresh: u32 = unsafe { ::std::mem::transmute(singleSliceIntraRefresh) };
+            singleSliceIntraRefresh as u64
+        });
+        __bindgen_bitfield_unit.set(21usize, 1u8, {
+            let enableTimeCode: u32 = unsafe { ::std::mem::transmute(enableTimeCode) };
+            enableTimeCode as u64
+        });
+        __bindgen_bitfield_unit.set(22usize, 10u8, {
+            let reservedBitFields: u32 = unsafe { ::std::mem::transmute(reservedBitFields) };
+            reservedBitFields as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+pub type NV_ENC_CONFIG_H264 = _NV_ENC_CONFIG_H264;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_CONFIG_HEVC {
+    pub level: u32,
+    pub tier: u32,
+    pub minCUSize: NV_ENC_HEVC_CUSIZE,
+    pub maxCUSize: NV_ENC_HEVC_CUSIZE,
+    pub _bitfield_align_1: [u32; 0],
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize]>,
+    pub idrPeriod: u32,
+    pub intraRefreshPeriod: u32,
+    pub intraRefreshCnt: u32,
+    pub maxNumRefFramesInDPB: u32,
+    pub ltrNumFrames: u32,
+    pub vpsId: u32,
+    pub spsId: u32,
+    pub ppsId: u32,
+    pub sliceMode: u32,
+    pub sliceModeData: u32,
+    pub maxTemporalLayersMinus1: u32,
+    pub hevcVUIParameters: NV_ENC_CONFIG_HEVC_VUI_PARAMETERS,
+    pub ltrTrustMode: u32,
+    pub useBFramesAsRef: NV_ENC_BFRAME_REF_MODE,
+    pub numRefL0: NV_ENC_NUM_REF_FRAMES,
+    pub numRefL1: NV_ENC_NUM_REF_FRAMES,
+    pub tfLevel: NV_ENC_TEMPORAL_FILTER_LEVEL,
+    pub disableDeblockingFilterIDC: u32,
+    pub outputBitDepth: NV_ENC_BIT_DEPTH,
+    pub inputBitDepth: NV_ENC_BIT_DEPTH,
+    pub reserved1: [u32; 210usize],
+    pub reserved2: [*mut ::std::os::raw::c_void; 64usize],
+}
+impl _NV_ENC_CONFIG_HEVC {
+    #[inline]
+    pub fn useConstrainedIntraPred(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_useConstrainedIntraPred(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn disableDeblockAcrossSliceBoundary(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(1usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_disableDeblockAcrossSliceBoundary(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(1usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn outputBufferingPeriodSEI(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(2usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_outputBufferingPeriodSEI(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(2usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn outputPictureTimingSEI(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(3usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_outputPictureTimingSEI(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(3usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn outputAUD(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(4usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_outputAUD(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(4usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableLTR(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(5usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableLTR(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(5usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn disableSPSPPS(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(6usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_disableSPSPPS(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(6usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn repeatSPSPPS(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(7usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_repeatSPSPPS(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(7usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableIntraRefresh(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(8usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableIntraRefresh(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(8usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn chromaFormatIDC(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(9usize, 2u8) as u32) }
+    }
+    #[inline]
+    pub fn set_chromaFormatIDC(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(9usize, 2u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn reserved3(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(11usize, 3u8) as u32) }
+    }
+    #[inline]
+    pub fn set_reserved3(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(11usize, 3u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableFillerDataInsertion(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(14usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableFillerDataInsertion(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(14usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableConstrainedEncoding(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(15usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableConstrainedEncoding(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(15usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableAlphaLayerEncoding(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(16usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableAlphaLayerEncoding(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(16usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn singleSliceIntraRefresh(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(17usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_singleSliceIntraRefresh(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(17usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn outputRecoveryPointSEI(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(18usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_outputRecoveryPointSEI(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(18usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn outputTimeCodeSEI(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(19usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_outputTimeCodeSEI(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(19usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn reserved(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(20usize, 12u8) as u32) }
+    }
+    #[inline]
+    pub fn set_reserved(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(20usize, 12u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(useConstrainedIntraPred: u32, disableDeblockAcrossSliceBoundary: u32, outputBufferingPeriodSEI: u32, outputPictureTimingSEI: u32, outputAUD: u32, enableLTR: u32, disableSPSPPS: u32, repeatSPSPPS: u32, enableIntraRefresh: u32, chromaFormatIDC: u32, reserved3: u32, enableFillerDataInsertion: u32, enableConstrainedEncoding: u32, enableAlphaLayerEncoding: u32, singleSliceIntraRefresh: u32, outputRecoveryPointSEI: u32, outputTimeCodeSEI: u32, reserved: u32) -> __BindgenBitfieldUnit<[u8; 4usize]> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize]> = Default::default();
+        __bindgen_bitfield_unit.set(0usize, 1u8, {
+            let useConstrainedIntraPred: u32 = unsafe { ::std::mem::transmute(useConstrainedIntraPred) };
+            useConstrainedIntraPred as u64
+        });
+        __bindgen_bitfield_unit.set(1usize, 1u8, {
+            let disableDeblockAcrossSliceBoundary: u32 = unsafe { ::std::mem::transmute(disableDeblockAcrossSliceBoundary) };
+            disableDeblockAcrossSliceBoundary as u64
+        });
+        __bindgen_bitfield_unit.set(2usize, 1u8, {
+            let outputBufferingPeriodSEI: u32 = unsafe { ::std::mem::transmute(outputBufferingPeriodSEI) };
+            outputBufferingPeriodSEI as u64
+        });
+        __bindgen_bitfield_unit.set(3usize, 1u8, {
+            let outputPictureTimingSEI: u32 = unsafe { ::std::mem::transmute(outputPictureTimingSEI) };
+            outputPictureTimingSEI as u64
+        });
+        __bindgen_bitfield_unit.set(4usize, 1u8, {
+            let outputAUD: u32 = unsafe { ::std::mem::transmute(outputAUD) };
+            outputAUD as u64
+        });
+        __bindgen_bitfield_unit.set(5usize, 1u8, {
+            let enableLTR: u32 = unsafe { ::std::mem::transmute(enableLTR) };
+            enableLTR as u64
+        });
+        __bindgen_bitfield_unit.set(6usize, 1u8, {
+            let disableSPSPPS: u32 = unsafe { ::std::mem::transmute(disableSPSPPS) };
+            disableSPSPPS as u64
+        });
+        __bindgen_bitfield_unit.set(7usize, 1u8, {
+            let repeatSPSPPS: u32 = unsafe { ::std::mem::transmute(repeatSPSPPS) };
+            repeatSPSPPS as u64
+        });
+        __bindgen_bitfield_unit.set(8usize, 1u8, {
+            let enableIntraRefresh: u32 = unsafe { ::std::mem::transmute(enableIntraRefresh) };
+            enableIntraRefresh as u64
+        });
+        __bindgen_bitfield_unit.set(9usize, 2u8, {
+            let chromaFormatIDC: u32 = unsafe { ::std::mem::transmute(chromaFormatIDC) };
+            chromaFormatIDC as u64
+        });
+        __bindgen_bitfield_unit.set(11usize, 3u8, {
+            let reserved3: u32 = unsafe { ::std::mem::transmute(reserved3) };
+            reserved3 as u64
+        });
+        __bindgen_bitfield_unit.set(14usize, 1u8, {
+            let enableFillerDataInsertion: u32 = unsafe { ::std::mem::transmute(enableFillerDataInsertion) };
+            enableFillerDataInsertion as u64
+        });
+        __bindgen_bitfield_unit.set(15usize, 1u8, {
+            let enableConstrainedEncoding: u32 = unsafe { ::std::mem::transmute(enableConstrainedEncoding) };
+            enableConstrainedEncoding as u64
+        });
+        __bindgen_bitfield_unit.set(16usize, 1u8, {
+            let enableAlphaLayerEncoding: u32 = unsafe { ::std::mem::transmute(enableAlphaLayerEncoding) };
+            enableAlphaLayerEncoding as u64
+        });
+        __bindgen_bitfield_unit.set(17usize, 1u8, {
+            let singleSliceIntraRefresh: u32 = unsafe { ::std::mem::transmute(singleSliceIntraRefresh) };
+            singleSliceIntraRefresh as u64
+        });
+        __bindgen_bitfield_unit.set(18usize, 1u8, {
+            let outputRecoveryPointSEI: u32 = unsafe { ::std::mem::transmute(outputRecoveryPointSEI) };
+            outputRecoveryPointSEI as u64
+        });
+        __bindgen_bitfield_unit.set(19usize, 1u8, {
+            let outputTimeCodeSEI: u32 = unsafe { ::std::mem::transmute(outputTimeCodeSEI) };
+            outputTimeCodeSEI as u64
+        });
+        __bindgen_bitfield_unit.set(20usize, 12u8, {
+            let reserved: u32 = unsafe { ::std::mem::transmute(reserved) };
+            reserved as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+pub type NV_ENC_CONFIG_HEVC = _NV_ENC_CONFIG_HEVC;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_CONFIG_AV1 {
+    pub level: u32,
+    pub tier: u32,
+    pub minPartSize: NV_ENC_AV1_PART_SIZE,
+    pub maxPartSize: NV_ENC_AV1_PART_SIZE,
+    pub _bitfield_align_1: [u32; 0],
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize]>,
+    pub idrPeriod: u32,
+    pub intraRefreshPeriod: u32,
+    pub intraRefreshCnt: u32,
+    pub maxNumRefFramesInDPB: u32,
+    pub numTileColumns: u32,
+    pub numTileRows: u32,
+    pub reserved2: u32,
+    pub tileWidths: *mut u32,
+    pub tileHeights: *mut u32,
+    pub maxTemporalLayersMinus1: u32,
+    pub colorPrimaries: u32,
+    pub transferCharacteristics: u32,
+    pub matrixCoefficients: u32,
+    pub colorRange: u32,
+    pub chromaSamplePosition: u32,
+    pub useBFramesAsRef: NV_ENC_BFRAME_REF_MODE,
+    pub filmGrainParams: *mut ::std::os::raw::c_void,
+    pub numFwdRefs: NV_ENC_NUM_REF_FRAMES,
+    pub numBwdRefs: NV_ENC_NUM_REF_FRAMES,
+    pub outputBitDepth: NV_ENC_BIT_DEPTH,
+    pub inputBitDepth: NV_ENC_BIT_DEPTH,
+    pub reserved1: [u32; 233usize],
+    pub reserved3: [*mut ::std::os::raw::c_void; 62usize],
+}
+impl _NV_ENC_CONFIG_AV1 {
+    #[inline]
+    pub fn outputAnnexBFormat(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_outputAnnexBFormat(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableTimingInfo(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(1usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableTimingInfo(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(1usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableDecoderModelInfo(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(2usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableDecoderModelInfo(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(2usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableFrameIdNumbers(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(3usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableFrameIdNumbers(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(3usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn disableSeqHdr(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(4usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_disableSeqHdr(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(4usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn repeatSeqHdr(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(5usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_repeatSeqHdr(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(5usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableIntraRefresh(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(6usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableIntraRefresh(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(6usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn chromaFormatIDC(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(7usize, 2u8) as u32) }
+    }
+    #[inline]
+    pub fn set_chromaFormatIDC(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(7usize, 2u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableBitstreamPadding(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(9usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableBitstreamPadding(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(9usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableCustomTileConfig(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(10usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableCustomTileConfig(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(10usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableFilmGrainParams(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(11usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableFilmGrainParams(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(11usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn reserved4(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(12usize, 3u8) as u32) }
+    }
+    #[inline]
+    pub fn set_reserved4(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(12usize, 3u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn reserved(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(15usize, 17u8) as u32) }
+    }
+    #[inline]
+    pub fn set_reserved(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(15usize, 17u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(outputAnnexBFormat: u32, enableTimingInfo: u32, enableDecoderModelInfo: u32, enableFrameIdNumbers: u32, disableSeqHdr: u32, repeatSeqHdr: u32, enableIntraRefresh: u32, chromaFormatIDC: u32, enableBitstreamPadding: u32, enableCustomTileConfig: u32, enableFilmGrainParams: u32, reserved4: u32, reserved: u32) -> __BindgenBitfieldUnit<[u8; 4usize]> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize]> = Default::default();
+        __bindgen_bitfield_unit.set(0usize, 1u8, {
+            let outputAnnexBFormat: u32 = unsafe { ::std::mem::transmute(outputAnnexBFormat) };
+            outputAnnexBFormat as u64
+        });
+        __bindgen_bitfield_unit.set(1usize, 1u8, {
+            let enableTimingInfo: u32 = unsafe { ::std::mem::transmute(enableTimingInfo) };
+            enableTimingInfo as u64
+        });
+        __bindgen_bitfield_unit.set(2usize, 1u8, {
+            let enableDecoderModelInfo: u32 = unsafe { ::std::mem::transmute(enableDecoderModelInfo) };
+            enableDecoderModelInfo as u64
+        });
+        __bindgen_bitfield_unit.set(3usize, 1u8, {
+            let enableFrameIdNumbers: u32 = unsafe { ::std::mem::transmute(enableFrameIdNumbers) };
+            enableFrameIdNumbers as u64
+        });
+        __bindgen_bitfield_unit.set(4usize, 1u8, {
+            let disableSeqHdr: u32 = unsafe { ::std::mem::transmute(disableSeqHdr) };
+            disableSeqHdr as u64
+        });
+        __bindgen_bitfield_unit.set(5usize, 1u8, {
+            let repeatSeqHdr: u32 = unsafe { ::std::mem::transmute(repeatSeqHdr) };
+            repeatSeqHdr as u64
+        });
+        __bindgen_bitfield_unit.set(6usize, 1u8, {
+            let enableIntraRefresh: u32 = unsafe { ::std::mem::transmute(enableIntraRefresh) };
+            enableIntraRefresh as u64
+        });
+        __bindgen_bitfield_unit.set(7usize, 2u8, {
+            let chromaFormatIDC: u32 = unsafe { ::std::mem::transmute(chromaFormatIDC) };
+            chromaFormatIDC as u64
+        });
+        __bindgen_bitfield_unit.set(9usize, 1u8, {
+            let enableBitstreamPadding: u32 = unsafe { ::std::mem::transmute(enableBitstreamPadding) };
+            enableBitstreamPadding as u64
+        });
+        __bindgen_bitfield_unit.set(10usize, 1u8, {
+            let enableCustomTileConfig: u32 = unsafe { ::std::mem::transmute(enableCustomTileConfig) };
+            enableCustomTileConfig as u64
+        });
+        __bindgen_bitfield_unit.set(11usize, 1u8, {
+            let enableFilmGrainParams: u32 = unsafe { ::std::mem::transmute(enableFilmGrainParams) };
+            enableFilmGrainParams as u64
+        });
+        __bindgen_bitfield_unit.set(12usize, 3u8, {
+            let reserved4: u32 = unsafe { ::std::mem::transmute(reserved4) };
+            reserved4 as u64
+        });
+        __bindgen_bitfield_unit.set(15usize, 17u8, {
+            let reserved: u32 = unsafe { ::std::mem::transmute(reserved) };
+            reserved as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+pub type NV_ENC_CONFIG_AV1 = _NV_ENC_CONFIG_AV1;
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union _NV_ENC_CODEC_CONFIG {
+    pub h264Config: NV_ENC_CONFIG_H264,
+    pub hevcConfig: NV_ENC_CONFIG_HEVC,
+    pub av1Config: NV_ENC_CONFIG_AV1,
+    pub reserved: [u32; 320usize],
+}
+pub type NV_ENC_CODEC_CONFIG = _NV_ENC_CODEC_CONFIG;
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _NV_ENC_CONFIG {
+    pub version: u32,
+    pub profileGUID: GUID,
+    pub gopLength: u32,
+    pub frameIntervalP: i32,
+    pub monoChromeEncoding: u32,
+    pub frameFieldMode: NV_ENC_PARAMS_FRAME_FIELD_MODE,
+    pub mvPrecision: NV_ENC_MV_PRECISION,
+    pub rcParams: NV_ENC_RC_PARAMS,
+    pub encodeCodecConfig: NV_ENC_CODEC_CONFIG,
+    pub reserved: [u32; 278usize],
+    pub reserved2: [*mut ::std::os::raw::c_void; 64usize],
+}
+pub type NV_ENC_CONFIG = _NV_ENC_CONFIG;
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _NV_ENC_PRESET_CONFIG {
+    pub version: u32,
+    pub reserved: u32,
+    pub presetCfg: NV_ENC_CONFIG,
+    pub reserved1: [u32; 256usize],
+    pub reserved2: [*mut ::std::os::raw::c_void; 64usize],
+}
+pub type NV_ENC_PRESET_CONFIG = _NV_ENC_PRESET_CONFIG;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NVENC_EXTERNAL_ME_HINT_COUNTS_PER_BLOCKTYPE {
+    pub _bitfield_align_1: [u32; 0],
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize]>,
+    pub reserved1: [u32; 3usize],
+}
+impl _NVENC_EXTERNAL_ME_HINT_COUNTS_PER_BLOCKTYPE {
+    #[inline]
+    pub fn numCandsPerBlk16x16(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 4u8) as u32) }
+    }
+    #[inline]
+    pub fn set_numCandsPerBlk16x16(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 4u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn numCandsPerBlk16x8(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(4usize, 4u8) as u32) }
+    }
+    #[inline]
+    pub fn set_numCandsPerBlk16x8(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(4usize, 4u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn numCandsPerBlk8x16(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(8usize, 4u8) as u32) }
+    }
+    #[inline]
+    pub fn set_numCandsPerBlk8x16(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(8usize, 4u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn numCandsPerBlk8x8(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(12usize, 4u8) as u32) }
+    }
+    #[inline]
+    pub fn set_numCandsPerBlk8x8(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(12usize, 4u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn reserved(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(16usize, 16u8) as u32) }
+    }
+    #[inline]
+    pub fn set_reserved(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(16usize, 16u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(numCandsPerBlk16x16: u32, numCandsPerBlk16x8: u32, numCandsPerBlk8x16: u32, numCandsPerBlk8x8: u32, reserved: u32) -> __BindgenBitfieldUnit<[u8; 4usize]> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize]> = Default::default();
+        __bindgen_bitfield_unit.set(0usize, 4u8, {
+            let numCandsPerBlk16x16: u32 = unsafe { ::std::mem::transmute(numCandsPerBlk16x16) };
+            numCandsPerBlk16x16 as u64
+        });
+        __bindgen_bitfield_unit.set(4usize, 4u8, {
+            let numCandsPerBlk16x8: u32 = unsafe { ::std::mem::transmute(numCandsPerBlk16x8) };
+            numCandsPerBlk16x8 as u64
+        });
+        __bindgen_bitfield_unit.set(8usize, 4u8, {
+            let numCandsPerBlk8x16: u32 = unsafe { ::std::mem::transmute(numCandsPerBlk8x16) };
+            numCandsPerBlk8x16 as u64
+        });
+        __bindgen_bitfield_unit.set(12usize, 4u8, {
+            let numCandsPerBlk8x8: u32 = unsafe { ::std::mem::transmute(numCandsPerBlk8x8) };
+            numCandsPerBlk8x8 as u64
+        });
+        __bindgen_bitfield_unit.set(16usize, 16u8, {
+            let reserved: u32 = unsafe { ::std::mem::transmute(reserved) };
+            reserved as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+pub type NVENC_EXTERNAL_ME_HINT_COUNTS_PER_BLOCKTYPE = _NVENC_EXTERNAL_ME_HINT_COUNTS_PER_BLOCKTYPE;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_INITIALIZE_PARAMS {
+    pub version: u32,
+    pub encodeGUID: GUID,
+    pub presetGUID: GUID,
+    pub encodeWidth: u32,
+    pub encodeHeight: u32,
+    pub darWidth: u32,
+    pub darHeight: u32,
+    pub frameRateNum: u32,
+    pub frameRateDen: u32,
+    pub enableEncodeAsync: u32,
+    pub enablePTD: u32,
+    pub _bitfield_align_1: [u32; 0],
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize]>,
+    pub privDataSize: u32,
+    pub privData: *mut ::std::os::raw::c_void,
+    pub encodeConfig: *mut NV_ENC_CONFIG,
+    pub maxEncodeWidth: u32,
+    pub maxEncodeHeight: u32,
+    pub maxMEHintCountsPerBlock: [NVENC_EXTERNAL_ME_HINT_COUNTS_PER_BLOCKTYPE; 2usize],
+    pub tuningInfo: NV_ENC_TUNING_INFO,
+    pub bufferFormat: NV_ENC_BUFFER_FORMAT,
+    pub reserved: [u32; 287usize],
+    pub reserved2: [*mut ::std::os::raw::c_void; 64usize],
+}
+impl _NV_ENC_INITIALIZE_PARAMS {
+    #[inline]
+    pub fn reportSliceOffsets(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_reportSliceOffsets(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableSubFrameWrite(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(1usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableSubFrameWrite(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(1usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableExternalMEHints(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(2usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableExternalMEHints(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(2usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableMEOnlyMode(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(3usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableMEOnlyMode(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(3usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableWeightedPrediction(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(4usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableWeightedPrediction(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(4usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn splitEncodeMode(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(5usize, 4u8) as u32) }
+    }
+    #[inline]
+    pub fn set_splitEncodeMode(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(5usize, 4u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn enableOutputInVidmem(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(9usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_enableOutputInVidmem(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(9usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn reservedBitFields(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(10usize, 22u8) as u32) }
+    }
+    #[inline]
+    pub fn set_reservedBitFields(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(10usize, 22u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(reportSliceOffsets: u32, enableSubFrameWrite: u32, enableExternalMEHints: u32, enableMEOnlyMode: u32, enableWeightedPrediction: u32, splitEncodeMode: u32, enableOutputInVidmem: u32, reservedBitFields: u32) -> __BindgenBitfieldUnit<[u8; 4usize]> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize]> = Default::default();
+        __bindgen_bitfield_unit.set(0usize, 1u8, {
+            let reportSliceOffsets: u32 = unsafe { ::std::mem::transmute(reportSliceOffsets) };
+            reportSliceOffsets as u64
+        });
+        __bindgen_bitfield_unit.set(1usize, 1u8, {
+            let enableSubFrameWrite: u32 = unsafe { ::std::mem::transmute(enableSubFrameWrite) };
+            enableSubFrameWrite as u64
+        });
+        __bindgen_bitfield_unit.set(2usize, 1u8, {
+            let enableExternalMEHints: u32 = unsafe { ::std::mem::transmute(enableExternalMEHints) };
+            enableExternalMEHints as u64
+        });
+        __bindgen_bitfield_unit.set(3usize, 1u8, {
+            let enableMEOnlyMode: u32 = unsafe { ::std::mem::transmute(enableMEOnlyMode) };
+            enableMEOnlyMode as u64
+        });
+        __bindgen_bitfield_unit.set(4usize, 1u8, {
+            let enableWeightedPrediction: u32 = unsafe { ::std::mem::transmute(enableWeightedPrediction) };
+            enableWeightedPrediction as u64
+        });
+        __bindgen_bitfield_unit.set(5usize, 4u8, {
+            let splitEncodeMode: u32 = unsafe { ::std::mem::transmute(splitEncodeMode) };
+            splitEncodeMode as u64
+        });
+        __bindgen_bitfield_unit.set(9usize, 1u8, {
+            let enableOutputInVidmem: u32 = unsafe { ::std::mem::transmute(enableOutputInVidmem) };
+            enableOutputInVidmem as u64
+        });
+        __bindgen_bitfield_unit.set(10usize, 22u8, {
+            let reservedBitFields: u32 = unsafe { ::std::mem::transmute(reservedBitFields) };
+            reservedBitFields as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+pub type NV_ENC_INITIALIZE_PARAMS = _NV_ENC_INITIALIZE_PARAMS;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_CREATE_BITSTREAM_BUFFER {
+    pub version: u32,
+    pub size: u32,
+    pub memoryHeap: NV_ENC_MEMORY_HEAP,
+    pub reserved: u32,
+    pub bitstreamBuffer: NV_ENC_OUTPUT_PTR,
+    pub bitstreamBufferPtr: *mut ::std::os::raw::c_void,
+    pub reserved1: [u32; 58usize],
+    pub reserved2: [*mut ::std::os::raw::c_void; 64usize],
+}
+pub type NV_ENC_CREATE_BITSTREAM_BUFFER = _NV_ENC_CREATE_BITSTREAM_BUFFER;
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union _NV_ENC_CODEC_PIC_PARAMS {
+    pub reserved: [u64; 192usize],
+}
+pub type NV_ENC_CODEC_PIC_PARAMS = _NV_ENC_CODEC_PIC_PARAMS;
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _NV_ENC_PIC_PARAMS {
+    pub version: u32,
+    pub inputWidth: u32,
+    pub inputHeight: u32,
+    pub inputPitch: u32,
+    pub encodePicFlags: u32,
+    pub frameIdx: u32,
+    pub inputTimeStamp: u64,
+    pub inputDuration: u64,
+    pub inputBuffer: NV_ENC_INPUT_PTR,
+    pub outputBitstream: NV_ENC_OUTPUT_PTR,
+    pub completionEvent: *mut ::std::os::raw::c_void,
+    pub bufferFmt: NV_ENC_BUFFER_FORMAT,
+    pub pictureStruct: NV_ENC_PIC_STRUCT,
+    pub pictureType: NV_ENC_PIC_TYPE,
+    pub codecPicParams: NV_ENC_CODEC_PIC_PARAMS,
+    pub meHintCountsPerBlock: [NVENC_EXTERNAL_ME_HINT_COUNTS_PER_BLOCKTYPE; 2usize],
+    pub meExternalHints: *mut ::std::os::raw::c_void,
+    pub reserved1: [u32; 6usize],
+    pub reserved2: [*mut ::std::os::raw::c_void; 2usize],
+    pub qpDeltaMap: *mut i8,
+    pub qpDeltaMapSize: u32,
+    pub reservedBitFields: u32,
+    pub meHintRefPicDist: [u16; 2usize],
+    pub reserved4: u32,
+    pub alphaBuffer: NV_ENC_INPUT_PTR,
+    pub meExternalSbHints: *mut ::std::os::raw::c_void,
+    pub meSbHintsCount: u32,
+    pub stateBufferIdx: u32,
+    pub outputReconBuffer: NV_ENC_OUTPUT_PTR,
+    pub reserved3: [u32; 284usize],
+    pub reserved5: [*mut ::std::os::raw::c_void; 57usize],
+}
+pub type NV_ENC_PIC_PARAMS = _NV_ENC_PIC_PARAMS;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_LOCK_BITSTREAM {
+    pub version: u32,
+    pub _bitfield_align_1: [u32; 0],
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize]>,
+    pub outputBitstream: *mut ::std::os::raw::c_void,
+    pub sliceOffsets: *mut u32,
+    pub frameIdx: u32,
+    pub hwEncodeStatus: u32,
+    pub numSlices: u32,
+    pub bitstreamSizeInBytes: u32,
+    pub outputTimeStamp: u64,
+    pub outputDuration: u64,
+    pub bitstreamBufferPtr: *mut ::std::os::raw::c_void,
+    pub pictureType: NV_ENC_PIC_TYPE,
+    pub pictureStruct: NV_ENC_PIC_STRUCT,
+    pub frameAvgQP: u32,
+    pub frameSatd: u32,
+    pub ltrFrameIdx: u32,
+    pub ltrFrameBitmap: u32,
+    pub temporalId: u32,
+    pub intraMBCount: u32,
+    pub interMBCount: u32,
+    pub averageMVX: i32,
+    pub averageMVY: i32,
+    pub alphaLayerSizeInBytes: u32,
+    pub reserved1: [u32; 224usize],
+    pub reserved2: [*mut ::std::os::raw::c_void; 63usize],
+    pub reservedInternal: [u32; 8usize],
+}
+impl _NV_ENC_LOCK_BITSTREAM {
+    #[inline]
+    pub fn doNotWait(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_doNotWait(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn ltrFrame(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(1usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_ltrFrame(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(1usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn getRCStats(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(2usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_getRCStats(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(2usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn reservedBitFields(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(3usize, 29u8) as u32) }
+    }
+    #[inline]
+    pub fn set_reservedBitFields(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(3usize, 29u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(doNotWait: u32, ltrFrame: u32, getRCStats: u32, reservedBitFields: u32) -> __BindgenBitfieldUnit<[u8; 4usize]> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize]> = Default::default();
+        __bindgen_bitfield_unit.set(0usize, 1u8, {
+            let doNotWait: u32 = unsafe { ::std::mem::transmute(doNotWait) };
+            doNotWait as u64
+        });
+        __bindgen_bitfield_unit.set(1usize, 1u8, {
+            let ltrFrame: u32 = unsafe { ::std::mem::transmute(ltrFrame) };
+            ltrFrame as u64
+        });
+        __bindgen_bitfield_unit.set(2usize, 1u8, {
+            let getRCStats: u32 = unsafe { ::std::mem::transmute(getRCStats) };
+            getRCStats as u64
+        });
+        __bindgen_bitfield_unit.set(3usize, 29u8, {
+            let reservedBitFields: u32 = unsafe { ::std::mem::transmute(reservedBitFields) };
+            reservedBitFields as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+pub type NV_ENC_LOCK_BITSTREAM = _NV_ENC_LOCK_BITSTREAM;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_SEQUENCE_PARAM_PAYLOAD {
+    pub version: u32,
+    pub inBufferSize: u32,
+    pub spsId: u32,
+    pub ppsId: u32,
+    pub spsppsBuffer: *mut ::std::os::raw::c_void,
+    pub outSPSPPSPayloadSize: *mut u32,
+    pub reserved: [u32; 250usize],
+    pub reserved2: [*mut ::std::os::raw::c_void; 64usize],
+}
+pub type NV_ENC_SEQUENCE_PARAM_PAYLOAD = _NV_ENC_SEQUENCE_PARAM_PAYLOAD;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_MAP_INPUT_RESOURCE {
+    pub version: u32,
+    pub subResourceIndex: u32,
+    pub inputResource: *mut ::std::os::raw::c_void,
+    pub registeredResource: NV_ENC_REGISTERED_PTR,
+    pub mappedResource: NV_ENC_INPUT_PTR,
+    pub mappedBufferFmt: NV_ENC_BUFFER_FORMAT,
+    pub reserved1: [u32; 251usize],
+    pub reserved2: [*mut ::std::os::raw::c_void; 63usize],
+}
+pub type NV_ENC_MAP_INPUT_RESOURCE = _NV_ENC_MAP_INPUT_RESOURCE;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_REGISTER_RESOURCE {
+    pub version: u32,
+    pub resourceType: NV_ENC_INPUT_RESOURCE_TYPE,
+    pub width: u32,
+    pub height: u32,
+    pub pitch: u32,
+    pub subResourceIndex: u32,
+    pub resourceToRegister: *mut ::std::os::raw::c_void,
+    pub registeredResource: NV_ENC_REGISTERED_PTR,
+    pub bufferFormat: NV_ENC_BUFFER_FORMAT,
+    pub bufferUsage: NV_ENC_BUFFER_USAGE,
+    pub pInputFencePoint: *mut ::std::os::raw::c_void,
+    pub reserved1: [u32; 247usize],
+    pub reserved2: [*mut ::std::os::raw::c_void; 61usize],
+}
+pub type NV_ENC_REGISTER_RESOURCE = _NV_ENC_REGISTER_RESOURCE;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENC_OPEN_ENCODE_SESSIONEX_PARAMS {
+    pub version: u32,
+    pub deviceType: NV_ENC_DEVICE_TYPE,
+    pub device: *mut ::std::os::raw::c_void,
+    pub reserved: *mut ::std::os::raw::c_void,
+    pub apiVersion: u32,
+    pub reserved1: [u32; 253usize],
+    pub reserved2: [*mut ::std::os::raw::c_void; 64usize],
+}
+pub type NV_ENC_OPEN_ENCODE_SESSION_EX_PARAMS = _NV_ENC_OPEN_ENCODE_SESSIONEX_PARAMS;
+pub type PNVENCOPENENCODESESSIONEX = ::std::option::Option<unsafe extern "C" fn(openSessionExParams: *mut NV_ENC_OPEN_ENCODE_SESSION_EX_PARAMS, encoder: *mut *mut ::std::os::raw::c_void) -> NVENCSTATUS>;
+pub type PNVENCGETENCODEPRESETCONFIGEX = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, encodeGUID: GUID, presetGUID: GUID, tuningInfo: NV_ENC_TUNING_INFO, presetConfig: *mut NV_ENC_PRESET_CONFIG) -> NVENCSTATUS>;
+pub type PNVENCINITIALIZEENCODER = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, createEncodeParams: *mut NV_ENC_INITIALIZE_PARAMS) -> NVENCSTATUS>;
+pub type PNVENCCREATEBITSTREAMBUFFER = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, createBitstreamBufferParams: *mut NV_ENC_CREATE_BITSTREAM_BUFFER) -> NVENCSTATUS>;
+pub type PNVENCDESTROYBITSTREAMBUFFER = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, bitstreamBuffer: NV_ENC_OUTPUT_PTR) -> NVENCSTATUS>;
+pub type PNVENCENCODEPICTURE = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, encodePicParams: *mut NV_ENC_PIC_PARAMS) -> NVENCSTATUS>;
+pub type PNVENCLOCKBITSTREAM = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, lockBitstreamBufferParams: *mut NV_ENC_LOCK_BITSTREAM) -> NVENCSTATUS>;
+pub type PNVENCUNLOCKBITSTREAM = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, bitstreamBuffer: NV_ENC_OUTPUT_PTR) -> NVENCSTATUS>;
+pub type PNVENCGETSEQUENCEPARAMS = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, sequenceParamPayload: *mut NV_ENC_SEQUENCE_PARAM_PAYLOAD) -> NVENCSTATUS>;
+pub type PNVENCMAPINPUTRESOURCE = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, mapInputResParams: *mut NV_ENC_MAP_INPUT_RESOURCE) -> NVENCSTATUS>;
+pub type PNVENCUNMAPINPUTRESOURCE = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, mappedInputBuffer: NV_ENC_INPUT_PTR) -> NVENCSTATUS>;
+pub type PNVENCDESTROYENCODER = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void) -> NVENCSTATUS>;
+pub type PNVENCREGISTERRESOURCE = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, registerResParams: *mut NV_ENC_REGISTER_RESOURCE) -> NVENCSTATUS>;
+pub type PNVENCUNREGISTERRESOURCE = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, registeredResource: NV_ENC_REGISTERED_PTR) -> NVENCSTATUS>;
+pub type PNVENCGETLASTERROR = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void) -> *const ::std::os::raw::c_char>;
+pub type PNVENCSETIOCUDASTREAMS = ::std::option::Option<unsafe extern "C" fn(encoder: *mut ::std::os::raw::c_void, inputStream: NV_ENC_CUSTREAM_PTR, outputStream: NV_ENC_CUSTREAM_PTR) -> NVENCSTATUS>;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _NV_ENCODE_API_FUNCTION_LIST {
+    pub version: u32,
+    pub reserved: u32,
+    pub nvEncOpenEncodeSession: *mut ::std::os::raw::c_void,
+    pub nvEncGetEncodeGUIDCount: *mut ::std::os::raw::c_void,
+    pub nvEncGetEncodeProfileGUIDCount: *mut ::std::os::raw::c_void,
+    pub nvEncGetEncodeProfileGUIDs: *mut ::std::os::raw::c_void,
+    pub nvEncGetEncodeGUIDs: *mut ::std::os::raw::c_void,
+    pub nvEncGetInputFormatCount: *mut ::std::os::raw::c_void,
+    pub nvEncGetInputFormats: *mut ::std::os::raw::c_void,
+    pub nvEncGetEncodeCaps: *mut ::std::os::raw::c_void,
+    pub nvEncGetEncodePresetCount: *mut ::std::os::raw::c_void,
+    pub nvEncGetEncodePresetGUIDs: *mut ::std::os::raw::c_void,
+    pub nvEncGetEncodePresetConfig: *mut ::std::os::raw::c_void,
+    pub nvEncInitializeEncoder: PNVENCINITIALIZEENCODER,
+    pub nvEncCreateInputBuffer: *mut ::std::os::raw::c_void,
+    pub nvEncDestroyInputBuffer: *mut ::std::os::raw::c_void,
+    pub nvEncCreateBitstreamBuffer: PNVENCCREATEBITSTREAMBUFFER,
+    pub nvEncDestroyBitstreamBuffer: PNVENCDESTROYBITSTREAMBUFFER,
+    pub nvEncEncodePicture: PNVENCENCODEPICTURE,
+    pub nvEncLockBitstream: PNVENCLOCKBITSTREAM,
+    pub nvEncUnlockBitstream: PNVENCUNLOCKBITSTREAM,
+    pub nvEncLockInputBuffer: *mut ::std::os::raw::c_void,
+    pub nvEncUnlockInputBuffer: *mut ::std::os::raw::c_void,
+    pub nvEncGetEncodeStats: *mut ::std::os::raw::c_void,
+    pub nvEncGetSequenceParams: PNVENCGETSEQUENCEPARAMS,
+    pub nvEncRegisterAsyncEvent: *mut ::std::os::raw::c_void,
+    pub nvEncUnregisterAsyncEvent: *mut ::std::os::raw::c_void,
+    pub nvEncMapInputResource: PNVENCMAPINPUTRESOURCE,
+    pub nvEncUnmapInputResource: PNVENCUNMAPINPUTRESOURCE,
+    pub nvEncDestroyEncoder: PNVENCDESTROYENCODER,
+    pub nvEncInvalidateRefFrames: *mut ::std::os::raw::c_void,
+    pub nvEncOpenEncodeSessionEx: PNVENCOPENENCODESESSIONEX,
+    pub nvEncRegisterResource: PNVENCREGISTERRESOURCE,
+    pub nvEncUnregisterResource: PNVENCUNREGISTERRESOURCE,
+    pub nvEncReconfigureEncoder: *mut ::std::os::raw::c_void,
+    pub reserved1: *mut ::std::os::raw::c_void,
+    pub nvEncCreateMVBuffer: *mut ::std::os::raw::c_void,
+    pub nvEncDestroyMVBuffer: *mut ::std::os::raw::c_void,
+    pub nvEncRunMotionEstimationOnly: *mut ::std::os::raw::c_void,
+    pub nvEncGetLastErrorString: PNVENCGETLASTERROR,
+    pub nvEncSetIOCudaStreams: PNVENCSETIOCUDASTREAMS,
+    pub nvEncGetEncodePresetConfigEx: PNVENCGETENCODEPRESETCONFIGEX,
+    pub nvEncGetSequenceParamEx: *mut ::std::os::raw::c_void,
+    pub nvEncRestoreEncoderState: *mut ::std::os::raw::c_void,
+    pub nvEncLookaheadPicture: *mut ::std::os::raw::c_void,
+    pub reserved2: [*mut ::std::os::raw::c_void; 275usize],
+}
+pub type NV_ENCODE_API_FUNCTION_LIST = _NV_ENCODE_API_FUNCTION_LIST;
@@ -0,0 +1,748 @@
+// NVIDIA Video Codec SDK 12.2 decode API (cuviddec.h, nvcuvid.h) and
+// the CUDA driver types it depends on, in bindgen's layout. Functions are
+// declared in `functions.rs`.
+//
+// The codec-specific members of `CUVIDPICPARAMS` and `CUVIDEOFORMATEX` are
+// left as their reserved storage. Regenerate with
+// `NVCODEC_UPDATE_BINDINGS=1 cargo build --features bindgen`, which adds the
+// per-codec structs to those unions and otherwise produces the same items;
+// `src/layout.rs` checks the layouts either way.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct __BindgenBitfieldUnit<Storage> {
+    storage: Storage,
+}
+impl<Storage> __BindgenBitfieldUnit<Storage> {
+    #[inline]
+    pub const fn new(storage: Storage) -> Self {
+        Self { storage }
+    }
+}
+impl<Storage> __BindgenBitfieldUnit<Storage>
+where
+    Storage: AsRef<[u8]> + AsMut<[u8]>,
+{
+    #[inline]
+    pub fn get_bit(&self, index: usize) -> bool {
+        debug_assert!(index / 8 < self.storage.as_ref().len());
+        let byte_index = index / 8;
+        let byte = self.storage.as_ref()[byte_index];
+        let bit_index = if cfg!(target_endian = "big") {
+            7 - (index % 8)
+        } else {
+            index % 8
+        };
+        let mask = 1 << bit_index;
+        byte & mask == mask
+    }
+    #[inline]
+    pub fn set_bit(&mut self, index: usize, val: bool) {
+        debug_assert!(index / 8 < self.storage.as_ref().len());
+        let byte_index = index / 8;
+        let byte = &mut self.storage.as_mut()[byte_index];
+        let bit_index = if cfg!(target_endian = "big") {
+            7 - (index % 8)
+        } else {
+            index % 8
+        };
+        let mask = 1 << bit_index;
+        if val {
+            *byte |= mask;
+        } else {
+            *byte &= !mask;
+        }
+    }
+    #[inline]
+    pub fn get(&self, bit_offset: usize, bit_width: u8) -> u64 {
+        debug_assert!(bit_width <= 64);
+        debug_assert!(bit_offset / 8 < self.storage.as_ref().len());
+        debug_assert!((bit_offset + (bit_width as usize)) / 8 <= self.storage.as_ref().len());
+        let mut val = 0;
+        for i in 0..(bit_width as usize) {
+            if self.get_bit(i + bit_offset) {
+                let index = if cfg!(target_endian = "big") {
+                    bit_width as usize - 1 - i
+                } else {
+                    i
+                };
+                val |= 1 << index;
+            }
+        }
+        val
+    }
+    #[inline]
+    pub fn set(&mut self, bit_offset: usize, bit_width: u8, val: u64) {
+        debug_assert!(bit_width <= 64);
+        debug_assert!(bit_offset / 8 < self.storage.as_ref().len());
+        debug_assert!((bit_offset + (bit_width as usize)) / 8 <= self.storage.as_ref().len());
+        for i in 0..(bit_width as usize) {
+            let mask = 1 << i;
+            let val_bit_is_set = val & mask == mask;
+            let index = if cfg!(target_endian = "big") {
+                bit_width as usize - 1 - i
+            } else {
+                i
+            };
+            self.set_bit(index + bit_offset, val_bit_is_set);
+        }
+    }
+}
+pub type CUdeviceptr_v2 = ::std::os::raw::c_ulonglong;
+pub type CUdeviceptr = CUdeviceptr_v2;
+pub type CUdevice_v1 = ::std::os::raw::c_int;
+pub type CUdevice = CUdevice_v1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUctx_st {
+    _unused: [u8; 0],
+}
+pub type CUcontext = *mut CUctx_st;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUmod_st {
+    _unused: [u8; 0],
+}
+pub type CUmodule = *mut CUmod_st;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUfunc_st {
+    _unused: [u8; 0],
+}
+pub type CUfunction = *mut CUfunc_st;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUarray_st {
+    _unused: [u8; 0],
+}
+pub type CUarray = *mut CUarray_st;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUstream_st {
+    _unused: [u8; 0],
+}
+pub type CUstream = *mut CUstream_st;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUevent_st {
+    _unused: [u8; 0],
+}
+pub type CUevent = *mut CUevent_st;
+pub type CUhostFn = ::std::option::Option<unsafe extern "C" fn(userData: *mut ::std::os::raw::c_void)>;
+pub const CUevent_flags_enum_CU_EVENT_DEFAULT: CUevent_flags_enum = 0;
+pub const CUevent_flags_enum_CU_EVENT_BLOCKING_SYNC: CUevent_flags_enum = 1;
+pub const CUevent_flags_enum_CU_EVENT_DISABLE_TIMING: CUevent_flags_enum = 2;
+pub const CUevent_flags_enum_CU_EVENT_INTERPROCESS: CUevent_flags_enum = 4;
+pub type CUevent_flags_enum = ::std::os::raw::c_uint;
+pub use self::CUevent_flags_enum as CUevent_flags;
+pub const CUmemorytype_enum_CU_MEMORYTYPE_HOST: CUmemorytype_enum = 1;
+pub const CUmemorytype_enum_CU_MEMORYTYPE_DEVICE: CUmemorytype_enum = 2;
+pub const CUmemorytype_enum_CU_MEMORYTYPE_ARRAY: CUmemorytype_enum = 3;
+pub const CUmemorytype_enum_CU_MEMORYTYPE_UNIFIED: CUmemorytype_enum = 4;
+pub type CUmemorytype_enum = ::std::os::raw::c_uint;
+pub use self::CUmemorytype_enum as CUmemorytype;
+pub const cudaError_enum_CUDA_SUCCESS: cudaError_enum = 0;
+pub const cudaError_enum_CUDA_ERROR_INVALID_VALUE: cudaError_enum = 1;
+pub const cudaError_enum_CUDA_ERROR_OUT_OF_MEMORY: cudaError_enum = 2;
+pub const cudaError_enum_CUDA_ERROR_NOT_INITIALIZED: cudaError_enum = 3;
+pub const cudaError_enum_CUDA_ERROR_DEINITIALIZED: cudaError_enum = 4;
+pub const cudaError_enum_CUDA_ERROR_PROFILER_DISABLED: cudaError_enum = 5;
+pub const cudaError_enum_CUDA_ERROR_PROFILER_NOT_INITIALIZED: cudaError_enum = 6;
+pub const cudaError_enum_CUDA_ERROR_PROFILER_ALREADY_STARTED: cudaError_enum = 7;
+pub const cudaError_enum_CUDA_ERROR_PROFILER_ALREADY_STOPPED: cudaError_enum = 8;
+pub const cudaError_enum_CUDA_ERROR_STUB_LIBRARY: cudaError_enum = 34;
+pub const cudaError_enum_CUDA_ERROR_DEVICE_UNAVAILABLE: cudaError_enum = 46;
+pub const cudaError_enum_CUDA_ERROR_NO_DEVICE: cudaError_enum = 100;
+pub const cudaError_enum_CUDA_ERROR_INVALID_DEVICE: cudaError_enum = 101;
+pub const cudaError_enum_CUDA_ERROR_DEVICE_NOT_LICENSED: cudaError_enum = 102;
+pub const cudaError_enum_CUDA_ERROR_INVALID_IMAGE: cudaError_enum = 200;
+pub const cudaError_enum_CUDA_ERROR_INVALID_CONTEXT: cudaError_enum = 201;
+pub const cudaError_enum_CUDA_ERROR_CONTEXT_ALREADY_CURRENT: cudaError_enum = 202;
+pub const cudaError_enum_CUDA_ERROR_MAP_FAILED: cudaError_enum = 205;
+pub const cudaError_enum_CUDA_ERROR_UNMAP_FAILED: cudaError_enum = 206;
+pub const cudaError_enum_CUDA_ERROR_ARRAY_IS_MAPPED: cudaError_enum = 207;
+pub const cudaError_enum_CUDA_ERROR_ALREADY_MAPPED: cudaError_enum = 208;
+pub const cudaError_enum_CUDA_ERROR_NO_BINARY_FOR_GPU: cudaError_enum = 209;
+pub const cudaError_enum_CUDA_ERROR_ALREADY_ACQUIRED: cudaError_enum = 210;
+pub const cudaError_enum_CUDA_ERROR_NOT_MAPPED: cudaError_enum = 211;
+pub const cudaError_enum_CUDA_ERROR_NOT_MAPPED_AS_ARRAY: cudaError_enum = 212;
+pub const cudaError_enum_CUDA_ERROR_NOT_MAPPED_AS_POINTER: cudaError_enum = 213;
+pub const cudaError_enum_CUDA_ERROR_ECC_UNCORRECTABLE: cudaError_enum = 214;
+pub const cudaError_enum_CUDA_ERROR_UNSUPPORTED_LIMIT: cudaError_enum = 215;
+pub const cudaError_enum_CUDA_ERROR_CONTEXT_ALREADY_IN_USE: cudaError_enum = 216;
+pub const cudaError_enum_CUDA_ERROR_PEER_ACCESS_UNSUPPORTED: cudaError_enum = 217;
+pub const cudaError_enum_CUDA_ERROR_INVALID_PTX: cudaError_enum = 218;
+pub const cudaError_enum_CUDA_ERROR_INVALID_GRAPHICS_CONTEXT: cudaError_enum = 219;
+pub const cudaError_enum_CUDA_ERROR_NVLINK_UNCORRECTABLE: cudaError_enum = 220;
+pub const cudaError_enum_CUDA_ERROR_JIT_COMPILER_NOT_FOUND: cudaError_enum = 221;
+pub const cudaError_enum_CUDA_ERROR_UNSUPPORTED_PTX_VERSION: cudaError_enum = 222;
+pub const cudaError_enum_CUDA_ERROR_JIT_COMPILATION_DISABLED: cudaError_enum = 223;
+pub const cudaError_enum_CUDA_ERROR_UNSUPPORTED_EXEC_AFFINITY: cudaError_enum = 224;
+pub const cudaError_enum_CUDA_ERROR_UNSUPPORTED_DEVSIDE_SYNC: cudaError_enum = 225;
+pub const cudaError_enum_CUDA_ERROR_INVALID_SOURCE: cudaError_enum = 300;
+pub const cudaError_enum_CUDA_ERROR_FILE_NOT_FOUND: cudaError_enum = 301;
+pub const cudaError_enum_CUDA_ERROR_SHARED_OBJECT_SYMBOL_NOT_FOUND: cudaError_enum = 302;
+pub const cudaError_enum_CUDA_ERROR_SHARED_OBJECT_INIT_FAILED: cudaError_enum = 303;
+pub const cudaError_enum_CUDA_ERROR_OPERATING_SYSTEM: cudaError_enum = 304;
+pub const cudaError_enum_CUDA_ERROR_INVALID_HANDLE: cudaError_enum = 400;
+pub const cudaError_enum_CUDA_ERROR_ILLEGAL_STATE: cudaError_enum = 401;
+pub const cudaError_enum_CUDA_ERROR_LOSSY_QUERY: cudaError_enum = 402;
+pub const cudaError_enum_CUDA_ERROR_NOT_FOUND: cudaError_enum = 500;
+pub const cudaError_enum_CUDA_ERROR_NOT_READY: cudaError_enum = 600;
+pub const cudaError_enum_CUDA_ERROR_ILLEGAL_ADDRESS: cudaError_enum = 700;
+pub const cudaError_enum_CUDA_ERROR_LAUNCH_OUT_OF_RESOURCES: cudaError_enum = 701;
+pub const cudaError_enum_CUDA_ERROR_LAUNCH_TIMEOUT: cudaError_enum = 702;
+pub const cudaError_enum_CUDA_ERROR_LAUNCH_INCOMPATIBLE_TEXTURING: cudaError_enum = 703;
+pub const cudaError_enum_CUDA_ERROR_PEER_ACCESS_ALREADY_ENABLED: cudaError_enum = 704;
+pub const cudaError_enum_CUDA_ERROR_PEER_ACCESS_NOT_ENABLED: cudaError_enum = 705;
+pub const cudaError_enum_CUDA_ERROR_PRIMARY_CONTEXT_ACTIVE: cudaError_enum = 708;
+pub const cudaError_enum_CUDA_ERROR_CONTEXT_IS_DESTROYED: cudaError_enum = 709;
+pub const cudaError_enum_CUDA_ERROR_ASSERT: cudaError_enum = 710;
+pub const cudaError_enum_CUDA_ERROR_TOO_MANY_PEERS: cudaError_enum = 711;
+pub const cudaError_enum_CUDA_ERROR_HOST_MEMORY_ALREADY_REGISTERED: cudaError_enum = 712;
+pub const cudaError_enum_CUDA_ERROR_HOST_MEMORY_NOT_REGISTERED: cudaError_enum = 713;
+pub const cudaError_enum_CUDA_ERROR_HARDWARE_STACK_ERROR: cudaError_enum = 714;
+pub const cudaError_enum_CUDA_ERROR_ILLEGAL_INSTRUCTION: cudaError_enum = 715;
+pub const cudaError_enum_CUDA_ERROR_MISALIGNED_ADDRESS: cudaError_enum = 716;
+pub const cudaError_enum_CUDA_ERROR_INVALID_ADDRESS_SPACE: cudaError_enum = 717;
+pub const cudaError_enum_CUDA_ERROR_INVALID_PC: cudaError_enum = 718;
+pub const cudaError_enum_CUDA_ERROR_LAUNCH_FAILED: cudaError_enum = 719;
+pub const cudaError_enum_CUDA_ERROR_COOPERATIVE_LAUNCH_TOO_LARGE: cudaError_enum = 720;
+pub const cudaError_enum_CUDA_ERROR_NOT_PERMITTED: cudaError_enum = 800;
+pub const cudaError_enum_CUDA_ERROR_NOT_SUPPORTED: cudaError_enum = 801;
+pub const cudaError_enum_CUDA_ERROR_SYSTEM_NOT_READY: cudaError_enum = 802;
+pub const cudaError_enum_CUDA_ERROR_SYSTEM_DRIVER_MISMATCH: cudaError_enum = 803;
+pub const cudaError_enum_CUDA_ERROR_COMPAT_NOT_SUPPORTED_ON_DEVICE: cudaError_enum = 804;
+pub const cudaError_enum_CUDA_ERROR_MPS_CONNECTION_FAILED: cudaError_enum = 805;
+pub const cudaError_enum_CUDA_ERROR_MPS_RPC_FAILURE: cudaError_enum = 806;
+pub const cudaError_enum_CUDA_ERROR_MPS_SERVER_NOT_READY: cudaError_enum = 807;
+pub const cudaError_enum_CUDA_ERROR_MPS_MAX_CLIENTS_REACHED: cudaError_enum = 808;
+pub const cudaError_enum_CUDA_ERROR_MPS_MAX_CONNECTIONS_REACHED: cudaError_enum = 809;
+pub const cudaError_enum_CUDA_ERROR_MPS_CLIENT_TERMINATED: cudaError_enum = 810;
+pub const cudaError_enum_CUDA_ERROR_CDP_NOT_SUPPORTED: cudaError_enum = 811;
+pub const cudaError_enum_CUDA_ERROR_CDP_VERSION_MISMATCH: cudaError_enum = 812;
+pub const cudaError_enum_CUDA_ERROR_STREAM_CAPTURE_UNSUPPORTED: cudaError_enum = 900;
+pub const cudaError_enum_CUDA_ERROR_STREAM_CAPTURE_INVALIDATED: cudaError_enum = 901;
+pub const cudaError_enum_CUDA_ERROR_STREAM_CAPTURE_MERGE: cudaError_enum = 902;
+pub const cudaError_enum_CUDA_ERROR_STREAM_CAPTURE_UNMATCHED: cudaError_enum = 903;
+pub const cudaError_enum_CUDA_ERROR_STREAM_CAPTURE_UNJOINED: cudaError_enum = 904;
+pub const cudaError_enum_CUDA_ERROR_STREAM_CAPTURE_ISOLATION: cudaError_enum = 905;
+pub const cudaError_enum_CUDA_ERROR_STREAM_CAPTURE_IMPLICIT: cudaError_enum = 906;
+pub const cudaError_enum_CUDA_ERROR_CAPTURED_EVENT: cudaError_enum = 907;
+pub const cudaError_enum_CUDA_ERROR_STREAM_CAPTURE_WRONG_THREAD: cudaError_enum = 908;
+pub const cudaError_enum_CUDA_ERROR_TIMEOUT: cudaError_enum = 909;
+pub const cudaError_enum_CUDA_ERROR_GRAPH_EXEC_UPDATE_FAILURE: cudaError_enum = 910;
+pub const cudaError_enum_CUDA_ERROR_EXTERNAL_DEVICE: cudaError_enum = 911;
+pub const cudaError_enum_CUDA_ERROR_INVALID_CLUSTER_SIZE: cudaError_enum = 912;
+pub const cudaError_enum_CUDA_ERROR_FUNCTION_NOT_LOADED: cudaError_enum = 913;
+pub const cudaError_enum_CUDA_ERROR_INVALID_RESOURCE_TYPE: cudaError_enum = 914;
+pub const cudaError_enum_CUDA_ERROR_INVALID_RESOURCE_CONFIGURATION: cudaError_enum = 915;
+pub const cudaError_enum_CUDA_ERROR_UNKNOWN: cudaError_enum = 999;
+pub type cudaError_enum = ::std::os::raw::c_uint;
+pub use self::cudaError_enum as CUresult;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUDA_MEMCPY2D_st {
+    pub srcXInBytes: usize,
+    pub srcY: usize,
+    pub srcMemoryType: CUmemorytype,
+    pub srcHost: *const ::std::os::raw::c_void,
+    pub srcDevice: CUdeviceptr,
+    pub srcArray: CUarray,
+    pub srcPitch: usize,
+    pub dstXInBytes: usize,
+    pub dstY: usize,
+    pub dstMemoryType: CUmemorytype,
+    pub dstHost: *mut ::std::os::raw::c_void,
+    pub dstDevice: CUdeviceptr,
+    pub dstArray: CUarray,
+    pub dstPitch: usize,
+    pub WidthInBytes: usize,
+    pub Height: usize,
+}
+pub type CUDA_MEMCPY2D_v2 = CUDA_MEMCPY2D_st;
+pub type CUDA_MEMCPY2D = CUDA_MEMCPY2D_v2;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUDA_MEMCPY3D_st {
+    pub srcXInBytes: usize,
+    pub srcY: usize,
+    pub srcZ: usize,
+    pub srcLOD: usize,
+    pub srcMemoryType: CUmemorytype,
+    pub srcHost: *const ::std::os::raw::c_void,
+    pub srcDevice: CUdeviceptr,
+    pub srcArray: CUarray,
+    pub reserved0: *mut ::std::os::raw::c_void,
+    pub srcPitch: usize,
+    pub srcHeight: usize,
+    pub dstXInBytes: usize,
+    pub dstY: usize,
+    pub dstZ: usize,
+    pub dstLOD: usize,
+    pub dstMemoryType: CUmemorytype,
+    pub dstHost: *mut ::std::os::raw::c_void,
+    pub dstDevice: CUdeviceptr,
+    pub dstArray: CUarray,
+    pub reserved1: *mut ::std::os::raw::c_void,
+    pub dstPitch: usize,
+    pub dstHeight: usize,
+    pub WidthInBytes: usize,
+    pub Height: usize,
+    pub Depth: usize,
+}
+pub type CUDA_MEMCPY3D_v2 = CUDA_MEMCPY3D_st;
+pub type CUDA_MEMCPY3D = CUDA_MEMCPY3D_v2;
+pub type CUvideodecoder = *mut ::std::os::raw::c_void;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUcontextlock_st {
+    _unused: [u8; 0],
+}
+pub type CUvideoctxlock = *mut _CUcontextlock_st;
+pub const cudaVideoCodec_enum_cudaVideoCodec_MPEG1: cudaVideoCodec_enum = 0;
+pub const cudaVideoCodec_enum_cudaVideoCodec_MPEG2: cudaVideoCodec_enum = 1;
+pub const cudaVideoCodec_enum_cudaVideoCodec_MPEG4: cudaVideoCodec_enum = 2;
+pub const cudaVideoCodec_enum_cudaVideoCodec_VC1: cudaVideoCodec_enum = 3;
+pub const cudaVideoCodec_enum_cudaVideoCodec_H264: cudaVideoCodec_enum = 4;
+pub const cudaVideoCodec_enum_cudaVideoCodec_JPEG: cudaVideoCodec_enum = 5;
+pub const cudaVideoCodec_enum_cudaVideoCodec_H264_SVC: cudaVideoCodec_enum = 6;
+pub const cudaVideoCodec_enum_cudaVideoCodec_H264_MVC: cudaVideoCodec_enum = 7;
+pub const cudaVideoCodec_enum_cudaVideoCodec_HEVC: cudaVideoCodec_enum = 8;
+pub const cudaVideoCodec_enum_cudaVideoCodec_VP8: cudaVideoCodec_enum = 9;
+pub const cudaVideoCodec_enum_cudaVideoCodec_VP9: cudaVideoCodec_enum = 10;
+pub const cudaVideoCodec_enum_cudaVideoCodec_AV1: cudaVideoCodec_enum = 11;
+pub const cudaVideoCodec_enum_cudaVideoCodec_NumCodecs: cudaVideoCodec_enum = 12;
+pub const cudaVideoCodec_enum_cudaVideoCodec_YUV420: cudaVideoCodec_enum = 1230591318;
+pub const cudaVideoCodec_enum_cudaVideoCodec_YV12: cudaVideoCodec_enum = 1498820914;
+pub const cudaVideoCodec_enum_cudaVideoCodec_NV12: cudaVideoCodec_enum = 1314271538;
+pub const cudaVideoCodec_enum_cudaVideoCodec_YUYV: cudaVideoCodec_enum = 1498765654;
+pub const cudaVideoCodec_enum_cudaVideoCodec_UYVY: cudaVideoCodec_enum = 1431918169;
+pub type cudaVideoCodec_enum = ::std::os::raw::c_uint;
+pub use self::cudaVideoCodec_enum as cudaVideoCodec;
+pub const cudaVideoSurfaceFormat_enum_cudaVideoSurfaceFormat_NV12: cudaVideoSurfaceFormat_enum = 0;
+pub const cudaVideoSurfaceFormat_enum_cudaVideoSurfaceFormat_P016: cudaVideoSurfaceFormat_enum = 1;
+pub const cudaVideoSurfaceFormat_enum_cudaVideoSurfaceFormat_YUV444: cudaVideoSurfaceFormat_enum = 2;
+pub const cudaVideoSurfaceFormat_enum_cudaVideoSurfaceFormat_YUV444_16Bit: cudaVideoSurfaceFormat_enum = 3;
+pub type cudaVideoSurfaceFormat_enum = ::std::os::raw::c_uint;
+pub use self::cudaVideoSurfaceFormat_enum as cudaVideoSurfaceFormat;
+pub const cudaVideoDeinterlaceMode_enum_cudaVideoDeinterlaceMode_Weave: cudaVideoDeinterlaceMode_enum = 0;
+pub const cudaVideoDeinterlaceMode_enum_cudaVideoDeinterlaceMode_Bob: cudaVideoDeinterlaceMode_enum = 1;
+pub const cudaVideoDeinterlaceMode_enum_cudaVideoDeinterlaceMode_Adaptive: cudaVideoDeinterlaceMode_enum = 2;
+pub type cudaVideoDeinterlaceMode_enum = ::std::os::raw::c_uint;
+pub use self::cudaVideoDeinterlaceMode_enum as cudaVideoDeinterlaceMode;
+pub const cudaVideoChromaFormat_enum_cudaVideoChromaFormat_Monochrome: cudaVideoChromaFormat_enum = 0;
+pub const cudaVideoChromaFormat_enum_cudaVideoChromaFormat_420: cudaVideoChromaFormat_enum = 1;
+pub const cudaVideoChromaFormat_enum_cudaVideoChromaFormat_422: cudaVideoChromaFormat_enum = 2;
+pub const cudaVideoChromaFormat_enum_cudaVideoChromaFormat_444: cudaVideoChromaFormat_enum = 3;
+pub type cudaVideoChromaFormat_enum = ::std::os::raw::c_uint;
+pub use self::cudaVideoChromaFormat_enum as cudaVideoChromaFormat;
+pub const cudaVideoCreateFlags_enum_cudaVideoCreate_Default: cudaVideoCreateFlags_enum = 0;
+pub const cudaVideoCreateFlags_enum_cudaVideoCreate_PreferCUDA: cudaVideoCreateFlags_enum = 1;
+pub const cudaVideoCreateFlags_enum_cudaVideoCreate_PreferDXVA: cudaVideoCreateFlags_enum = 2;
+pub const cudaVideoCreateFlags_enum_cudaVideoCreate_PreferCUVID: cudaVideoCreateFlags_enum = 4;
+pub type cudaVideoCreateFlags_enum = ::std::os::raw::c_uint;
+pub use self::cudaVideoCreateFlags_enum as cudaVideoCreateFlags;
+pub const cuvidDecodeStatus_enum_cuvidDecodeStatus_Invalid: cuvidDecodeStatus_enum = 0;
+pub const cuvidDecodeStatus_enum_cuvidDecodeStatus_InProgress: cuvidDecodeStatus_enum = 1;
+pub const cuvidDecodeStatus_enum_cuvidDecodeStatus_Success: cuvidDecodeStatus_enum = 2;
+pub const cuvidDecodeStatus_enum_cuvidDecodeStatus_Error: cuvidDecodeStatus_enum = 8;
+pub const cuvidDecodeStatus_enum_cuvidDecodeStatus_Error_Concealed: cuvidDecodeStatus_enum = 9;
+pub type cuvidDecodeStatus_enum = ::std::os::raw::c_uint;
+pub use self::cuvidDecodeStatus_enum as cuvidDecodeStatus;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDDECODECAPS {
+    pub eCodecType: cudaVideoCodec,
+    pub eChromaFormat: cudaVideoChromaFormat,
+    pub nBitDepthMinus8: ::std::os::raw::c_uint,
+    pub reserved1: [::std::os::raw::c_uint; 3usize],
+    pub bIsSupported: ::std::os::raw::c_uchar,
+    pub nNumNVDECs: ::std::os::raw::c_uchar,
+    pub nOutputFormatMask: ::std::os::raw::c_ushort,
+    pub nMaxWidth: ::std::os::raw::c_uint,
+    pub nMaxHeight: ::std::os::raw::c_uint,
+    pub nMaxMBCount: ::std::os::raw::c_uint,
+    pub nMinWidth: ::std::os::raw::c_ushort,
+    pub nMinHeight: ::std::os::raw::c_ushort,
+    pub bIsHistogramSupported: ::std::os::raw::c_uchar,
+    pub nCounterBitDepth: ::std::os::raw::c_uchar,
+    pub nMaxHistogramBins: ::std::os::raw::c_ushort,
+    pub reserved3: [::std::os::raw::c_uint; 10usize],
+}
+pub type CUVIDDECODECAPS = _CUVIDDECODECAPS;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDDECODECREATEINFO {
+    pub ulWidth: ::std::os::raw::c_ulong,
+    pub ulHeight: ::std::os::raw::c_ulong,
+    pub ulNumDecodeSurfaces: ::std::os::raw::c_ulong,
+    pub CodecType: cudaVideoCodec,
+    pub ChromaFormat: cudaVideoChromaFormat,
+    pub ulCreationFlags: ::std::os::raw::c_ulong,
+    pub bitDepthMinus8: ::std::os::raw::c_ulong,
+    pub ulIntraDecodeOnly: ::std::os::raw::c_ulong,
+    pub ulMaxWidth: ::std::os::raw::c_ulong,
+    pub ulMaxHeight: ::std::os::raw::c_ulong,
+    pub Reserved1: ::std::os::raw::c_ulong,
+    pub display_area: _CUVIDDECODECREATEINFO__bindgen_ty_1,
+    pub OutputFormat: cudaVideoSurfaceFormat,
+    pub DeinterlaceMode: cudaVideoDeinterlaceMode,
+    pub ulTargetWidth: ::std::os::raw::c_ulong,
+    pub ulTargetHeight: ::std::os::raw::c_ulong,
+    pub ulNumOutputSurfaces: ::std::os::raw::c_ulong,
+    pub vidLock: CUvideoctxlock,
+    pub target_rect: _CUVIDDECODECREATEINFO__bindgen_ty_2,
+    pub enableHistogram: ::std::os::raw::c_ulong,
+    pub Reserved2: [::std::os::raw::c_ulong; 4usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDDECODECREATEINFO__bindgen_ty_1 {
+    pub left: ::std::os::raw::c_short,
+    pub top: ::std::os::raw::c_short,
+    pub right: ::std::os::raw::c_short,
+    pub bottom: ::std::os::raw::c_short,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDDECODECREATEINFO__bindgen_ty_2 {
+    pub left: ::std::os::raw::c_short,
+    pub top: ::std::os::raw::c_short,
+    pub right: ::std::os::raw::c_short,
+    pub bottom: ::std::os::raw::c_short,
+}
+pub type CUVIDDECODECREATEINFO = _CUVIDDECODECREATEINFO;
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _CUVIDPICPARAMS {
+    pub PicWidthInMbs: ::std::os::raw::c_int,
+    pub FrameHeightInMbs: ::std::os::raw::c_int,
+    pub CurrPicIdx: ::std::os::raw::c_int,
+    pub field_pic_flag: ::std::os::raw::c_int,
+    pub bottom_field_flag: ::std::os::raw::c_int,
+    pub second_field: ::std::os::raw::c_int,
+    pub nBitstreamDataLen: ::std::os::raw::c_uint,
+    pub pBitstreamData: *const ::std::os::raw::c_uchar,
+    pub nNumSlices: ::std::os::raw::c_uint,
+    pub pSliceDataOffsets: *const ::std::os::raw::c_uint,
+    pub ref_pic_flag: ::std::os::raw::c_int,
+    pub intra_pic_flag: ::std::os::raw::c_int,
+    pub Reserved: [::std::os::raw::c_uint; 30usize],
+    pub CodecSpecific: _CUVIDPICPARAMS__bindgen_ty_1,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union _CUVIDPICPARAMS__bindgen_ty_1 {
+    pub CodecReserved: [::std::os::raw::c_uint; 1024usize],
+}
+pub type CUVIDPICPARAMS = _CUVIDPICPARAMS;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDPROCPARAMS {
+    pub progressive_frame: ::std::os::raw::c_int,
+    pub second_field: ::std::os::raw::c_int,
+    pub top_field_first: ::std::os::raw::c_int,
+    pub unpaired_field: ::std::os::raw::c_int,
+    pub reserved_flags: ::std::os::raw::c_uint,
+    pub reserved_zero: ::std::os::raw::c_uint,
+    pub raw_input_dptr: ::std::os::raw::c_ulonglong,
+    pub raw_input_pitch: ::std::os::raw::c_uint,
+    pub raw_input_format: ::std::os::raw::c_uint,
+    pub raw_output_dptr: ::std::os::raw::c_ulonglong,
+    pub raw_output_pitch: ::std::os::raw::c_uint,
+    pub Reserved1: ::std::os::raw::c_uint,
+    pub output_stream: CUstream,
+    pub Reserved: [::std::os::raw::c_uint; 46usize],
+    pub histogram_dptr: *mut ::std::os::raw::c_ulonglong,
+    pub Reserved2: [*mut ::std::os::raw::c_void; 1usize],
+}
+pub type CUVIDPROCPARAMS = _CUVIDPROCPARAMS;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDGETDECODESTATUS {
+    pub decodeStatus: cuvidDecodeStatus,
+    pub reserved: [::std::os::raw::c_uint; 31usize],
+    pub pReserved: [*mut ::std::os::raw::c_void; 8usize],
+}
+pub type CUVIDGETDECODESTATUS = _CUVIDGETDECODESTATUS;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDRECONFIGUREDECODERINFO {
+    pub ulWidth: ::std::os::raw::c_uint,
+    pub ulHeight: ::std::os::raw::c_uint,
+    pub ulTargetWidth: ::std::os::raw::c_uint,
+    pub ulTargetHeight: ::std::os::raw::c_uint,
+    pub ulNumDecodeSurfaces: ::std::os::raw::c_uint,
+    pub reserved1: [::std::os::raw::c_uint; 12usize],
+    pub display_area: _CUVIDRECONFIGUREDECODERINFO__bindgen_ty_1,
+    pub target_rect: _CUVIDRECONFIGUREDECODERINFO__bindgen_ty_2,
+    pub reserved2: [::std::os::raw::c_uint; 11usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDRECONFIGUREDECODERINFO__bindgen_ty_1 {
+    pub left: ::std::os::raw::c_short,
+    pub top: ::std::os::raw::c_short,
+    pub right: ::std::os::raw::c_short,
+    pub bottom: ::std::os::raw::c_short,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDRECONFIGUREDECODERINFO__bindgen_ty_2 {
+    pub left: ::std::os::raw::c_short,
+    pub top: ::std::os::raw::c_short,
+    pub right: ::std::os::raw::c_short,
+    pub bottom: ::std::os::raw::c_short,
+}
+pub type CUVIDRECONFIGUREDECODERINFO = _CUVIDRECONFIGUREDECODERINFO;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUvideoparser {
+    _unused: [u8; 0],
+}
+pub type CUvideoparser = *mut _CUvideoparser;
+pub type CUvideotimestamp = ::std::os::raw::c_longlong;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUVIDEOFORMAT {
+    pub codec: cudaVideoCodec,
+    pub frame_rate: CUVIDEOFORMAT__bindgen_ty_1,
+    pub progressive_sequence: ::std::os::raw::c_uchar,
+    pub bit_depth_luma_minus8: ::std::os::raw::c_uchar,
+    pub bit_depth_chroma_minus8: ::std::os::raw::c_uchar,
+    pub min_num_decode_surfaces: ::std::os::raw::c_uchar,
+    pub coded_width: ::std::os::raw::c_uint,
+    pub coded_height: ::std::os::raw::c_uint,
+    pub display_area: CUVIDEOFORMAT__bindgen_ty_2,
+    pub chroma_format: cudaVideoChromaFormat,
+    pub bitrate: ::std::os::raw::c_uint,
+    pub display_aspect_ratio: CUVIDEOFORMAT__bindgen_ty_3,
+    pub video_signal_description: CUVIDEOFORMAT__bindgen_ty_4,
+    pub seqhdr_data_length: ::std::os::raw::c_uint,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUVIDEOFORMAT__bindgen_ty_1 {
+    pub numerator: ::std::os::raw::c_uint,
+    pub denominator: ::std::os::raw::c_uint,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUVIDEOFORMAT__bindgen_ty_2 {
+    pub left: ::std::os::raw::c_int,
+    pub top: ::std::os::raw::c_int,
+    pub right: ::std::os::raw::c_int,
+    pub bottom: ::std::os::raw::c_int,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUVIDEOFORMAT__bindgen_ty_3 {
+    pub x: ::std::os::raw::c_int,
+    pub y: ::std::os::raw::c_int,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUVIDEOFORMAT__bindgen_ty_4 {
+    pub _bitfield_align_1: [u8; 0],
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 1usize]>,
+    pub color_primaries: ::std::os::raw::c_uchar,
+    pub transfer_characteristics: ::std::os::raw::c_uchar,
+    pub matrix_coefficients: ::std::os::raw::c_uchar,
+}
+impl CUVIDEOFORMAT__bindgen_ty_4 {
+    #[inline]
+    pub fn video_format(&self) -> ::std::os::raw::c_uchar {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 3u8) as u8) }
+    }
+    #[inline]
+    pub fn set_video_format(&mut self, val: ::std::os::raw::c_uchar) {
+        unsafe {
+            let val: u8 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 3u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn video_full_range_flag(&self) -> ::std::os::raw::c_uchar {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(3usize, 1u8) as u8) }
+    }
+    #[inline]
+    pub fn set_video_full_range_flag(&mut self, val: ::std::os::raw::c_uchar) {
+        unsafe {
+            let val: u8 = ::std::mem::transmute(val);
+            self._bitfield_1.set(3usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn reserved_zero_bits(&self) -> ::std::os::raw::c_uchar {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(4usize, 4u8) as u8) }
+    }
+    #[inline]
+    pub fn set_reserved_zero_bits(&mut self, val: ::std::os::raw::c_uchar) {
+        unsafe {
+            let val: u8 = ::std::mem::transmute(val);
+            self._bitfield_1.set(4usize, 4u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(video_format: ::std::os::raw::c_uchar, video_full_range_flag: ::std::os::raw::c_uchar, reserved_zero_bits: ::std::os::raw::c_uchar) -> __BindgenBitfieldUnit<[u8; 1usize]> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 1usize]> = Default::default();
+        __bindgen_bitfield_unit.set(0usize, 3u8, {
+            let video_format: u8 = unsafe { ::std::mem::transmute(video_format) };
+            video_format as u64
+        });
+        __bindgen_bitfield_unit.set(3usize, 1u8, {
+            let video_full_range_flag: u8 = unsafe { ::std::mem::transmute(video_full_range_flag) };
+            video_full_range_flag as u64
+        });
+        __bindgen_bitfield_unit.set(4usize, 4u8, {
+            let reserved_zero_bits: u8 = unsafe { ::std::mem::transmute(reserved_zero_bits) };
+            reserved_zero_bits as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct CUVIDEOFORMATEX {
+    pub format: CUVIDEOFORMAT,
+    pub __bindgen_anon_1: CUVIDEOFORMATEX__bindgen_ty_1,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union CUVIDEOFORMATEX__bindgen_ty_1 {
+    pub raw_seqhdr_data: [::std::os::raw::c_uchar; 1024usize],
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct CUVIDOPERATINGPOINTINFO {
+    pub codec: cudaVideoCodec,
+    pub __bindgen_anon_1: CUVIDOPERATINGPOINTINFO__bindgen_ty_1,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union CUVIDOPERATINGPOINTINFO__bindgen_ty_1 {
+    pub av1: CUVIDOPERATINGPOINTINFO__bindgen_ty_1__bindgen_ty_1,
+    pub CodecReserved: [::std::os::raw::c_uchar; 1024usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CUVIDOPERATINGPOINTINFO__bindgen_ty_1__bindgen_ty_1 {
+    pub operating_points_cnt: ::std::os::raw::c_uchar,
+    pub reserved24_bits: [::std::os::raw::c_uchar; 3usize],
+    pub operating_points_idc: [::std::os::raw::c_ushort; 32usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDSEIMESSAGE {
+    pub sei_message_type: ::std::os::raw::c_uchar,
+    pub reserved: [::std::os::raw::c_uchar; 3usize],
+    pub sei_message_size: ::std::os::raw::c_uint,
+}
+pub type CUVIDSEIMESSAGE = _CUVIDSEIMESSAGE;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDSEIMESSAGEINFO {
+    pub pSEIData: *mut ::std::os::raw::c_void,
+    pub pSEIMessage: *mut CUVIDSEIMESSAGE,
+    pub sei_message_count: ::std::os::raw::c_uint,
+    pub picIdx: ::std::os::raw::c_uint,
+}
+pub type CUVIDSEIMESSAGEINFO = _CUVIDSEIMESSAGEINFO;
+pub const CUvideopacketflags_CUVID_PKT_ENDOFSTREAM: CUvideopacketflags = 1;
+pub const CUvideopacketflags_CUVID_PKT_TIMESTAMP: CUvideopacketflags = 2;
+pub const CUvideopacketflags_CUVID_PKT_DISCONTINUITY: CUvideopacketflags = 4;
+pub const CUvideopacketflags_CUVID_PKT_ENDOFPICTURE: CUvideopacketflags = 8;
+pub const CUvideopacketflags_CUVID_PKT_NOTIFY_EOS: CUvideopacketflags = 16;
+pub type CUvideopacketflags = ::std::os::raw::c_uint;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDSOURCEDATAPACKET {
+    pub flags: ::std::os::raw::c_ulong,
+    pub payload_size: ::std::os::raw::c_ulong,
+    pub payload: *const ::std::os::raw::c_uchar,
+    pub timestamp: CUvideotimestamp,
+}
+pub type CUVIDSOURCEDATAPACKET = _CUVIDSOURCEDATAPACKET;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDPARSERDISPINFO {
+    pub picture_index: ::std::os::raw::c_int,
+    pub progressive_frame: ::std::os::raw::c_int,
+    pub top_field_first: ::std::os::raw::c_int,
+    pub repeat_first_field: ::std::os::raw::c_int,
+    pub timestamp: CUvideotimestamp,
+}
+pub type CUVIDPARSERDISPINFO = _CUVIDPARSERDISPINFO;
+pub type PFNVIDSEQUENCECALLBACK = ::std::option::Option<
+    unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void, arg2: *mut CUVIDEOFORMAT) -> ::std::os::raw::c_int,
+>;
+pub type PFNVIDDECODECALLBACK = ::std::option::Option<
+    unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void, arg2: *mut CUVIDPICPARAMS) -> ::std::os::raw::c_int,
+>;
+pub type PFNVIDDISPLAYCALLBACK = ::std::option::Option<
+    unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void, arg2: *mut CUVIDPARSERDISPINFO) -> ::std::os::raw::c_int,
+>;
+pub type PFNVIDOPPOINTCALLBACK = ::std::option::Option<
+    unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void, arg2: *mut CUVIDOPERATINGPOINTINFO) -> ::std::os::raw::c_int,
+>;
+pub type PFNVIDSEIMSGCALLBACK = ::std::option::Option<
+    unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void, arg2: *mut CUVIDSEIMESSAGEINFO) -> ::std::os::raw::c_int,
+>;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _CUVIDPARSERPARAMS {
+    pub CodecType: cudaVideoCodec,
+    pub ulMaxNumDecodeSurfaces: ::std::os::raw::c_uint,
+    pub ulClockRate: ::std::os::raw::c_uint,
+    pub ulErrorThreshold: ::std::os::raw::c_uint,
+    pub ulMaxDisplayDelay: ::std::os::raw::c_uint,
+    pub _bitfield_align_1: [u32; 0],
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize]>,
+    pub uReserved1: [::std::os::raw::c_uint; 4usize],
+    pub pUserData: *mut ::std::os::raw::c_void,
+    pub pfnSequenceCallback: PFNVIDSEQUENCECALLBACK,
+    pub pfnDecodePicture: PFNVIDDECODECALLBACK,
+    pub pfnDisplayPicture: PFNVIDDISPLAYCALLBACK,
+    pub pfnGetOperatingPoint: PFNVIDOPPOINTCALLBACK,
+    pub pfnGetSEIMsg: PFNVIDSEIMSGCALLBACK,
+    pub pvReserved2: [*mut ::std::os::raw::c_void; 5usize],
+    pub pExtVideoInfo: *mut CUVIDEOFORMATEX,
+}
+impl _CUVIDPARSERPARAMS {
+    #[inline]
+    pub fn bAnnexb(&self) -> ::std::os::raw::c_uint {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 1u8) as u32) }
+    }
+    #[inline]
+    pub fn set_bAnnexb(&mut self, val: ::std::os::raw::c_uint) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 1u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn uReserved(&self) -> ::std::os::raw::c_uint {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(1usize, 31u8) as u32) }
+    }
+    #[inline]
+    pub fn set_uReserved(&mut self, val: ::std::os::raw::c_uint) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(1usize, 31u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(bAnnexb: ::std::os::raw::c_uint, uReserved: ::std::os::raw::c_uint) -> __BindgenBitfieldUnit<[u8; 4usize]> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize]> = Default::default();
+        __bindgen_bitfield_unit.set(0usize, 1u8, {
+            let bAnnexb: u32 = unsafe { ::std::mem::transmute(bAnnexb) };
+            bAnnexb as u64
+        });
+        __bindgen_bitfield_unit.set(1usize, 31u8, {
+            let uReserved: u32 = unsafe { ::std::mem::transmute(uReserved) };
+            uReserved as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+pub type CUVIDPARSERPARAMS = _CUVIDPARSERPARAMS;
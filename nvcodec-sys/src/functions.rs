@@ -0,0 +1,162 @@
+use crate::*;
+use std::os::raw::{c_char, c_int, c_uint, c_ulonglong, c_void};
+
+/// Declares each function once, as a linked `extern` function or, with
+/// `dynamic-loading`, as a field of its library's symbol table plus a free
+/// function of the same name forwarding to it. The two values after a
+/// library's return type are what the forwarders report when the library,
+/// or just the symbol, is missing.
+macro_rules! functions {
+    ($($library:ident: $table:ident -> $ret:ty [$no_library:expr, $no_symbol:expr $(,)?] {
+        $(fn $name:ident($($arg:ident: $ty:ty),* $(,)?);)*
+    })*) => {
+        $(
+            #[cfg(not(feature = "dynamic-loading"))]
+            extern "C" {
+                $(pub fn $name($($arg: $ty),*) -> $ret;)*
+            }
+
+            /// Symbols resolved from one library. Those the installed driver
+            /// does not export are `None`.
+            #[cfg(feature = "dynamic-loading")]
+            pub struct $table {
+                _library: libloading::Library,
+                $(pub $name: Option<unsafe extern "C" fn($($ty),*) -> $ret>,)*
+            }
+
+            #[cfg(feature = "dynamic-loading")]
+            impl $table {
+                /// # Safety
+                ///
+                /// Runs the library's initialisers, so `path` must name the
+                /// NVIDIA library this table is for.
+                pub unsafe fn open(path: &str) -> Result<Self, libloading::Error> {
+                    let library = libloading::Library::new(path)?;
+                    Ok(Self {
+                        $($name: library
+                            .get(concat!(stringify!($name), "\0").as_bytes())
+                            .ok()
+                            .map(|symbol| *symbol),)*
+                        _library: library,
+                    })
+                }
+            }
+
+            $(
+                #[cfg(feature = "dynamic-loading")]
+                #[allow(clippy::missing_safety_doc, clippy::too_many_arguments)]
+                pub unsafe fn $name($($arg: $ty),*) -> $ret {
+                    match crate::loader::$library() {
+                        Ok(table) => match table.$name {
+                            Some(function) => function($($arg),*),
+                            None => $no_symbol,
+                        },
+                        Err(_) => $no_library,
+                    }
+                }
+            )*
+        )*
+    };
+}
+
+functions! {
+    cuda: CudaLibrary -> CUresult [
+        cudaError_enum_CUDA_ERROR_SHARED_OBJECT_INIT_FAILED,
+        cudaError_enum_CUDA_ERROR_NOT_FOUND,
+    ] {
+        fn cuInit(flags: c_uint);
+        fn cuDriverGetVersion(version: *mut c_int);
+        fn cuGetErrorName(error: CUresult, name: *mut *const c_char);
+        fn cuDeviceGet(device: *mut CUdevice, ordinal: c_int);
+        fn cuDeviceGetCount(count: *mut c_int);
+        fn cuDeviceTotalMem_v2(bytes: *mut usize, device: CUdevice);
+        fn cuDevicePrimaryCtxRetain(ctx: *mut CUcontext, device: CUdevice);
+        fn cuDevicePrimaryCtxRelease_v2(device: CUdevice);
+        fn cuCtxPushCurrent_v2(ctx: CUcontext);
+        fn cuCtxPopCurrent_v2(ctx: *mut CUcontext);
+        fn cuCtxGetDevice(device: *mut CUdevice);
+        fn cuStreamCreate(stream: *mut CUstream, flags: c_uint);
+        fn cuStreamDestroy_v2(stream: CUstream);
+        fn cuStreamGetCtx(stream: CUstream, ctx: *mut CUcontext);
+        fn cuStreamSynchronize(stream: CUstream);
+        fn cuMemGetInfo_v2(free: *mut usize, total: *mut usize);
+        fn cuMemAllocPitch_v2(
+            dptr: *mut CUdeviceptr,
+            pitch: *mut usize,
+            width_bytes: usize,
+            height: usize,
+            element_size: c_uint,
+        );
+        fn cuMemFree_v2(dptr: CUdeviceptr);
+        fn cuMemAllocHost_v2(pp: *mut *mut c_void, bytesize: usize);
+        fn cuMemFreeHost(p: *mut c_void);
+        fn cuMemcpy2DAsync_v2(copy: *const CUDA_MEMCPY2D, stream: CUstream);
+        fn cuMemcpy3DAsync_v2(copy: *const CUDA_MEMCPY3D, stream: CUstream);
+        fn cuEventCreate(event: *mut CUevent, flags: c_uint);
+        fn cuEventDestroy_v2(event: CUevent);
+        fn cuEventRecord(event: CUevent, stream: CUstream);
+        fn cuEventSynchronize(event: CUevent);
+        fn cuStreamWaitEvent(stream: CUstream, event: CUevent, flags: c_uint);
+        fn cuLaunchHostFunc(stream: CUstream, func: CUhostFn, user_data: *mut c_void);
+        fn cuModuleLoadData(module: *mut CUmodule, image: *const c_void);
+        fn cuModuleUnload(module: CUmodule);
+        fn cuModuleGetFunction(function: *mut CUfunction, module: CUmodule, name: *const c_char);
+        fn cuLaunchKernel(
+            function: CUfunction,
+            grid_dim_x: c_uint,
+            grid_dim_y: c_uint,
+            grid_dim_z: c_uint,
+            block_dim_x: c_uint,
+            block_dim_y: c_uint,
+            block_dim_z: c_uint,
+            shared_mem_bytes: c_uint,
+            stream: CUstream,
+            kernel_params: *mut *mut c_void,
+            extra: *mut *mut c_void,
+        );
+    }
+    nvcuvid: NvcuvidLibrary -> CUresult [
+        cudaError_enum_CUDA_ERROR_SHARED_OBJECT_INIT_FAILED,
+        cudaError_enum_CUDA_ERROR_NOT_FOUND,
+    ] {
+        fn cuvidGetDecoderCaps(caps: *mut CUVIDDECODECAPS);
+        fn cuvidCreateDecoder(decoder: *mut CUvideodecoder, info: *mut CUVIDDECODECREATEINFO);
+        fn cuvidDestroyDecoder(decoder: CUvideodecoder);
+        fn cuvidDecodePicture(decoder: CUvideodecoder, params: *mut CUVIDPICPARAMS);
+        fn cuvidGetDecodeStatus(
+            decoder: CUvideodecoder,
+            pic_idx: c_int,
+            status: *mut CUVIDGETDECODESTATUS,
+        );
+        fn cuvidReconfigureDecoder(
+            decoder: CUvideodecoder,
+            params: *mut CUVIDRECONFIGUREDECODERINFO,
+        );
+        fn cuvidMapVideoFrame64(
+            decoder: CUvideodecoder,
+            pic_idx: c_int,
+            dev_ptr: *mut c_ulonglong,
+            pitch: *mut c_uint,
+            params: *mut CUVIDPROCPARAMS,
+        );
+        fn cuvidUnmapVideoFrame64(decoder: CUvideodecoder, dev_ptr: c_ulonglong);
+        fn cuvidCtxLockCreate(lock: *mut CUvideoctxlock, ctx: CUcontext);
+        fn cuvidCtxLockDestroy(lock: CUvideoctxlock);
+        fn cuvidCtxLock(lock: CUvideoctxlock, flags: c_uint);
+        fn cuvidCtxUnlock(lock: CUvideoctxlock, flags: c_uint);
+        fn cuvidCreateVideoParser(parser: *mut CUvideoparser, params: *mut CUVIDPARSERPARAMS);
+        fn cuvidParseVideoData(parser: CUvideoparser, packet: *mut CUVIDSOURCEDATAPACKET);
+        fn cuvidDestroyVideoParser(parser: CUvideoparser);
+    }
+}
+
+#[cfg(feature = "nvenc")]
+functions! {
+    nvenc: NvencLibrary -> NVENCSTATUS [
+        _NVENCSTATUS_NV_ENC_ERR_NO_ENCODE_DEVICE,
+        _NVENCSTATUS_NV_ENC_ERR_UNIMPLEMENTED,
+    ] {
+        fn NvEncodeAPIGetMaxSupportedVersion(version: *mut u32);
+        fn NvEncodeAPICreateInstance(function_list: *mut NV_ENCODE_API_FUNCTION_LIST);
+    }
+}
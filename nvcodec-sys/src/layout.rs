@@ -0,0 +1,574 @@
+//! Sizes, alignments and field offsets of the bundled types, as the SDK
+//! headers give them on 64-bit Linux, where `unsigned long` is 8 bytes.
+#![cfg(all(unix, target_pointer_width = "64"))]
+
+use crate::*;
+use std::mem::{align_of, offset_of, size_of};
+
+macro_rules! assert_layout {
+    ($ty:ty, size $size:expr, align $align:expr $(, $field:ident @ $offset:expr)* $(,)?) => {
+        assert_eq!(size_of::<$ty>(), $size, "size of {}", stringify!($ty));
+        assert_eq!(align_of::<$ty>(), $align, "alignment of {}", stringify!($ty));
+        $(
+            assert_eq!(
+                offset_of!($ty, $field),
+                $offset,
+                "offset of {}::{}",
+                stringify!($ty),
+                stringify!($field)
+            );
+        )*
+    };
+}
+
+#[test]
+fn memcpy() {
+    assert_layout!(
+        CUDA_MEMCPY2D_st, size 128, align 8,
+        srcXInBytes @ 0,
+        srcY @ 8,
+        srcMemoryType @ 16,
+        srcHost @ 24,
+        srcDevice @ 32,
+        srcArray @ 40,
+        srcPitch @ 48,
+        dstXInBytes @ 56,
+        dstY @ 64,
+        dstMemoryType @ 72,
+        dstHost @ 80,
+        dstDevice @ 88,
+        dstArray @ 96,
+        dstPitch @ 104,
+        WidthInBytes @ 112,
+        Height @ 120,
+    );
+    assert_layout!(
+        CUDA_MEMCPY3D_st, size 200, align 8,
+        srcXInBytes @ 0,
+        srcY @ 8,
+        srcZ @ 16,
+        srcLOD @ 24,
+        srcMemoryType @ 32,
+        srcHost @ 40,
+        srcDevice @ 48,
+        srcArray @ 56,
+        reserved0 @ 64,
+        srcPitch @ 72,
+        srcHeight @ 80,
+        dstXInBytes @ 88,
+        dstY @ 96,
+        dstZ @ 104,
+        dstLOD @ 112,
+        dstMemoryType @ 120,
+        dstHost @ 128,
+        dstDevice @ 136,
+        dstArray @ 144,
+        reserved1 @ 152,
+        dstPitch @ 160,
+        dstHeight @ 168,
+        WidthInBytes @ 176,
+        Height @ 184,
+        Depth @ 192,
+    );
+}
+
+#[test]
+fn decode_caps() {
+    assert_layout!(
+        _CUVIDDECODECAPS, size 88, align 4,
+        eCodecType @ 0,
+        eChromaFormat @ 4,
+        nBitDepthMinus8 @ 8,
+        reserved1 @ 12,
+        bIsSupported @ 24,
+        nNumNVDECs @ 25,
+        nOutputFormatMask @ 26,
+        nMaxWidth @ 28,
+        nMaxHeight @ 32,
+        nMaxMBCount @ 36,
+        nMinWidth @ 40,
+        nMinHeight @ 42,
+        bIsHistogramSupported @ 44,
+        nCounterBitDepth @ 45,
+        nMaxHistogramBins @ 46,
+        reserved3 @ 48,
+    );
+}
+
+#[test]
+fn decoder_create_info() {
+    assert_layout!(
+        _CUVIDDECODECREATEINFO, size 176, align 8,
+        ulWidth @ 0,
+        ulHeight @ 8,
+        ulNumDecodeSurfaces @ 16,
+        CodecType @ 24,
+        ChromaFormat @ 28,
+        ulCreationFlags @ 32,
+        bitDepthMinus8 @ 40,
+        ulIntraDecodeOnly @ 48,
+        ulMaxWidth @ 56,
+        ulMaxHeight @ 64,
+        Reserved1 @ 72,
+        display_area @ 80,
+        OutputFormat @ 88,
+        DeinterlaceMode @ 92,
+        ulTargetWidth @ 96,
+        ulTargetHeight @ 104,
+        ulNumOutputSurfaces @ 112,
+        vidLock @ 120,
+        target_rect @ 128,
+        enableHistogram @ 136,
+        Reserved2 @ 144,
+    );
+    assert_layout!(_CUVIDDECODECREATEINFO__bindgen_ty_1, size 8, align 2, left @ 0, top @ 2, right @ 4, bottom @ 6);
+    assert_layout!(_CUVIDDECODECREATEINFO__bindgen_ty_2, size 8, align 2, left @ 0, top @ 2, right @ 4, bottom @ 6);
+}
+
+#[test]
+fn reconfigure_decoder_info() {
+    assert_layout!(
+        _CUVIDRECONFIGUREDECODERINFO, size 128, align 4,
+        ulWidth @ 0,
+        ulHeight @ 4,
+        ulTargetWidth @ 8,
+        ulTargetHeight @ 12,
+        ulNumDecodeSurfaces @ 16,
+        reserved1 @ 20,
+        display_area @ 68,
+        target_rect @ 76,
+        reserved2 @ 84,
+    );
+    assert_layout!(_CUVIDRECONFIGUREDECODERINFO__bindgen_ty_1, size 8, align 2, left @ 0, top @ 2, right @ 4, bottom @ 6);
+    assert_layout!(_CUVIDRECONFIGUREDECODERINFO__bindgen_ty_2, size 8, align 2, left @ 0, top @ 2, right @ 4, bottom @ 6);
+}
+
+#[test]
+fn pic_params() {
+    assert_layout!(
+        _CUVIDPICPARAMS, size 4280, align 8,
+        PicWidthInMbs @ 0,
+        FrameHeightInMbs @ 4,
+        CurrPicIdx @ 8,
+        field_pic_flag @ 12,
+        bottom_field_flag @ 16,
+        second_field @ 20,
+        nBitstreamDataLen @ 24,
+        pBitstreamData @ 32,
+        nNumSlices @ 40,
+        pSliceDataOffsets @ 48,
+        ref_pic_flag @ 56,
+        intra_pic_flag @ 60,
+        Reserved @ 64,
+        CodecSpecific @ 184,
+    );
+    assert_eq!(size_of::<_CUVIDPICPARAMS__bindgen_ty_1>(), 4096);
+}
+
+#[test]
+fn proc_params() {
+    assert_layout!(
+        _CUVIDPROCPARAMS, size 264, align 8,
+        progressive_frame @ 0,
+        second_field @ 4,
+        top_field_first @ 8,
+        unpaired_field @ 12,
+        reserved_flags @ 16,
+        reserved_zero @ 20,
+        raw_input_dptr @ 24,
+        raw_input_pitch @ 32,
+        raw_input_format @ 36,
+        raw_output_dptr @ 40,
+        raw_output_pitch @ 48,
+        Reserved1 @ 52,
+        output_stream @ 56,
+        Reserved @ 64,
+        histogram_dptr @ 248,
+        Reserved2 @ 256,
+    );
+}
+
+#[test]
+fn decode_status() {
+    assert_layout!(
+        _CUVIDGETDECODESTATUS, size 192, align 8,
+        decodeStatus @ 0,
+        reserved @ 4,
+        pReserved @ 128,
+    );
+}
+
+#[test]
+fn video_format() {
+    assert_layout!(
+        CUVIDEOFORMAT, size 64, align 4,
+        codec @ 0,
+        frame_rate @ 4,
+        progressive_sequence @ 12,
+        bit_depth_luma_minus8 @ 13,
+        bit_depth_chroma_minus8 @ 14,
+        min_num_decode_surfaces @ 15,
+        coded_width @ 16,
+        coded_height @ 20,
+        display_area @ 24,
+        chroma_format @ 40,
+        bitrate @ 44,
+        display_aspect_ratio @ 48,
+        video_signal_description @ 56,
+        seqhdr_data_length @ 60,
+    );
+    assert_layout!(CUVIDEOFORMAT__bindgen_ty_1, size 8, align 4, numerator @ 0, denominator @ 4);
+    assert_layout!(CUVIDEOFORMAT__bindgen_ty_2, size 16, align 4, left @ 0, top @ 4, right @ 8, bottom @ 12);
+    assert_layout!(CUVIDEOFORMAT__bindgen_ty_3, size 8, align 4, x @ 0, y @ 4);
+    assert_layout!(
+        CUVIDEOFORMAT__bindgen_ty_4, size 4, align 1,
+        _bitfield_1 @ 0,
+        color_primaries @ 1,
+        transfer_characteristics @ 2,
+        matrix_coefficients @ 3,
+    );
+    assert_layout!(CUVIDEOFORMATEX, size 1088, align 4, format @ 0, __bindgen_anon_1 @ 64);
+    assert_eq!(size_of::<CUVIDEOFORMATEX__bindgen_ty_1>(), 1024);
+}
+
+#[test]
+fn video_signal_bits() {
+    // video_format:3, video_full_range_flag:1, reserved_zero_bits:4, from
+    // the least significant bit.
+    let bits = CUVIDEOFORMAT__bindgen_ty_4::new_bitfield_1(5, 1, 0);
+    let signal = CUVIDEOFORMAT__bindgen_ty_4 {
+        _bitfield_align_1: [],
+        _bitfield_1: bits,
+        color_primaries: 1,
+        transfer_characteristics: 1,
+        matrix_coefficients: 1,
+    };
+    let raw: [u8; 4] = unsafe { std::mem::transmute(signal) };
+    assert_eq!(raw, [0b0000_1101, 1, 1, 1]);
+    assert_eq!(signal.video_format(), 5);
+    assert_eq!(signal.video_full_range_flag(), 1);
+}
+
+#[test]
+fn operating_point_info() {
+    assert_layout!(CUVIDOPERATINGPOINTINFO, size 1028, align 4, codec @ 0, __bindgen_anon_1 @ 4);
+    assert_eq!(size_of::<CUVIDOPERATINGPOINTINFO__bindgen_ty_1>(), 1024);
+    assert_layout!(
+        CUVIDOPERATINGPOINTINFO__bindgen_ty_1__bindgen_ty_1, size 68, align 2,
+        operating_points_cnt @ 0,
+        reserved24_bits @ 1,
+        operating_points_idc @ 4,
+    );
+}
+
+#[test]
+fn sei_messages() {
+    assert_layout!(
+        _CUVIDSEIMESSAGE, size 8, align 4,
+        sei_message_type @ 0,
+        reserved @ 1,
+        sei_message_size @ 4,
+    );
+    assert_layout!(
+        _CUVIDSEIMESSAGEINFO, size 24, align 8,
+        pSEIData @ 0,
+        pSEIMessage @ 8,
+        sei_message_count @ 16,
+        picIdx @ 20,
+    );
+}
+
+#[test]
+fn parser_packets() {
+    assert_layout!(
+        _CUVIDSOURCEDATAPACKET, size 32, align 8,
+        flags @ 0,
+        payload_size @ 8,
+        payload @ 16,
+        timestamp @ 24,
+    );
+    assert_layout!(
+        _CUVIDPARSERDISPINFO, size 24, align 8,
+        picture_index @ 0,
+        progressive_frame @ 4,
+        top_field_first @ 8,
+        repeat_first_field @ 12,
+        timestamp @ 16,
+    );
+}
+
+#[test]
+fn parser_params() {
+    assert_layout!(
+        _CUVIDPARSERPARAMS, size 136, align 8,
+        CodecType @ 0,
+        ulMaxNumDecodeSurfaces @ 4,
+        ulClockRate @ 8,
+        ulErrorThreshold @ 12,
+        ulMaxDisplayDelay @ 16,
+        _bitfield_1 @ 20,
+        uReserved1 @ 24,
+        pUserData @ 40,
+        pfnSequenceCallback @ 48,
+        pfnDecodePicture @ 56,
+        pfnDisplayPicture @ 64,
+        pfnGetOperatingPoint @ 72,
+        pfnGetSEIMsg @ 80,
+        pvReserved2 @ 88,
+        pExtVideoInfo @ 128,
+    );
+
+    // bAnnexb:1 is the least significant bit of its unsigned int.
+    let bits = _CUVIDPARSERPARAMS::new_bitfield_1(1, 0);
+    let raw: [u8; 4] = unsafe { std::mem::transmute(bits) };
+    assert_eq!(u32::from_ne_bytes(raw), 1);
+}
+
+#[cfg(feature = "nvenc")]
+#[test]
+fn nvenc_config() {
+    assert_layout!(GUID, size 16, align 4, Data1 @ 0, Data2 @ 4, Data3 @ 6, Data4 @ 8);
+    assert_layout!(NV_ENC_QP, size 12, align 4, qpInterP @ 0, qpInterB @ 4, qpIntra @ 8);
+    assert_layout!(
+        NV_ENC_RC_PARAMS, size 128, align 4,
+        rateControlMode @ 4,
+        constQP @ 8,
+        averageBitRate @ 20,
+        maxBitRate @ 24,
+        _bitfield_1 @ 36,
+        minQP @ 40,
+        temporalLayerQP @ 80,
+        lookaheadDepth @ 90,
+        reserved @ 96,
+    );
+    assert_layout!(
+        NV_ENC_CONFIG_H264, size 1792, align 8,
+        _bitfield_1 @ 0,
+        idrPeriod @ 8,
+        h264VUIParameters @ 72,
+        chromaFormatIDC @ 192,
+        reserved1 @ 212,
+        reserved2 @ 1280,
+    );
+    assert_layout!(
+        NV_ENC_CONFIG_HEVC, size 1560, align 8,
+        _bitfield_1 @ 16,
+        idrPeriod @ 20,
+        hevcVUIParameters @ 64,
+        outputBitDepth @ 200,
+        inputBitDepth @ 204,
+        reserved2 @ 1048,
+    );
+    assert_layout!(
+        NV_ENC_CONFIG_AV1, size 1552, align 8,
+        _bitfield_1 @ 16,
+        idrPeriod @ 20,
+        tileWidths @ 48,
+        filmGrainParams @ 96,
+        outputBitDepth @ 112,
+        inputBitDepth @ 116,
+    );
+    assert_eq!(size_of::<NV_ENC_CODEC_CONFIG>(), 1792);
+    assert_layout!(
+        NV_ENC_CONFIG, size 3584, align 8,
+        profileGUID @ 4,
+        gopLength @ 20,
+        frameIntervalP @ 24,
+        rcParams @ 40,
+        encodeCodecConfig @ 168,
+        reserved @ 1960,
+        reserved2 @ 3072,
+    );
+    assert_layout!(NV_ENC_PRESET_CONFIG, size 5128, align 8, presetCfg @ 8, reserved1 @ 3592);
+
+    // repeatSPSPPS:1 is bit 12 of the H.264 flags, chromaFormatIDC:2 bits
+    // 9-10 of the HEVC ones and repeatSeqHdr:1 bit 5 of the AV1 ones.
+    let mut config: NV_ENC_CODEC_CONFIG = unsafe { std::mem::zeroed() };
+    unsafe { config.h264Config.set_repeatSPSPPS(1) };
+    assert_eq!(unsafe { config.reserved[0] }, 1 << 12);
+    let mut config: NV_ENC_CODEC_CONFIG = unsafe { std::mem::zeroed() };
+    unsafe { config.hevcConfig.set_chromaFormatIDC(3) };
+    assert_eq!(unsafe { config.reserved[4] }, 3 << 9);
+    let mut config: NV_ENC_CODEC_CONFIG = unsafe { std::mem::zeroed() };
+    unsafe { config.av1Config.set_repeatSeqHdr(1) };
+    assert_eq!(unsafe { config.reserved[4] }, 1 << 5);
+}
+
+#[cfg(feature = "nvenc")]
+#[test]
+fn nvenc_session() {
+    assert_layout!(
+        NV_ENC_OPEN_ENCODE_SESSION_EX_PARAMS, size 1552, align 8,
+        deviceType @ 4,
+        device @ 8,
+        apiVersion @ 24,
+        reserved2 @ 1040,
+    );
+    assert_layout!(
+        NV_ENC_INITIALIZE_PARAMS, size 1808, align 8,
+        encodeGUID @ 4,
+        presetGUID @ 20,
+        encodeWidth @ 36,
+        frameRateNum @ 52,
+        enablePTD @ 64,
+        privData @ 80,
+        encodeConfig @ 88,
+        maxEncodeWidth @ 96,
+        maxMEHintCountsPerBlock @ 104,
+        tuningInfo @ 136,
+        bufferFormat @ 140,
+        reserved2 @ 1296,
+    );
+    assert_layout!(
+        NV_ENC_REGISTER_RESOURCE, size 1536, align 8,
+        resourceType @ 4,
+        pitch @ 16,
+        resourceToRegister @ 24,
+        registeredResource @ 32,
+        bufferFormat @ 40,
+        bufferUsage @ 44,
+        pInputFencePoint @ 48,
+        reserved2 @ 1048,
+    );
+    assert_layout!(
+        NV_ENC_MAP_INPUT_RESOURCE, size 1544, align 8,
+        registeredResource @ 16,
+        mappedResource @ 24,
+        mappedBufferFmt @ 32,
+        reserved2 @ 1040,
+    );
+    assert_layout!(
+        NV_ENC_CREATE_BITSTREAM_BUFFER, size 776, align 8,
+        bitstreamBuffer @ 16,
+        reserved2 @ 264,
+    );
+    assert_layout!(
+        NV_ENC_SEQUENCE_PARAM_PAYLOAD, size 1544, align 8,
+        inBufferSize @ 4,
+        spsppsBuffer @ 16,
+        outSPSPPSPayloadSize @ 24,
+        reserved2 @ 1032,
+    );
+    assert_layout!(
+        NV_ENCODE_API_FUNCTION_LIST, size 2552, align 8,
+        nvEncOpenEncodeSession @ 8,
+        nvEncInitializeEncoder @ 96,
+        nvEncEncodePicture @ 136,
+        nvEncGetSequenceParams @ 184,
+        nvEncOpenEncodeSessionEx @ 240,
+        nvEncGetLastErrorString @ 304,
+        nvEncGetEncodePresetConfigEx @ 320,
+        nvEncLookaheadPicture @ 344,
+        reserved2 @ 352,
+    );
+}
+
+#[cfg(feature = "nvenc")]
+#[test]
+fn nvenc_pictures() {
+    assert_layout!(
+        NV_ENC_PIC_PARAMS, size 3344, align 8,
+        inputPitch @ 12,
+        encodePicFlags @ 16,
+        inputTimeStamp @ 24,
+        inputBuffer @ 40,
+        outputBitstream @ 48,
+        bufferFmt @ 64,
+        pictureStruct @ 68,
+        codecPicParams @ 80,
+        meHintCountsPerBlock @ 1616,
+        qpDeltaMap @ 1696,
+        alphaBuffer @ 1720,
+        outputReconBuffer @ 1744,
+        reserved5 @ 2888,
+    );
+    assert_layout!(
+        NV_ENC_LOCK_BITSTREAM, size 1544, align 8,
+        _bitfield_1 @ 4,
+        outputBitstream @ 8,
+        bitstreamSizeInBytes @ 36,
+        outputTimeStamp @ 40,
+        bitstreamBufferPtr @ 56,
+        pictureType @ 64,
+        alphaLayerSizeInBytes @ 108,
+        reserved2 @ 1008,
+        reservedInternal @ 1512,
+    );
+}
+
+/// `size`, `align` and member offset entries in the format of
+/// `tools/nvenc_layout.c`.
+#[cfg(feature = "nvenc")]
+macro_rules! layout_entries {
+    ($($ty:ident { $($member:ident),* $(,)? })*) => {
+        vec![$(
+            (concat!(stringify!($ty), " size"), size_of::<$ty>()),
+            (concat!(stringify!($ty), " align"), align_of::<$ty>()),
+            $((concat!(stringify!($ty), ".", stringify!($member)), offset_of!($ty, $member)),)*
+        )*]
+    };
+}
+
+/// Compares the NVENC bindings with the C compiler's layout of the header
+/// they were made from, `src/bindings/nvenc_<major>_<minor>.layout`. The
+/// expected values above come from the bindings; this is what ties them to
+/// the header, for SDK releases with a dump checked in.
+#[cfg(feature = "nvenc")]
+#[cfg_attr(not(nvcodec_nvenc_layout), ignore = "no layout dump from tools/nvenc_layout.c for this SDK release")]
+#[test]
+fn nvenc_header_layout() {
+    let rust = layout_entries! {
+        GUID { Data1, Data2, Data3, Data4 }
+        NV_ENC_QP { qpInterP, qpInterB, qpIntra }
+        NV_ENC_RC_PARAMS {
+            rateControlMode, constQP, averageBitRate, maxBitRate, minQP, temporalLayerQP, lookaheadDepth,
+        }
+        NV_ENC_CONFIG_H264 { idrPeriod, h264VUIParameters, chromaFormatIDC }
+        NV_ENC_CONFIG_HEVC { idrPeriod, hevcVUIParameters, outputBitDepth, inputBitDepth }
+        NV_ENC_CONFIG_AV1 { idrPeriod, tileWidths, filmGrainParams, outputBitDepth, inputBitDepth }
+        NV_ENC_CODEC_CONFIG {}
+        NV_ENC_CONFIG { profileGUID, gopLength, frameIntervalP, rcParams, encodeCodecConfig }
+        NV_ENC_PRESET_CONFIG { presetCfg }
+        NV_ENC_OPEN_ENCODE_SESSION_EX_PARAMS { deviceType, device, apiVersion }
+        NV_ENC_INITIALIZE_PARAMS {
+            encodeGUID, presetGUID, encodeWidth, frameRateNum, enablePTD, privData, encodeConfig,
+            maxEncodeWidth, maxMEHintCountsPerBlock, tuningInfo, bufferFormat,
+        }
+        NV_ENC_REGISTER_RESOURCE {
+            resourceType, pitch, resourceToRegister, registeredResource, bufferFormat, bufferUsage,
+            pInputFencePoint,
+        }
+        NV_ENC_MAP_INPUT_RESOURCE { registeredResource, mappedResource, mappedBufferFmt }
+        NV_ENC_CREATE_BITSTREAM_BUFFER { bitstreamBuffer }
+        NV_ENC_SEQUENCE_PARAM_PAYLOAD { inBufferSize, spsppsBuffer, outSPSPPSPayloadSize }
+        NV_ENCODE_API_FUNCTION_LIST {
+            nvEncOpenEncodeSession, nvEncInitializeEncoder, nvEncEncodePicture, nvEncGetSequenceParams,
+            nvEncOpenEncodeSessionEx, nvEncGetLastErrorString, nvEncGetEncodePresetConfigEx,
+            nvEncLookaheadPicture,
+        }
+        NV_ENC_CODEC_PIC_PARAMS {}
+        NV_ENC_PIC_PARAMS {
+            inputPitch, encodePicFlags, inputTimeStamp, inputBuffer, outputBitstream, bufferFmt,
+            pictureStruct, codecPicParams, meHintCountsPerBlock, qpDeltaMap, alphaBuffer,
+            outputReconBuffer,
+        }
+        NV_ENC_LOCK_BITSTREAM {
+            outputBitstream, bitstreamSizeInBytes, outputTimeStamp, bitstreamBufferPtr, pictureType,
+            alphaLayerSizeInBytes,
+        }
+    };
+
+    let path = option_env!("NVCODEC_SYS_NVENC_LAYOUT").expect("no layout dump for this SDK release");
+    let dump = std::fs::read_to_string(path).unwrap();
+    let mut checked = 0;
+    for line in dump.lines().filter(|line| !line.trim().is_empty()) {
+        let (name, value) = line.rsplit_once(' ').unwrap();
+        let value: usize = value.parse().unwrap();
+        let (_, actual) = rust.iter().find(|(entry, _)| *entry == name).unwrap_or_else(|| {
+            panic!("{} is in the dump but not in this test", name)
+        });
+        assert_eq!(*actual, value, "{}", name);
+        checked += 1;
+    }
+    assert_eq!(checked, rust.len(), "the dump misses entries this test checks");
+}
@@ -3,4 +3,27 @@
 #![allow(non_camel_case_types)]
 #![allow(non_upper_case_globals)]
 
-include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+#[allow(clippy::all)]
+mod bindings {
+    include!(env!("NVCODEC_SYS_BINDINGS"));
+}
+mod functions;
+#[cfg(test)]
+mod layout;
+mod loader;
+#[cfg(feature = "nvenc")]
+mod nvenc;
+
+pub use bindings::*;
+// Both binding modules define it.
+#[cfg(feature = "nvenc")]
+pub use bindings::__BindgenBitfieldUnit;
+pub use functions::*;
+pub use loader::*;
+#[cfg(feature = "nvenc")]
+pub use nvenc::*;
+
+/// Video Codec SDK release the bindings come from, as (major, minor). The
+/// same release is reported to build scripts as `DEP_NVCUVID_SDK_VERSION`
+/// and to this crate as cumulative `nvcodec_sdk_<major>_<minor>` cfg flags.
+pub const SDK_VERSION: (u32, u32) = include!(concat!(env!("OUT_DIR"), "/sdk_version.rs"));
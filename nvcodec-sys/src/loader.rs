@@ -0,0 +1,106 @@
+use std::fmt;
+
+/// A driver library that could not be opened.
+#[derive(Clone, Debug)]
+pub struct LoadError {
+    pub library: &'static str,
+    pub reason: String,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Could not load {}: {}", self.library, self.reason)
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Makes sure libcuda and libnvcuvid are usable. Always succeeds when they
+/// are linked.
+#[cfg(not(feature = "dynamic-loading"))]
+pub fn load() -> Result<(), LoadError> {
+    Ok(())
+}
+
+/// Opens libcuda and libnvcuvid, once per process.
+#[cfg(feature = "dynamic-loading")]
+pub fn load() -> Result<(), LoadError> {
+    cuda().map_err(Clone::clone)?;
+    nvcuvid().map_err(Clone::clone)?;
+    Ok(())
+}
+
+/// Makes sure libnvidia-encode is usable, on top of what `load` checks.
+#[cfg(feature = "nvenc")]
+pub fn load_nvenc() -> Result<(), LoadError> {
+    load()?;
+    #[cfg(feature = "dynamic-loading")]
+    nvenc().map_err(Clone::clone)?;
+    Ok(())
+}
+
+#[cfg(feature = "dynamic-loading")]
+pub use dynamic::*;
+
+#[cfg(feature = "dynamic-loading")]
+mod dynamic {
+    use super::LoadError;
+    use crate::{CudaLibrary, NvcuvidLibrary};
+    #[cfg(feature = "nvenc")]
+    use crate::NvencLibrary;
+    use std::sync::OnceLock;
+
+    #[cfg(windows)]
+    const CUDA_NAMES: &[&str] = &["nvcuda.dll"];
+    #[cfg(not(windows))]
+    const CUDA_NAMES: &[&str] = &["libcuda.so.1", "libcuda.so"];
+
+    #[cfg(windows)]
+    const NVCUVID_NAMES: &[&str] = &["nvcuvid.dll"];
+    #[cfg(not(windows))]
+    const NVCUVID_NAMES: &[&str] = &["libnvcuvid.so.1", "libnvcuvid.so"];
+
+    #[cfg(all(feature = "nvenc", windows))]
+    const NVENC_NAMES: &[&str] = &["nvEncodeAPI64.dll"];
+    #[cfg(all(feature = "nvenc", not(windows)))]
+    const NVENC_NAMES: &[&str] = &["libnvidia-encode.so.1", "libnvidia-encode.so"];
+
+    static CUDA: OnceLock<Result<CudaLibrary, LoadError>> = OnceLock::new();
+    static NVCUVID: OnceLock<Result<NvcuvidLibrary, LoadError>> = OnceLock::new();
+    #[cfg(feature = "nvenc")]
+    static NVENC: OnceLock<Result<NvencLibrary, LoadError>> = OnceLock::new();
+
+    fn open<T>(
+        names: &[&'static str],
+        open: unsafe fn(&str) -> Result<T, libloading::Error>,
+    ) -> Result<T, LoadError> {
+        // Report why the preferred name failed; the others are fallbacks.
+        let mut reason = None;
+        for name in names {
+            match unsafe { open(name) } {
+                Ok(library) => return Ok(library),
+                Err(e) => {
+                    reason.get_or_insert_with(|| e.to_string());
+                }
+            }
+        }
+
+        Err(LoadError { library: names[0], reason: reason.unwrap_or_default() })
+    }
+
+    /// The driver API.
+    pub fn cuda() -> Result<&'static CudaLibrary, &'static LoadError> {
+        CUDA.get_or_init(|| open(CUDA_NAMES, CudaLibrary::open)).as_ref()
+    }
+
+    /// The NVDEC API.
+    pub fn nvcuvid() -> Result<&'static NvcuvidLibrary, &'static LoadError> {
+        NVCUVID.get_or_init(|| open(NVCUVID_NAMES, NvcuvidLibrary::open)).as_ref()
+    }
+
+    /// The NVENC API.
+    #[cfg(feature = "nvenc")]
+    pub fn nvenc() -> Result<&'static NvencLibrary, &'static LoadError> {
+        NVENC.get_or_init(|| open(NVENC_NAMES, NvencLibrary::open)).as_ref()
+    }
+}
@@ -0,0 +1,37 @@
+//! The NVENC API from `nvEncodeAPI.h`, and the GUIDs bindgen cannot produce.
+
+#[allow(clippy::all)]
+mod bindings {
+    // Includes `NVENCAPI_VERSION` and the `*_VER` struct versions, which the
+    // build script evaluates from the header when generating.
+    include!(env!("NVCODEC_SYS_NVENC_BINDINGS"));
+}
+
+pub use bindings::*;
+
+macro_rules! guids {
+    ($($name:ident = $data1:literal, $data2:literal, $data3:literal, [$($data4:literal),*];)*) => {
+        $(pub const $name: GUID = GUID {
+            Data1: $data1,
+            Data2: $data2,
+            Data3: $data3,
+            Data4: [$($data4),*],
+        };)*
+    };
+}
+
+guids! {
+    NV_ENC_CODEC_H264_GUID = 0x6bc82762, 0x4e63, 0x4ca4, [0xaa, 0x85, 0x1e, 0x50, 0xf3, 0x21, 0xf6, 0xbf];
+    NV_ENC_CODEC_HEVC_GUID = 0x790cdc88, 0x4522, 0x4d7b, [0x94, 0x25, 0xbd, 0xa9, 0x97, 0x5f, 0x76, 0x03];
+    NV_ENC_CODEC_AV1_GUID = 0x0a352289, 0x0aa7, 0x4759, [0x86, 0x2d, 0x5d, 0x15, 0xcd, 0x16, 0xd2, 0x54];
+
+    NV_ENC_CODEC_PROFILE_AUTOSELECT_GUID = 0xbfd6f8e7, 0x233c, 0x4341, [0x8b, 0x3e, 0x48, 0x18, 0x52, 0x38, 0x03, 0xf4];
+
+    NV_ENC_PRESET_P1_GUID = 0xfc0a8d3e, 0x45f8, 0x4cf8, [0x80, 0xc7, 0x29, 0x88, 0x71, 0x59, 0x0e, 0xbf];
+    NV_ENC_PRESET_P2_GUID = 0xf581cfb8, 0x88d6, 0x4381, [0x93, 0xf0, 0xdf, 0x13, 0xf9, 0xc2, 0x7d, 0xab];
+    NV_ENC_PRESET_P3_GUID = 0x36850110, 0x3a07, 0x441f, [0x94, 0xd5, 0x36, 0x70, 0x63, 0x1f, 0x91, 0xf6];
+    NV_ENC_PRESET_P4_GUID = 0x90a7b826, 0xdf06, 0x4862, [0xb9, 0xd2, 0xcd, 0x6d, 0x73, 0xa0, 0x86, 0x81];
+    NV_ENC_PRESET_P5_GUID = 0x21c6e6b4, 0x297a, 0x4cba, [0x99, 0x8f, 0xb6, 0xcb, 0xde, 0x72, 0xad, 0xe3];
+    NV_ENC_PRESET_P6_GUID = 0x8e75c279, 0x6299, 0x4ab6, [0x83, 0x02, 0x0b, 0x21, 0x5a, 0x33, 0x5c, 0xf5];
+    NV_ENC_PRESET_P7_GUID = 0x84848c12, 0x6f71, 0x4c13, [0x93, 0x1b, 0x53, 0xe2, 0x83, 0xf5, 0x79, 0x74];
+}
@@ -0,0 +1,154 @@
+/*
+ * Prints the sizes, alignments and member offsets of the NVENC types the
+ * bundled bindings declare, as the C compiler lays them out:
+ *
+ *     NV_ENC_CONFIG size 3584
+ *     NV_ENC_CONFIG align 8
+ *     NV_ENC_CONFIG.rcParams 40
+ *
+ * Run it against the headers of the SDK release the bindings come from and
+ * check the output in next to them, where the `nvenc_header_layout` test in
+ * src/layout.rs picks it up:
+ *
+ *     cc -I "$NVIDIA_VIDEO_CODEC_INCLUDE_PATH" tools/nvenc_layout.c -o nvenc_layout
+ *     ./nvenc_layout > src/bindings/nvenc_12_2.layout
+ *
+ * Every member listed here must exist in the bundled bindings; add members
+ * the encoder starts using to both.
+ */
+#include <stddef.h>
+#include <stdio.h>
+
+#include <nvEncodeAPI.h>
+
+#define TYPE(t) printf("%s size %zu\n%s align %zu\n", #t, sizeof(t), #t, _Alignof(t))
+#define MEMBER(t, m) printf("%s.%s %zu\n", #t, #m, offsetof(t, m))
+
+int main(void)
+{
+    TYPE(GUID);
+    MEMBER(GUID, Data1);
+    MEMBER(GUID, Data2);
+    MEMBER(GUID, Data3);
+    MEMBER(GUID, Data4);
+
+    TYPE(NV_ENC_QP);
+    MEMBER(NV_ENC_QP, qpInterP);
+    MEMBER(NV_ENC_QP, qpInterB);
+    MEMBER(NV_ENC_QP, qpIntra);
+
+    TYPE(NV_ENC_RC_PARAMS);
+    MEMBER(NV_ENC_RC_PARAMS, rateControlMode);
+    MEMBER(NV_ENC_RC_PARAMS, constQP);
+    MEMBER(NV_ENC_RC_PARAMS, averageBitRate);
+    MEMBER(NV_ENC_RC_PARAMS, maxBitRate);
+    MEMBER(NV_ENC_RC_PARAMS, minQP);
+    MEMBER(NV_ENC_RC_PARAMS, temporalLayerQP);
+    MEMBER(NV_ENC_RC_PARAMS, lookaheadDepth);
+
+    TYPE(NV_ENC_CONFIG_H264);
+    MEMBER(NV_ENC_CONFIG_H264, idrPeriod);
+    MEMBER(NV_ENC_CONFIG_H264, h264VUIParameters);
+    MEMBER(NV_ENC_CONFIG_H264, chromaFormatIDC);
+
+    TYPE(NV_ENC_CONFIG_HEVC);
+    MEMBER(NV_ENC_CONFIG_HEVC, idrPeriod);
+    MEMBER(NV_ENC_CONFIG_HEVC, hevcVUIParameters);
+    MEMBER(NV_ENC_CONFIG_HEVC, outputBitDepth);
+    MEMBER(NV_ENC_CONFIG_HEVC, inputBitDepth);
+
+    TYPE(NV_ENC_CONFIG_AV1);
+    MEMBER(NV_ENC_CONFIG_AV1, idrPeriod);
+    MEMBER(NV_ENC_CONFIG_AV1, tileWidths);
+    MEMBER(NV_ENC_CONFIG_AV1, filmGrainParams);
+    MEMBER(NV_ENC_CONFIG_AV1, outputBitDepth);
+    MEMBER(NV_ENC_CONFIG_AV1, inputBitDepth);
+
+    TYPE(NV_ENC_CODEC_CONFIG);
+
+    TYPE(NV_ENC_CONFIG);
+    MEMBER(NV_ENC_CONFIG, profileGUID);
+    MEMBER(NV_ENC_CONFIG, gopLength);
+    MEMBER(NV_ENC_CONFIG, frameIntervalP);
+    MEMBER(NV_ENC_CONFIG, rcParams);
+    MEMBER(NV_ENC_CONFIG, encodeCodecConfig);
+
+    TYPE(NV_ENC_PRESET_CONFIG);
+    MEMBER(NV_ENC_PRESET_CONFIG, presetCfg);
+
+    TYPE(NV_ENC_OPEN_ENCODE_SESSION_EX_PARAMS);
+    MEMBER(NV_ENC_OPEN_ENCODE_SESSION_EX_PARAMS, deviceType);
+    MEMBER(NV_ENC_OPEN_ENCODE_SESSION_EX_PARAMS, device);
+    MEMBER(NV_ENC_OPEN_ENCODE_SESSION_EX_PARAMS, apiVersion);
+
+    TYPE(NV_ENC_INITIALIZE_PARAMS);
+    MEMBER(NV_ENC_INITIALIZE_PARAMS, encodeGUID);
+    MEMBER(NV_ENC_INITIALIZE_PARAMS, presetGUID);
+    MEMBER(NV_ENC_INITIALIZE_PARAMS, encodeWidth);
+    MEMBER(NV_ENC_INITIALIZE_PARAMS, frameRateNum);
+    MEMBER(NV_ENC_INITIALIZE_PARAMS, enablePTD);
+    MEMBER(NV_ENC_INITIALIZE_PARAMS, privData);
+    MEMBER(NV_ENC_INITIALIZE_PARAMS, encodeConfig);
+    MEMBER(NV_ENC_INITIALIZE_PARAMS, maxEncodeWidth);
+    MEMBER(NV_ENC_INITIALIZE_PARAMS, maxMEHintCountsPerBlock);
+    MEMBER(NV_ENC_INITIALIZE_PARAMS, tuningInfo);
+    MEMBER(NV_ENC_INITIALIZE_PARAMS, bufferFormat);
+
+    TYPE(NV_ENC_REGISTER_RESOURCE);
+    MEMBER(NV_ENC_REGISTER_RESOURCE, resourceType);
+    MEMBER(NV_ENC_REGISTER_RESOURCE, pitch);
+    MEMBER(NV_ENC_REGISTER_RESOURCE, resourceToRegister);
+    MEMBER(NV_ENC_REGISTER_RESOURCE, registeredResource);
+    MEMBER(NV_ENC_REGISTER_RESOURCE, bufferFormat);
+    MEMBER(NV_ENC_REGISTER_RESOURCE, bufferUsage);
+    MEMBER(NV_ENC_REGISTER_RESOURCE, pInputFencePoint);
+
+    TYPE(NV_ENC_MAP_INPUT_RESOURCE);
+    MEMBER(NV_ENC_MAP_INPUT_RESOURCE, registeredResource);
+    MEMBER(NV_ENC_MAP_INPUT_RESOURCE, mappedResource);
+    MEMBER(NV_ENC_MAP_INPUT_RESOURCE, mappedBufferFmt);
+
+    TYPE(NV_ENC_CREATE_BITSTREAM_BUFFER);
+    MEMBER(NV_ENC_CREATE_BITSTREAM_BUFFER, bitstreamBuffer);
+
+    TYPE(NV_ENC_SEQUENCE_PARAM_PAYLOAD);
+    MEMBER(NV_ENC_SEQUENCE_PARAM_PAYLOAD, inBufferSize);
+    MEMBER(NV_ENC_SEQUENCE_PARAM_PAYLOAD, spsppsBuffer);
+    MEMBER(NV_ENC_SEQUENCE_PARAM_PAYLOAD, outSPSPPSPayloadSize);
+
+    TYPE(NV_ENCODE_API_FUNCTION_LIST);
+    MEMBER(NV_ENCODE_API_FUNCTION_LIST, nvEncOpenEncodeSession);
+    MEMBER(NV_ENCODE_API_FUNCTION_LIST, nvEncInitializeEncoder);
+    MEMBER(NV_ENCODE_API_FUNCTION_LIST, nvEncEncodePicture);
+    MEMBER(NV_ENCODE_API_FUNCTION_LIST, nvEncGetSequenceParams);
+    MEMBER(NV_ENCODE_API_FUNCTION_LIST, nvEncOpenEncodeSessionEx);
+    MEMBER(NV_ENCODE_API_FUNCTION_LIST, nvEncGetLastErrorString);
+    MEMBER(NV_ENCODE_API_FUNCTION_LIST, nvEncGetEncodePresetConfigEx);
+    MEMBER(NV_ENCODE_API_FUNCTION_LIST, nvEncLookaheadPicture);
+
+    TYPE(NV_ENC_CODEC_PIC_PARAMS);
+
+    TYPE(NV_ENC_PIC_PARAMS);
+    MEMBER(NV_ENC_PIC_PARAMS, inputPitch);
+    MEMBER(NV_ENC_PIC_PARAMS, encodePicFlags);
+    MEMBER(NV_ENC_PIC_PARAMS, inputTimeStamp);
+    MEMBER(NV_ENC_PIC_PARAMS, inputBuffer);
+    MEMBER(NV_ENC_PIC_PARAMS, outputBitstream);
+    MEMBER(NV_ENC_PIC_PARAMS, bufferFmt);
+    MEMBER(NV_ENC_PIC_PARAMS, pictureStruct);
+    MEMBER(NV_ENC_PIC_PARAMS, codecPicParams);
+    MEMBER(NV_ENC_PIC_PARAMS, meHintCountsPerBlock);
+    MEMBER(NV_ENC_PIC_PARAMS, qpDeltaMap);
+    MEMBER(NV_ENC_PIC_PARAMS, alphaBuffer);
+    MEMBER(NV_ENC_PIC_PARAMS, outputReconBuffer);
+
+    TYPE(NV_ENC_LOCK_BITSTREAM);
+    MEMBER(NV_ENC_LOCK_BITSTREAM, outputBitstream);
+    MEMBER(NV_ENC_LOCK_BITSTREAM, bitstreamSizeInBytes);
+    MEMBER(NV_ENC_LOCK_BITSTREAM, outputTimeStamp);
+    MEMBER(NV_ENC_LOCK_BITSTREAM, bitstreamBufferPtr);
+    MEMBER(NV_ENC_LOCK_BITSTREAM, pictureType);
+    MEMBER(NV_ENC_LOCK_BITSTREAM, alphaLayerSizeInBytes);
+
+    return 0;
+}
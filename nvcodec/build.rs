@@ -0,0 +1,29 @@
+use std::env;
+
+fn parse_version(version: &str) -> (u32, u32) {
+    version
+        .split_once('.')
+        .and_then(|(major, minor)| Some((major.parse().ok()?, minor.parse().ok()?)))
+        .unwrap_or_else(|| panic!("Bad SDK version from nvcodec-sys: {}", version))
+}
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=DEP_NVCUVID_SDK_VERSION");
+    println!("cargo:rerun-if-env-changed=DEP_NVCUVID_KNOWN_VERSIONS");
+
+    // The Video Codec SDK release nvcodec-sys was built against, mirrored as
+    // the same cumulative `nvcodec_sdk_<major>_<minor>` cfg flags over the
+    // releases it knows.
+    let version = env::var("DEP_NVCUVID_SDK_VERSION")
+        .expect("nvcodec-sys did not report its SDK version");
+    let version = parse_version(&version);
+    let known = env::var("DEP_NVCUVID_KNOWN_VERSIONS")
+        .expect("nvcodec-sys did not report the SDK versions it knows");
+
+    for (major, minor) in known.split(',').map(parse_version) {
+        println!("cargo:rustc-check-cfg=cfg(nvcodec_sdk_{}_{})", major, minor);
+        if (major, minor) <= version {
+            println!("cargo:rustc-cfg=nvcodec_sdk_{}_{}", major, minor);
+        }
+    }
+}
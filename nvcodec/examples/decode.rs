@@ -1,9 +1,8 @@
 use clap::Parser;
-use cuda_rs::{stream::CuStream, context::CuContext, device::CuDevice};
-use futures::StreamExt;
 use nvcodec::{
     demuxer::ffmpeg::FFmpegDemuxStream,
-    decoder::NVDecoder,
+    decoder::DecodedFrame,
+    device::{DevicePolicy, DeviceSelector},
 };
 use indicatif::ProgressBar;
 use std::path::Path;
@@ -19,8 +18,7 @@ struct Args {
     output_dir: String,
 }
 
-#[tokio::main]
-async fn main() {
+fn main() {
     let args = Args::parse();
     let Args {
         input_video,
@@ -29,111 +27,59 @@ async fn main() {
 
     let output_dir = Path::new(&output_dir);
 
-    cuda_rs::init().unwrap();
+    nvcodec::cuda::init().unwrap();
 
-    let device = CuDevice::new(0).unwrap();
-    let ctx = CuContext::retain_primary_context(&device).unwrap();
-    let _guard = ctx.guard().unwrap();
-
-    let mut demuxer = FFmpegDemuxStream::new(&input_video).unwrap();
+    let demuxer = FFmpegDemuxStream::new(&input_video).unwrap();
 
     let bar = ProgressBar::new(demuxer.total_frames as u64);
 
-    let stream = CuStream::new().unwrap();
-
-    let mut decoder = NVDecoder::new(
-        &stream,
-        demuxer.codec_id.into(),
+    let selector = DeviceSelector::new().unwrap();
+    let mut decoder = selector.create_decoder(
+        DevicePolicy::MostFreeMemory,
+        demuxer.codec_id.try_into().unwrap(),
         None,
         None,
         false,
     ).unwrap();
+    let _guard = selector.context(decoder.device()).unwrap().guard().unwrap();
+    // NPP works on cuda-rs streams.
+    let stream = cuda_rs::stream::CuStream::new().unwrap();
 
     let mut i = 0;
-    loop {
-        tokio::select! {
-            res = demuxer.next() => {
-                match res {
-                    Some(res) => {
-                        match res {
-                            Ok(packet) => {
-                                decoder.decode(Some(&packet)).unwrap();
-                            },
-                            Err(e) => {
-                                eprintln!("demux error: {:?}", e);
-                                break;
-                            }
-                        }
-                    }
-                    None => {
-                        decoder.decode(None).unwrap();
-                        break;
-                    }
-                }
-            }
-            Some(res) = decoder.next() => {
-                match res {
-                    Ok(frame) => {
-                        let device_image: DeviceImage = frame.into();
-
-                        let device_image = device_image.convert_pixel_format(
-                            PixelFormat::RGB, &stream
-                        ).unwrap();
-
-                        let host_mem = device_image.mem.to_host().unwrap();
-
-                        stream.synchronize().unwrap();
-
-                        image::save_buffer(
-                            output_dir.join(format!("frame_{}.jpg", i)),
-                            host_mem.as_slice(),
-                            device_image.width as _,
-                            device_image.height as _,
-                            image::ColorType::Rgb8,
-                        ).unwrap();
-
-                        bar.inc(1);
-                        i += 1;
-                    },
-                    Err(e) => {
-                        eprintln!("decode error: {:?}", e);
-                        break;
-                    }
-                }
-            }
-            else => break,
-        }
-    }
+    let mut save = |frame: DecodedFrame| {
+        let device_image: DeviceImage = frame.try_into().unwrap();
+
+        let device_image = device_image.convert_pixel_format(
+            PixelFormat::RGB, &stream
+        ).unwrap();
 
-    while let Some(res) = decoder.next().await {
-        match res {
-            Ok(frame) => {
-                let device_image: DeviceImage = frame.into();
-
-                let device_image = device_image.convert_pixel_format(
-                    PixelFormat::RGB, &stream
-                ).unwrap();
-
-                let host_mem = device_image.mem.to_host().unwrap();
-
-                stream.synchronize().unwrap();
-
-                image::save_buffer(
-                    output_dir.join(format!("frame_{}.jpg", i)),
-                    host_mem.as_slice(),
-                    device_image.width as _,
-                    device_image.height as _,
-                    image::ColorType::Rgb8,
-                ).unwrap();
-
-                bar.inc(1);
-                i += 1;
-            },
-            Err(e) => {
-                eprintln!("decode error: {:?}", e);
-                break;
-            }
+        let host_mem = device_image.mem.to_host().unwrap();
+
+        stream.synchronize().unwrap();
+
+        image::save_buffer(
+            output_dir.join(format!("frame_{}.jpg", i)),
+            host_mem.as_slice(),
+            device_image.width as _,
+            device_image.height as _,
+            image::ColorType::Rgb8,
+        ).unwrap();
+
+        bar.inc(1);
+        i += 1;
+    };
+
+    for packet in demuxer.packets() {
+        let packet = packet.unwrap();
+        let frames = decoder.decode_blocking(&packet).unwrap_or_else(|err| panic!("Decoding failed: {}", err));
+        frames.into_iter().for_each(&mut save);
+    }
+    loop {
+        let frames = decoder.flush_blocking().unwrap_or_else(|err| panic!("Decoding failed: {}", err));
+        if frames.is_empty() {
+            break;
         }
+        frames.into_iter().for_each(&mut save);
     }
 
     bar.finish();
@@ -0,0 +1,23 @@
+//! Starts on machines without the NVIDIA driver and says whether decoding is
+//! available. Build with the driver libraries loaded at runtime and without
+//! the cuda-rs based NPP interop, which links them:
+//!
+//! cargo run --example no_driver --no-default-features --features dynamic-loading
+use nvcodec::{
+    codec::CuVideoCodecType,
+    cuda::CuStream,
+    decoder::NVDecoder,
+    error::NVCodecError,
+};
+
+fn main() {
+    // Creating a stream needs the driver; the default stream does not.
+    let stream = CuStream::null();
+
+    match NVDecoder::new(&stream, CuVideoCodecType::H264, None, None, false) {
+        Err(NVCodecError::LibraryError(err)) => println!("No NVIDIA driver: {}", err),
+        // No context is current here, which only the loaded driver can tell.
+        Err(err) => println!("NVIDIA driver found ({})", err),
+        Ok(_) => println!("NVIDIA driver found"),
+    }
+}
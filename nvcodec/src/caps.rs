@@ -0,0 +1,303 @@
+use crate::{
+    codec::CuVideoCodecType,
+    cuda::{self, CuContext, CuDevice},
+    error::{NVCodecError, NVCodecResult},
+    ffi,
+    surface::{ChromaFormat, VideoSurfaceFormat},
+};
+
+/// Surface formats NVDEC can write directly, in `nOutputFormatMask` bit order.
+const NATIVE_FORMATS: &[VideoSurfaceFormat] = &[
+    VideoSurfaceFormat::NV12,
+    VideoSurfaceFormat::P016,
+    VideoSurfaceFormat::YUV444,
+    VideoSurfaceFormat::YUV444_16Bit,
+    #[cfg(nvcodec_sdk_13_0)]
+    VideoSurfaceFormat::NV16,
+    #[cfg(nvcodec_sdk_13_0)]
+    VideoSurfaceFormat::P216,
+];
+
+const CODECS: [CuVideoCodecType; 10] = [
+    CuVideoCodecType::MPEG1,
+    CuVideoCodecType::MPEG2,
+    CuVideoCodecType::MPEG4,
+    CuVideoCodecType::VC1,
+    CuVideoCodecType::H264,
+    CuVideoCodecType::JPEG,
+    CuVideoCodecType::HEVC,
+    CuVideoCodecType::VP8,
+    CuVideoCodecType::VP9,
+    CuVideoCodecType::AV1,
+];
+
+const CHROMA_FORMATS: [ChromaFormat; 4] = [
+    ChromaFormat::Monochrome,
+    ChromaFormat::YUV420,
+    ChromaFormat::YUV422,
+    ChromaFormat::YUV444,
+];
+
+const BIT_DEPTHS: [u8; 3] = [8, 10, 12];
+
+/// What NVDEC on one device can do for a codec, chroma format and bit depth,
+/// from `cuvidGetDecoderCaps`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecoderCaps {
+    pub codec: CuVideoCodecType,
+    pub chroma_format: ChromaFormat,
+    pub bit_depth: u8,
+    pub supported: bool,
+    /// NVDEC engines on the device.
+    pub engines: u8,
+    pub min_width: u32,
+    pub min_height: u32,
+    pub max_width: u32,
+    pub max_height: u32,
+    /// Most 16x16 macroblocks per frame.
+    pub max_mb_count: u32,
+    pub output_formats: Vec<VideoSurfaceFormat>,
+    pub output_format_mask: u16,
+    pub histogram_supported: bool,
+    pub histogram_bins: u16,
+    pub histogram_counter_bit_depth: u8,
+}
+
+impl DecoderCaps {
+    fn from_raw(
+        codec: CuVideoCodecType,
+        chroma_format: ChromaFormat,
+        bit_depth: u8,
+        caps: &ffi::CUVIDDECODECAPS,
+    ) -> Self {
+        Self {
+            codec,
+            chroma_format,
+            bit_depth,
+            supported: caps.bIsSupported != 0,
+            engines: caps.nNumNVDECs,
+            min_width: caps.nMinWidth as _,
+            min_height: caps.nMinHeight as _,
+            max_width: caps.nMaxWidth,
+            max_height: caps.nMaxHeight,
+            max_mb_count: caps.nMaxMBCount,
+            output_formats: NATIVE_FORMATS
+                .iter()
+                .copied()
+                .filter(|format| format.is_supported_by(caps.nOutputFormatMask))
+                .collect(),
+            output_format_mask: caps.nOutputFormatMask,
+            histogram_supported: caps.bIsHistogramSupported != 0,
+            histogram_bins: caps.nMaxHistogramBins,
+            histogram_counter_bit_depth: caps.nCounterBitDepth,
+        }
+    }
+
+    /// A combination the driver failed to answer for.
+    fn unsupported(codec: CuVideoCodecType, chroma_format: ChromaFormat, bit_depth: u8) -> Self {
+        Self::from_raw(codec, chroma_format, bit_depth, &unsafe { std::mem::zeroed() })
+    }
+
+    /// Explains why a `width` x `height` coded stream cannot be decoded, if
+    /// it cannot.
+    pub fn check_size(&self, width: u32, height: u32) -> Result<(), String> {
+        if !self.supported {
+            return Err(format!(
+                "{:?} {:?} {}-bit not supported for this GPU",
+                self.codec,
+                self.chroma_format,
+                self.bit_depth
+            ));
+        }
+
+        if width < self.min_width || height < self.min_height {
+            return Err(format!(
+                "Resolution (wxh) {}x{} is below min resolution {}x{} for this GPU",
+                width,
+                height,
+                self.min_width,
+                self.min_height
+            ));
+        }
+
+        if width > self.max_width || height > self.max_height {
+            return Err(format!(
+                "Resolution (wxh) {}x{} is greater than max resolution {}x{} for this GPU",
+                width,
+                height,
+                self.max_width,
+                self.max_height
+            ));
+        }
+
+        let mb_count = (width >> 4) * (height >> 4);
+        if mb_count > self.max_mb_count {
+            return Err(format!(
+                "MB count {} is greater than max MB count {} for this GPU",
+                mb_count,
+                self.max_mb_count
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn supports_size(&self, width: u32, height: u32) -> bool {
+        self.check_size(width, height).is_ok()
+    }
+}
+
+/// Queries NVDEC on `device` through its primary context.
+pub fn query(
+    device: i32,
+    codec: CuVideoCodecType,
+    chroma_format: ChromaFormat,
+    bit_depth: u8,
+) -> NVCodecResult<DecoderCaps> {
+    cuda::init()?;
+
+    let cu_device = CuDevice::new(device as _)?;
+    let ctx = CuContext::retain_primary_context(&cu_device)?;
+    let _guard = ctx.guard()?;
+
+    query_current(codec, chroma_format, bit_depth)
+}
+
+/// Every codec, chroma format and bit depth combination on `device`,
+/// supported or not. A combination the driver rejects with an error counts
+/// as unsupported.
+pub fn support_matrix(device: i32) -> NVCodecResult<Vec<DecoderCaps>> {
+    cuda::init()?;
+
+    let cu_device = CuDevice::new(device as _)?;
+    let ctx = CuContext::retain_primary_context(&cu_device)?;
+    let _guard = ctx.guard()?;
+
+    let mut matrix = Vec::with_capacity(CODECS.len() * CHROMA_FORMATS.len() * BIT_DEPTHS.len());
+    for codec in CODECS {
+        for chroma_format in CHROMA_FORMATS {
+            for bit_depth in BIT_DEPTHS {
+                let caps = query_current(codec, chroma_format, bit_depth).unwrap_or_else(|err| {
+                    tracing::debug!(
+                        "Caps query for {:?} {:?} {}-bit failed: {}",
+                        codec,
+                        chroma_format,
+                        bit_depth,
+                        err
+                    );
+                    DecoderCaps::unsupported(codec, chroma_format, bit_depth)
+                });
+                matrix.push(caps);
+            }
+        }
+    }
+
+    Ok(matrix)
+}
+
+/// Like `query`, on the current context.
+pub(crate) fn query_current(
+    codec: CuVideoCodecType,
+    chroma_format: ChromaFormat,
+    bit_depth: u8,
+) -> NVCodecResult<DecoderCaps> {
+    if bit_depth < 8 {
+        return Err(NVCodecError::NotSupported(format!("Bit depth {} not supported", bit_depth)));
+    }
+
+    let mut caps: ffi::CUVIDDECODECAPS = unsafe { std::mem::zeroed() };
+    caps.eCodecType = codec as _;
+    caps.eChromaFormat = chroma_format.raw();
+    caps.nBitDepthMinus8 = (bit_depth - 8) as _;
+
+    let res = unsafe { ffi::cuvidGetDecoderCaps(&mut caps) };
+    wrap!((), res)?;
+
+    Ok(DecoderCaps::from_raw(codec, chroma_format, bit_depth, &caps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_query_is_unsupported() {
+        let caps = DecoderCaps::unsupported(CuVideoCodecType::AV1, ChromaFormat::YUV444, 12);
+
+        assert_eq!(caps.codec, CuVideoCodecType::AV1);
+        assert_eq!(caps.chroma_format, ChromaFormat::YUV444);
+        assert_eq!(caps.bit_depth, 12);
+        assert!(!caps.supported);
+        assert!(caps.output_formats.is_empty());
+        assert!(!caps.supports_size(1920, 1080));
+    }
+
+    fn raw_caps(output_format_mask: u16) -> ffi::CUVIDDECODECAPS {
+        let mut caps: ffi::CUVIDDECODECAPS = unsafe { std::mem::zeroed() };
+        caps.bIsSupported = 1;
+        caps.nNumNVDECs = 2;
+        caps.nMinWidth = 48;
+        caps.nMinHeight = 16;
+        caps.nMaxWidth = 4096;
+        caps.nMaxHeight = 2304;
+        caps.nMaxMBCount = 36864;
+        caps.nOutputFormatMask = output_format_mask;
+        caps
+    }
+
+    fn mask(formats: &[VideoSurfaceFormat]) -> u16 {
+        formats.iter().fold(0, |mask, format| mask | 1 << format.cuvid_format())
+    }
+
+    #[test]
+    fn decodes_the_output_format_mask() {
+        use VideoSurfaceFormat::*;
+
+        let cases: &[&[VideoSurfaceFormat]] = &[
+            &[],
+            &[NV12],
+            &[NV12, P016],
+            &[YUV444, YUV444_16Bit],
+            NATIVE_FORMATS,
+        ];
+        for &formats in cases {
+            let raw = raw_caps(mask(formats));
+            let caps = DecoderCaps::from_raw(CuVideoCodecType::HEVC, ChromaFormat::YUV420, 10, &raw);
+            assert_eq!(caps.output_formats, formats);
+            assert_eq!(caps.output_format_mask, raw.nOutputFormatMask);
+        }
+
+        // Bits past the formats this SDK knows are kept in the mask only.
+        let caps = DecoderCaps::from_raw(CuVideoCodecType::HEVC, ChromaFormat::YUV420, 10, &raw_caps(1 << 15 | 1));
+        assert_eq!(caps.output_formats, [NV12]);
+        assert_eq!(caps.output_format_mask, 1 << 15 | 1);
+    }
+
+    #[test]
+    fn reads_the_size_limits() {
+        let caps = DecoderCaps::from_raw(CuVideoCodecType::H264, ChromaFormat::YUV420, 8, &raw_caps(1));
+
+        assert!(caps.supported);
+        assert_eq!(caps.engines, 2);
+        assert_eq!((caps.min_width, caps.min_height), (48, 16));
+        assert_eq!((caps.max_width, caps.max_height), (4096, 2304));
+        assert_eq!(caps.max_mb_count, 36864);
+
+        assert!(caps.supports_size(48, 16));
+        assert!(caps.supports_size(4096, 2304));
+        assert!(!caps.supports_size(32, 16));
+        assert!(!caps.supports_size(4096, 2320));
+        assert!(caps.check_size(4112, 16).unwrap_err().contains("greater than max resolution"));
+    }
+
+    #[test]
+    fn checks_the_macroblock_count() {
+        let mut raw = raw_caps(1);
+        raw.nMaxMBCount = 8160;
+        let caps = DecoderCaps::from_raw(CuVideoCodecType::H264, ChromaFormat::YUV420, 8, &raw);
+
+        // 1920x1088 is 8160 macroblocks, 2048x1088 is 8704.
+        assert!(caps.supports_size(1920, 1088));
+        assert!(caps.check_size(2048, 1088).unwrap_err().contains("MB count 8704"));
+    }
+}
@@ -0,0 +1,140 @@
+use futures::stream::Stream;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// The receiving end of a channel fed by a producer thread or the parser
+/// callbacks, read as a `Stream`.
+///
+/// Yields items in the order they were sent and ends once every sender is
+/// gone and every item sent before that has been yielded.
+pub(crate) struct ChannelStream<T: 'static> {
+    receiver: flume::Receiver<T>,
+    stream: flume::r#async::RecvStream<'static, T>,
+}
+
+impl<T> ChannelStream<T> {
+    pub(crate) fn new(receiver: flume::Receiver<T>) -> Self {
+        Self {
+            stream: receiver.clone().into_stream(),
+            receiver,
+        }
+    }
+
+    /// The same channel, for blocking reads.
+    pub(crate) fn receiver(&self) -> &flume::Receiver<T> {
+        &self.receiver
+    }
+}
+
+impl<T> Stream for ChannelStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.stream).poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use futures::{executor::block_on, task::noop_waker, StreamExt};
+    use std::{
+        sync::mpsc,
+        thread,
+        time::Duration,
+    };
+
+    /// Collects `stream` on another thread, failing instead of hanging when a
+    /// wakeup is lost.
+    pub(crate) fn collect_within<S>(stream: S, timeout: Duration) -> Vec<S::Item>
+    where
+        S: Stream + Send + Unpin + 'static,
+        S::Item: Send,
+    {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(block_on(stream.collect::<Vec<_>>()));
+        });
+        rx.recv_timeout(timeout).expect("stream stalled: a wakeup was lost or it never ended")
+    }
+
+    /// Sends `0..count`, yielding or sleeping now and then so the consumer
+    /// sees both a full and an empty channel.
+    pub(crate) fn produce(tx: flume::Sender<usize>, count: usize) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            for i in 0..count {
+                tx.send(i).unwrap();
+                match i % 64 {
+                    0 => thread::sleep(Duration::from_millis(1)),
+                    _ => thread::yield_now(),
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn no_wakeup_lost_on_bounded_channel() {
+        for capacity in [1, 2, 8] {
+            let (tx, rx) = flume::bounded(capacity);
+            let producer = produce(tx, 2000);
+
+            let items = collect_within(ChannelStream::new(rx), Duration::from_secs(30));
+            producer.join().unwrap();
+            assert_eq!(items, (0..2000).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn no_wakeup_lost_on_unbounded_channel() {
+        let (tx, rx) = flume::unbounded();
+        let producer = produce(tx, 2000);
+
+        let items = collect_within(ChannelStream::new(rx), Duration::from_secs(30));
+        producer.join().unwrap();
+        assert_eq!(items, (0..2000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ends_only_once_senders_are_gone_and_drained() {
+        let (tx, rx) = flume::unbounded();
+        let mut stream = ChannelStream::new(rx);
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(Some(1)));
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(Some(2)));
+        // Empty, but the producer may still send.
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+
+        let second = tx.clone();
+        tx.send(3).unwrap();
+        drop(tx);
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(Some(3)));
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+
+        second.send(4).unwrap();
+        drop(second);
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(Some(4)));
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    #[test]
+    fn blocking_and_stream_reads_share_the_channel() {
+        let (tx, rx) = flume::unbounded();
+        let mut stream = ChannelStream::new(rx);
+        for i in 0..4 {
+            tx.send(i).unwrap();
+        }
+        drop(tx);
+
+        assert_eq!(stream.receiver().try_recv(), Ok(0));
+        assert_eq!(block_on(stream.next()), Some(1));
+        assert_eq!(stream.receiver().iter().collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(block_on(stream.next()), None);
+    }
+}
@@ -1,7 +1,11 @@
-use crate::ffi;
+use crate::{
+    error::{NVCodecError, NVCodecResult},
+    ffi,
+};
 use ffmpeg_next::codec::Id as CodecId;
+use std::fmt;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum CuVideoCodecType {
     MPEG1 = ffi::cudaVideoCodec_enum_cudaVideoCodec_MPEG1 as isize,
     MPEG2 = ffi::cudaVideoCodec_enum_cudaVideoCodec_MPEG2 as isize,
@@ -15,37 +19,166 @@ pub enum CuVideoCodecType {
     AV1 = ffi::cudaVideoCodec_enum_cudaVideoCodec_AV1 as isize,
 }
 
-impl From<CodecId> for CuVideoCodecType {
-    fn from(codec_id: CodecId) -> Self {
+impl CuVideoCodecType {
+    pub fn from_raw(codec: ffi::cudaVideoCodec) -> Option<Self> {
+        match codec {
+            ffi::cudaVideoCodec_enum_cudaVideoCodec_MPEG1 => Some(CuVideoCodecType::MPEG1),
+            ffi::cudaVideoCodec_enum_cudaVideoCodec_MPEG2 => Some(CuVideoCodecType::MPEG2),
+            ffi::cudaVideoCodec_enum_cudaVideoCodec_MPEG4 => Some(CuVideoCodecType::MPEG4),
+            ffi::cudaVideoCodec_enum_cudaVideoCodec_VC1 => Some(CuVideoCodecType::VC1),
+            ffi::cudaVideoCodec_enum_cudaVideoCodec_H264 => Some(CuVideoCodecType::H264),
+            ffi::cudaVideoCodec_enum_cudaVideoCodec_JPEG => Some(CuVideoCodecType::JPEG),
+            ffi::cudaVideoCodec_enum_cudaVideoCodec_HEVC => Some(CuVideoCodecType::HEVC),
+            ffi::cudaVideoCodec_enum_cudaVideoCodec_VP8 => Some(CuVideoCodecType::VP8),
+            ffi::cudaVideoCodec_enum_cudaVideoCodec_VP9 => Some(CuVideoCodecType::VP9),
+            ffi::cudaVideoCodec_enum_cudaVideoCodec_AV1 => Some(CuVideoCodecType::AV1),
+            _ => None,
+        }
+    }
+
+    /// Human-readable name, e.g. "H.264".
+    pub fn name(&self) -> &'static str {
+        match self {
+            CuVideoCodecType::MPEG1 => "MPEG-1",
+            CuVideoCodecType::MPEG2 => "MPEG-2",
+            CuVideoCodecType::MPEG4 => "MPEG-4 Part 2",
+            CuVideoCodecType::VC1 => "VC-1",
+            CuVideoCodecType::H264 => "H.264",
+            CuVideoCodecType::JPEG => "Motion JPEG",
+            CuVideoCodecType::HEVC => "HEVC",
+            CuVideoCodecType::VP8 => "VP8",
+            CuVideoCodecType::VP9 => "VP9",
+            CuVideoCodecType::AV1 => "AV1",
+        }
+    }
+
+    /// The usual FourCC, as found in MP4/Matroska sample entries.
+    pub fn fourcc(&self) -> [u8; 4] {
+        match self {
+            CuVideoCodecType::MPEG1 => *b"mp1v",
+            CuVideoCodecType::MPEG2 => *b"mp2v",
+            CuVideoCodecType::MPEG4 => *b"mp4v",
+            CuVideoCodecType::VC1 => *b"WVC1",
+            CuVideoCodecType::H264 => *b"avc1",
+            CuVideoCodecType::JPEG => *b"MJPG",
+            CuVideoCodecType::HEVC => *b"hvc1",
+            CuVideoCodecType::VP8 => *b"VP80",
+            CuVideoCodecType::VP9 => *b"vp09",
+            CuVideoCodecType::AV1 => *b"av01",
+        }
+    }
+
+    /// Recognises common FourCCs and their aliases, case-insensitively.
+    pub fn from_fourcc(fourcc: [u8; 4]) -> Option<Self> {
+        let mut fourcc = fourcc;
+        fourcc.make_ascii_lowercase();
+        match &fourcc {
+            b"mp1v" | b"mpg1" => Some(CuVideoCodecType::MPEG1),
+            b"mp2v" | b"mpg2" | b"hdv2" => Some(CuVideoCodecType::MPEG2),
+            b"mp4v" | b"xvid" | b"divx" | b"fmp4" => Some(CuVideoCodecType::MPEG4),
+            b"wvc1" | b"vc-1" => Some(CuVideoCodecType::VC1),
+            b"avc1" | b"avc3" | b"h264" | b"x264" => Some(CuVideoCodecType::H264),
+            b"mjpg" | b"jpeg" | b"avrn" => Some(CuVideoCodecType::JPEG),
+            b"hvc1" | b"hev1" | b"hevc" | b"h265" => Some(CuVideoCodecType::HEVC),
+            b"vp80" => Some(CuVideoCodecType::VP8),
+            b"vp09" | b"vp90" => Some(CuVideoCodecType::VP9),
+            b"av01" => Some(CuVideoCodecType::AV1),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<CodecId> for CuVideoCodecType {
+    type Error = NVCodecError;
+
+    fn try_from(codec_id: CodecId) -> NVCodecResult<Self> {
         match codec_id {
-            CodecId::MPEG1VIDEO => CuVideoCodecType::MPEG1,
-            CodecId::MPEG2VIDEO => CuVideoCodecType::MPEG2,
-            CodecId::MPEG4 => CuVideoCodecType::MPEG4,
-            CodecId::VC1 => CuVideoCodecType::VC1,
-            CodecId::H264 => CuVideoCodecType::H264,
-            CodecId::JPEG2000 => CuVideoCodecType::JPEG,
-            CodecId::HEVC => CuVideoCodecType::HEVC,
-            CodecId::VP8 => CuVideoCodecType::VP8,
-            CodecId::VP9 => CuVideoCodecType::VP9,
-            CodecId::AV1 => CuVideoCodecType::AV1,
-            _ => panic!("unsupported codec id: {:?}", codec_id),
+            CodecId::MPEG1VIDEO => Ok(CuVideoCodecType::MPEG1),
+            CodecId::MPEG2VIDEO => Ok(CuVideoCodecType::MPEG2),
+            CodecId::MPEG4 => Ok(CuVideoCodecType::MPEG4),
+            CodecId::VC1 => Ok(CuVideoCodecType::VC1),
+            CodecId::H264 => Ok(CuVideoCodecType::H264),
+            // cudaVideoCodec_JPEG is baseline motion JPEG; NVDEC has no JPEG 2000.
+            CodecId::MJPEG => Ok(CuVideoCodecType::JPEG),
+            CodecId::HEVC => Ok(CuVideoCodecType::HEVC),
+            CodecId::VP8 => Ok(CuVideoCodecType::VP8),
+            CodecId::VP9 => Ok(CuVideoCodecType::VP9),
+            CodecId::AV1 => Ok(CuVideoCodecType::AV1),
+            _ => Err(NVCodecError::NotSupported(format!("Codec {:?} not supported by NVDEC", codec_id))),
+        }
+    }
+}
+
+impl From<CuVideoCodecType> for CodecId {
+    fn from(codec_type: CuVideoCodecType) -> Self {
+        match codec_type {
+            CuVideoCodecType::MPEG1 => CodecId::MPEG1VIDEO,
+            CuVideoCodecType::MPEG2 => CodecId::MPEG2VIDEO,
+            CuVideoCodecType::MPEG4 => CodecId::MPEG4,
+            CuVideoCodecType::VC1 => CodecId::VC1,
+            CuVideoCodecType::H264 => CodecId::H264,
+            CuVideoCodecType::JPEG => CodecId::MJPEG,
+            CuVideoCodecType::HEVC => CodecId::HEVC,
+            CuVideoCodecType::VP8 => CodecId::VP8,
+            CuVideoCodecType::VP9 => CodecId::VP9,
+            CuVideoCodecType::AV1 => CodecId::AV1,
         }
     }
 }
 
-// impl Into<CodecId> for CuVideoCodecType {
-//     fn into(self) -> CodecId {
-//         match self {
-//             CuVideoCodecType::MPEG1 => CodecId::MPEG1VIDEO,
-//             CuVideoCodecType::MPEG2 => CodecId::MPEG2VIDEO,
-//             CuVideoCodecType::MPEG4 => CodecId::MPEG4,
-//             CuVideoCodecType::VC1 => CodecId::VC1,
-//             CuVideoCodecType::H264 => CodecId::H264,
-//             CuVideoCodecType::JPEG => CodecId::JPEG2000,
-//             CuVideoCodecType::HEVC => CodecId::HEVC,
-//             CuVideoCodecType::VP8 => CodecId::VP8,
-//             CuVideoCodecType::VP9 => CodecId::VP9,
-//             CuVideoCodecType::AV1 => CodecId::AV1,
-//         }
-//     }
-// }
+impl fmt::Display for CuVideoCodecType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn motion_jpeg_decodes_as_jpeg() {
+        assert_eq!(CuVideoCodecType::try_from(CodecId::MJPEG).unwrap(), CuVideoCodecType::JPEG);
+        assert_eq!(CodecId::from(CuVideoCodecType::JPEG), CodecId::MJPEG);
+    }
+
+    const TABLE: [(CuVideoCodecType, &[u8; 4], &str, CodecId); 10] = [
+        (CuVideoCodecType::MPEG1, b"mp1v", "MPEG-1", CodecId::MPEG1VIDEO),
+        (CuVideoCodecType::MPEG2, b"mp2v", "MPEG-2", CodecId::MPEG2VIDEO),
+        (CuVideoCodecType::MPEG4, b"mp4v", "MPEG-4 Part 2", CodecId::MPEG4),
+        (CuVideoCodecType::VC1, b"WVC1", "VC-1", CodecId::VC1),
+        (CuVideoCodecType::H264, b"avc1", "H.264", CodecId::H264),
+        (CuVideoCodecType::JPEG, b"MJPG", "Motion JPEG", CodecId::MJPEG),
+        (CuVideoCodecType::HEVC, b"hvc1", "HEVC", CodecId::HEVC),
+        (CuVideoCodecType::VP8, b"VP80", "VP8", CodecId::VP8),
+        (CuVideoCodecType::VP9, b"vp09", "VP9", CodecId::VP9),
+        (CuVideoCodecType::AV1, b"av01", "AV1", CodecId::AV1),
+    ];
+
+    #[test]
+    fn every_codec_round_trips() {
+        for (codec, fourcc, name, codec_id) in TABLE {
+            assert_eq!(codec.fourcc(), *fourcc);
+            assert_eq!(CuVideoCodecType::from_fourcc(*fourcc), Some(codec));
+            let mut upper = *fourcc;
+            upper.make_ascii_uppercase();
+            assert_eq!(CuVideoCodecType::from_fourcc(upper), Some(codec));
+            assert_eq!(codec.name(), name);
+            assert_eq!(codec.to_string(), name);
+            assert_eq!(CuVideoCodecType::from_raw(codec as _), Some(codec));
+            assert_eq!(CuVideoCodecType::try_from(codec_id).unwrap(), codec);
+            assert_eq!(CodecId::from(codec), codec_id);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_fourccs() {
+        assert_eq!(CuVideoCodecType::from_fourcc(*b"apch"), None);
+    }
+
+    #[test]
+    fn rejects_codecs_nvdec_lacks() {
+        let err = CuVideoCodecType::try_from(CodecId::JPEG2000).unwrap_err();
+        assert!(matches!(err, NVCodecError::NotSupported(_)));
+    }
+}
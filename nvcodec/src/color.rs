@@ -0,0 +1,240 @@
+use ffmpeg_next::util::color::{Primaries, Range, Space, TransferCharacteristic};
+
+/// How the samples of a frame map to colors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ColorDescription {
+    pub space: Space,
+    pub range: Range,
+    pub primaries: Primaries,
+    pub transfer: TransferCharacteristic,
+}
+
+impl Default for ColorDescription {
+    fn default() -> Self {
+        Self {
+            space: Space::Unspecified,
+            range: Range::Unspecified,
+            primaries: Primaries::Unspecified,
+            transfer: TransferCharacteristic::Unspecified,
+        }
+    }
+}
+
+impl ColorDescription {
+    /// Builds a description from the ISO/IEC 23091-2 code points signalled in
+    /// a bitstream (H.264/HEVC VUI, AV1 color config, ...).
+    ///
+    /// All-zero code points are what the parser reports when the bitstream
+    /// carries no description, so they leave space, primaries and transfer
+    /// unspecified. The range is decided on its own: a cleared flag is also
+    /// what an absent one looks like, so only full range is taken as
+    /// signalled and limited range is left for the container to tell.
+    pub fn from_code_points(
+        matrix_coefficients: u8,
+        color_primaries: u8,
+        transfer_characteristics: u8,
+        full_range: bool,
+    ) -> Self {
+        let range = if full_range { Range::JPEG } else { Range::Unspecified };
+        if matrix_coefficients == 0 && color_primaries == 0 && transfer_characteristics == 0 {
+            return Self { range, ..Self::default() };
+        }
+
+        Self {
+            space: space_from_code_point(matrix_coefficients),
+            range,
+            primaries: primaries_from_code_point(color_primaries),
+            transfer: transfer_from_code_point(transfer_characteristics),
+        }
+    }
+
+    /// The description a conversion to RGB should use for a frame of
+    /// `height` rows: an unspecified matrix is BT.709 from 720 rows up and
+    /// BT.601 below, and an unspecified range is limited.
+    pub fn for_conversion(self, height: usize) -> Self {
+        let space = match self.space {
+            Space::Unspecified | Space::Reserved if height >= 720 => Space::BT709,
+            Space::Unspecified | Space::Reserved => Space::SMPTE170M,
+            space => space,
+        };
+        let range = match self.range {
+            Range::Unspecified => Range::MPEG,
+            range => range,
+        };
+        Self { space, range, ..self }
+    }
+
+    /// Fills every unspecified field from `fallback`.
+    pub fn or(self, fallback: ColorDescription) -> Self {
+        Self {
+            space: if self.space == Space::Unspecified { fallback.space } else { self.space },
+            range: if self.range == Range::Unspecified { fallback.range } else { self.range },
+            primaries: if self.primaries == Primaries::Unspecified {
+                fallback.primaries
+            } else {
+                self.primaries
+            },
+            transfer: if self.transfer == TransferCharacteristic::Unspecified {
+                fallback.transfer
+            } else {
+                self.transfer
+            },
+        }
+    }
+}
+
+fn space_from_code_point(code_point: u8) -> Space {
+    match code_point {
+        0 => Space::RGB,
+        1 => Space::BT709,
+        4 => Space::FCC,
+        5 => Space::BT470BG,
+        6 => Space::SMPTE170M,
+        7 => Space::SMPTE240M,
+        8 => Space::YCGCO,
+        9 => Space::BT2020NCL,
+        10 => Space::BT2020CL,
+        11 => Space::SMPTE2085,
+        12 => Space::ChromaDerivedNCL,
+        13 => Space::ChromaDerivedCL,
+        14 => Space::ICTCP,
+        _ => Space::Unspecified,
+    }
+}
+
+fn primaries_from_code_point(code_point: u8) -> Primaries {
+    match code_point {
+        1 => Primaries::BT709,
+        4 => Primaries::BT470M,
+        5 => Primaries::BT470BG,
+        6 => Primaries::SMPTE170M,
+        7 => Primaries::SMPTE240M,
+        8 => Primaries::Film,
+        9 => Primaries::BT2020,
+        10 => Primaries::SMPTE428,
+        11 => Primaries::SMPTE431,
+        12 => Primaries::SMPTE432,
+        _ => Primaries::Unspecified,
+    }
+}
+
+fn transfer_from_code_point(code_point: u8) -> TransferCharacteristic {
+    match code_point {
+        1 => TransferCharacteristic::BT709,
+        4 => TransferCharacteristic::GAMMA22,
+        5 => TransferCharacteristic::GAMMA28,
+        6 => TransferCharacteristic::SMPTE170M,
+        7 => TransferCharacteristic::SMPTE240M,
+        8 => TransferCharacteristic::Linear,
+        9 => TransferCharacteristic::Log,
+        10 => TransferCharacteristic::LogSqrt,
+        11 => TransferCharacteristic::IEC61966_2_4,
+        12 => TransferCharacteristic::BT1361_ECG,
+        13 => TransferCharacteristic::IEC61966_2_1,
+        14 => TransferCharacteristic::BT2020_10,
+        15 => TransferCharacteristic::BT2020_12,
+        16 => TransferCharacteristic::SMPTE2084,
+        17 => TransferCharacteristic::SMPTE428,
+        18 => TransferCharacteristic::ARIB_STD_B67,
+        _ => TransferCharacteristic::Unspecified,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(space: Space, range: Range) -> ColorDescription {
+        ColorDescription { space, range, ..Default::default() }
+    }
+
+    #[test]
+    fn no_description_in_bitstream() {
+        assert_eq!(ColorDescription::from_code_points(0, 0, 0, false), ColorDescription::default());
+    }
+
+    #[test]
+    fn full_range_without_code_points() {
+        let color = ColorDescription::from_code_points(0, 0, 0, true);
+        assert_eq!(color, ColorDescription { range: Range::JPEG, ..Default::default() });
+    }
+
+    #[test]
+    fn code_points_leave_limited_range_unspecified() {
+        let color = ColorDescription::from_code_points(1, 1, 1, false);
+        assert_eq!(color, ColorDescription {
+            space: Space::BT709,
+            range: Range::Unspecified,
+            primaries: Primaries::BT709,
+            transfer: TransferCharacteristic::BT709,
+        });
+    }
+
+    #[test]
+    fn hdr10_full_range() {
+        let color = ColorDescription::from_code_points(9, 9, 16, true);
+        assert_eq!(color, ColorDescription {
+            space: Space::BT2020NCL,
+            range: Range::JPEG,
+            primaries: Primaries::BT2020,
+            transfer: TransferCharacteristic::SMPTE2084,
+        });
+    }
+
+    #[test]
+    fn unknown_code_points_are_unspecified() {
+        let color = ColorDescription::from_code_points(2, 2, 2, false);
+        assert_eq!(color, ColorDescription::default());
+
+        let color = ColorDescription::from_code_points(6, 200, 2, false);
+        assert_eq!(color.space, Space::SMPTE170M);
+        assert_eq!(color.primaries, Primaries::Unspecified);
+    }
+
+    #[test]
+    fn override_then_bitstream_then_container() {
+        let override_ = ColorDescription { range: Range::MPEG, ..Default::default() };
+        let bitstream = ColorDescription::from_code_points(1, 0, 0, true);
+        let container = container(Space::BT470BG, Range::JPEG);
+
+        let color = override_.or(bitstream).or(container);
+        assert_eq!(color.space, Space::BT709);
+        assert_eq!(color.range, Range::MPEG);
+
+        let color = ColorDescription::default().or(bitstream).or(container);
+        assert_eq!(color.space, Space::BT709);
+        assert_eq!(color.range, Range::JPEG);
+    }
+
+    #[test]
+    fn container_range_kept_when_bitstream_flag_is_clear() {
+        let bitstream = ColorDescription::from_code_points(1, 1, 1, false);
+
+        let color = ColorDescription::default().or(bitstream).or(container(Space::Unspecified, Range::JPEG));
+        assert_eq!(color.range, Range::JPEG);
+        assert_eq!(color.space, Space::BT709);
+
+        let color = ColorDescription::default().or(bitstream).or(ColorDescription::default());
+        assert_eq!(color.range, Range::Unspecified);
+    }
+
+    #[test]
+    fn conversion_fills_matrix_by_height_and_limited_range() {
+        let color = ColorDescription::default().for_conversion(1080);
+        assert_eq!((color.space, color.range), (Space::BT709, Range::MPEG));
+
+        let color = ColorDescription::default().for_conversion(576);
+        assert_eq!((color.space, color.range), (Space::SMPTE170M, Range::MPEG));
+
+        let signalled = container(Space::BT2020NCL, Range::JPEG);
+        assert_eq!(signalled.for_conversion(480), signalled);
+    }
+
+    #[test]
+    fn bitstream_full_range_beats_container() {
+        let bitstream = ColorDescription::from_code_points(0, 0, 0, true);
+        let color = bitstream.or(container(Space::BT709, Range::MPEG));
+        assert_eq!(color.range, Range::JPEG);
+        assert_eq!(color.space, Space::BT709);
+    }
+}
@@ -0,0 +1,425 @@
+use crate::{
+    color::ColorDescription,
+    error::{NVCodecError, NVCodecResult},
+    host::{plane_layouts, HostFrame, HostLayout},
+    surface::{PlaneLayout, VideoSurfaceFormat},
+};
+use ffmpeg_next::util::color::{Range, Space};
+
+/// Channel order of a converted image.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RgbFormat {
+    RGB,
+    BGR,
+    RGBA,
+}
+
+impl RgbFormat {
+    pub fn channels(&self) -> usize {
+        match self {
+            RgbFormat::RGB | RgbFormat::BGR => 3,
+            RgbFormat::RGBA => 4,
+        }
+    }
+}
+
+/// A YUV image in host memory, laid out like a downloaded `HostFrame`.
+///
+/// Can be built by hand from raw bytes, so conversions can be checked
+/// without a GPU.
+#[derive(Clone, Copy)]
+pub struct YuvImage<'a> {
+    pub data: &'a [u8],
+    pub layout: HostLayout,
+    pub surface_format: VideoSurfaceFormat,
+    pub color: ColorDescription,
+    pub width: usize,
+    pub height: usize,
+    /// Bytes between the starts of consecutive luma rows.
+    pub pitch: usize,
+}
+
+impl<'a> From<&'a HostFrame> for YuvImage<'a> {
+    fn from(frame: &'a HostFrame) -> Self {
+        Self {
+            data: &frame.data,
+            layout: frame.layout,
+            surface_format: frame.surface_format,
+            color: frame.color,
+            width: frame.width,
+            height: frame.height,
+            pitch: frame.pitch,
+        }
+    }
+}
+
+/// Reference YUV to 8-bit RGB conversion on the CPU.
+///
+/// Selects the matrix and range from `image.color` with
+/// `ColorDescription::for_conversion`, as the conversion to an NPP
+/// `DeviceImage` does, for validating GPU output offline.
+pub fn to_rgb8(image: &YuvImage, format: RgbFormat) -> NVCodecResult<Vec<u8>> {
+    convert(image, format, |v| (v * 255.).round().clamp(0., 255.) as u8, u8::MAX)
+}
+
+/// Like `to_rgb8`, with channels as `f32` in `[0, 1]`.
+pub fn to_rgb_f32(image: &YuvImage, format: RgbFormat) -> NVCodecResult<Vec<f32>> {
+    convert(image, format, |v| v.clamp(0., 1.), 1.)
+}
+
+/// One component of a plane.
+struct Component {
+    offset: usize,
+    pitch: usize,
+    /// Bytes between horizontally adjacent samples.
+    step: usize,
+    subsampling: (usize, usize),
+}
+
+impl Component {
+    fn new(plane: &PlaneLayout, index: usize) -> Self {
+        Self {
+            offset: plane.offset + index * plane.bytes_per_sample,
+            pitch: plane.pitch,
+            step: plane.bytes_per_sample * plane.components,
+            subsampling: (plane.subsampling.0 as _, plane.subsampling.1 as _),
+        }
+    }
+
+    fn sample(&self, data: &[u8], bytes_per_sample: usize, col: usize, row: usize) -> f32 {
+        let i = self.offset +
+            row / self.subsampling.1 * self.pitch +
+            col / self.subsampling.0 * self.step;
+        match bytes_per_sample {
+            1 => data[i] as f32,
+            _ => u16::from_le_bytes([data[i], data[i + 1]]) as f32,
+        }
+    }
+}
+
+/// Kr and Kb of the matrix for `space`, BT.709 for those without one.
+fn coefficients(space: Space) -> (f32, f32) {
+    match space {
+        Space::BT470BG | Space::SMPTE170M => (0.299, 0.114),
+        Space::BT2020NCL | Space::BT2020CL => (0.2627, 0.0593),
+        Space::SMPTE240M => (0.212, 0.087),
+        Space::FCC => (0.30, 0.11),
+        _ => (0.2126, 0.0722),
+    }
+}
+
+fn convert<T: Copy>(
+    image: &YuvImage,
+    format: RgbFormat,
+    scale: impl Fn(f32) -> T,
+    opaque: T,
+) -> NVCodecResult<Vec<T>> {
+    let (width, height) = (image.width, image.height);
+    let bytes_per_sample = image.surface_format.bytes_per_sample() as usize;
+    if width == 0 || height == 0 {
+        return Ok(Vec::new());
+    }
+
+    let planes = plane_layouts(image.layout, image.surface_format, width, height, image.pitch);
+    let end = planes.iter().map(|plane| plane.offset + plane.size()).max().unwrap_or(0);
+    if image.data.len() < end {
+        return Err(NVCodecError::SurfaceShapeMismatch);
+    }
+
+    let luma = Component::new(&planes[0], 0);
+    let chroma = match planes.len() {
+        1 => None,
+        2 => Some((Component::new(&planes[1], 0), Component::new(&planes[1], 1))),
+        _ => Some((Component::new(&planes[1], 0), Component::new(&planes[2], 0))),
+    };
+
+    // Samples of 16-bit surfaces are MSB aligned, so the 8-bit levels scale by 256.
+    let bits = bytes_per_sample as u32 * 8;
+    let max = ((1u32 << bits) - 1) as f32;
+    let unit = (1u32 << (bits - 8)) as f32;
+    let color = image.color.for_conversion(height);
+    let (y_offset, y_scale, c_offset, c_scale) = match color.range {
+        Range::JPEG => (0., max, 128. * unit, max),
+        _ => (16. * unit, 219. * unit, 128. * unit, 224. * unit),
+    };
+    let (kr, kb) = coefficients(color.space);
+    let kg = 1. - kr - kb;
+
+    let channels = format.channels();
+    let mut out = Vec::with_capacity(width * height * channels);
+    for row in 0..height {
+        for col in 0..width {
+            let y = (luma.sample(image.data, bytes_per_sample, col, row) - y_offset) / y_scale;
+            let (cb, cr) = match chroma {
+                Some((ref u, ref v)) => (
+                    (u.sample(image.data, bytes_per_sample, col, row) - c_offset) / c_scale,
+                    (v.sample(image.data, bytes_per_sample, col, row) - c_offset) / c_scale,
+                ),
+                None => (0., 0.),
+            };
+
+            let r = y + 2. * (1. - kr) * cr;
+            let b = y + 2. * (1. - kb) * cb;
+            let g = (y - kr * r - kb * b) / kg;
+
+            match format {
+                RgbFormat::RGB => out.extend_from_slice(&[scale(r), scale(g), scale(b)]),
+                RgbFormat::BGR => out.extend_from_slice(&[scale(b), scale(g), scale(r)]),
+                RgbFormat::RGBA => out.extend_from_slice(&[scale(r), scale(g), scale(b), opaque]),
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: usize = 4;
+    const HEIGHT: usize = 4;
+
+    /// A frame of one color: `yuv` at the surface's sample size, each row
+    /// padded by `padding` samples.
+    fn solid(
+        surface_format: VideoSurfaceFormat,
+        layout: HostLayout,
+        height: usize,
+        padding: usize,
+        (y, u, v): (u16, u16, u16),
+    ) -> (Vec<u8>, usize) {
+        let bytes_per_sample = surface_format.bytes_per_sample() as usize;
+        let pitch = (WIDTH + padding) * bytes_per_sample;
+        let planes = plane_layouts(layout, surface_format, WIDTH, height, pitch);
+        let end = planes.iter().map(|plane| plane.offset + plane.size()).max().unwrap();
+
+        let mut data = vec![0xa5; end];
+        for (i, plane) in planes.iter().enumerate() {
+            let values: &[u16] = match (i, plane.components) {
+                (0, _) => &[y],
+                (_, 2) => &[u, v],
+                (1, _) => &[u],
+                _ => &[v],
+            };
+            for row in 0..plane.height {
+                for col in 0..plane.width {
+                    for (component, value) in values.iter().enumerate() {
+                        let at = plane.offset +
+                            row * plane.pitch +
+                            (col * plane.components + component) * bytes_per_sample;
+                        data[at..at + bytes_per_sample].copy_from_slice(&value.to_le_bytes()[..bytes_per_sample]);
+                    }
+                }
+            }
+        }
+        (data, pitch)
+    }
+
+    fn convert_solid(
+        surface_format: VideoSurfaceFormat,
+        layout: HostLayout,
+        space: Space,
+        range: Range,
+        height: usize,
+        yuv: (u16, u16, u16),
+    ) -> [u8; 3] {
+        let padding = if layout == HostLayout::Pitched { 3 } else { 0 };
+        let (data, pitch) = solid(surface_format, layout, height, padding, yuv);
+        let image = YuvImage {
+            data: &data,
+            layout,
+            surface_format,
+            color: ColorDescription { space, range, ..Default::default() },
+            width: WIDTH,
+            height,
+            pitch,
+        };
+
+        let rgb = to_rgb8(&image, RgbFormat::RGB).unwrap();
+        assert_eq!(rgb.len(), WIDTH * height * 3);
+        assert!(rgb.chunks(3).all(|pixel| pixel == &rgb[..3]), "{:?}", rgb);
+        [rgb[0], rgb[1], rgb[2]]
+    }
+
+    fn assert_close(actual: [u8; 3], expected: [u8; 3]) {
+        let close = actual.iter().zip(expected).all(|(&a, e)| a.abs_diff(e) <= 2);
+        assert!(close, "{:?} is not {:?}", actual, expected);
+    }
+
+    /// 8-bit code values, with the color they encode.
+    type Case = ((u16, u16, u16), [u8; 3]);
+
+    // From the equations of each standard.
+    const BT601_LIMITED: [Case; 5] = [
+        ((81, 90, 240), [255, 0, 0]),
+        ((145, 54, 34), [0, 255, 0]),
+        ((41, 240, 110), [0, 0, 255]),
+        ((235, 128, 128), [255, 255, 255]),
+        ((16, 128, 128), [0, 0, 0]),
+    ];
+    const BT709_LIMITED: [Case; 3] = [
+        ((63, 102, 240), [255, 0, 0]),
+        ((173, 42, 26), [0, 255, 0]),
+        ((32, 240, 118), [0, 0, 255]),
+    ];
+    const BT709_FULL: [Case; 3] = [
+        ((54, 99, 255), [255, 0, 0]),
+        ((255, 128, 128), [255, 255, 255]),
+        ((0, 128, 128), [0, 0, 0]),
+    ];
+    const BT2020_LIMITED: [Case; 2] = [
+        ((74, 97, 240), [255, 0, 0]),
+        ((29, 240, 119), [0, 0, 255]),
+    ];
+
+    const LAYOUTS_8BIT: [(VideoSurfaceFormat, HostLayout); 5] = [
+        (VideoSurfaceFormat::NV12, HostLayout::Pitched),
+        (VideoSurfaceFormat::NV12, HostLayout::Packed),
+        (VideoSurfaceFormat::NV12, HostLayout::Planar),
+        (VideoSurfaceFormat::YUV444, HostLayout::Pitched),
+        (VideoSurfaceFormat::YUV444, HostLayout::Planar),
+    ];
+    const LAYOUTS_16BIT: [(VideoSurfaceFormat, HostLayout); 4] = [
+        (VideoSurfaceFormat::P016, HostLayout::Pitched),
+        (VideoSurfaceFormat::P016, HostLayout::Planar),
+        (VideoSurfaceFormat::YUV444_16Bit, HostLayout::Packed),
+        (VideoSurfaceFormat::YUV444_16Bit, HostLayout::Planar),
+    ];
+
+    /// `yuv` as MSB-aligned 10-bit samples of a 16-bit surface.
+    fn ten_bit((y, u, v): (u16, u16, u16)) -> (u16, u16, u16) {
+        (y << 6, u << 6, v << 6)
+    }
+
+    fn check(space: Space, range: Range, cases: &[Case]) {
+        for &(format, layout) in &LAYOUTS_8BIT {
+            for &(yuv, expected) in cases {
+                assert_close(convert_solid(format, layout, space, range, HEIGHT, yuv), expected);
+            }
+        }
+        // The same colors at 10 bits, as P010 and friends store them.
+        for &(format, layout) in &LAYOUTS_16BIT {
+            for &((y, u, v), expected) in cases {
+                let yuv = ten_bit((y * 4, u * 4, v * 4));
+                assert_close(convert_solid(format, layout, space, range, HEIGHT, yuv), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn bt601_limited() {
+        check(Space::SMPTE170M, Range::MPEG, &BT601_LIMITED);
+        check(Space::BT470BG, Range::MPEG, &BT601_LIMITED);
+    }
+
+    #[test]
+    fn bt709_limited() {
+        check(Space::BT709, Range::MPEG, &BT709_LIMITED);
+    }
+
+    #[test]
+    fn bt709_full() {
+        for &(format, layout) in &LAYOUTS_8BIT {
+            for &(yuv, expected) in &BT709_FULL {
+                assert_close(convert_solid(format, layout, Space::BT709, Range::JPEG, HEIGHT, yuv), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn bt2020_limited() {
+        check(Space::BT2020NCL, Range::MPEG, &BT2020_LIMITED);
+    }
+
+    #[test]
+    fn bt2020_10bit_limited() {
+        // ITU-R BT.2020 red at 10 bits.
+        let yuv = ten_bit((294, 387, 960));
+        for &(format, layout) in &LAYOUTS_16BIT {
+            assert_close(convert_solid(format, layout, Space::BT2020NCL, Range::MPEG, HEIGHT, yuv), [255, 0, 0]);
+        }
+    }
+
+    #[test]
+    fn sixteen_bit_full() {
+        let cases = [
+            ((65535, 32768, 32768), [255, 255, 255]),
+            ((0, 32768, 32768), [0, 0, 0]),
+            ((19595, 21710, 65535), [255, 0, 0]),
+        ];
+        for &(format, layout) in &LAYOUTS_16BIT {
+            for &(yuv, expected) in &cases {
+                assert_close(convert_solid(format, layout, Space::BT470BG, Range::JPEG, HEIGHT, yuv), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn unspecified_range_is_limited() {
+        let yuv = (63, 102, 240);
+        let unspecified = convert_solid(VideoSurfaceFormat::NV12, HostLayout::Packed, Space::BT709, Range::Unspecified, HEIGHT, yuv);
+        let limited = convert_solid(VideoSurfaceFormat::NV12, HostLayout::Packed, Space::BT709, Range::MPEG, HEIGHT, yuv);
+        assert_eq!(unspecified, limited);
+    }
+
+    #[test]
+    fn unspecified_matrix_follows_height() {
+        let yuv = (63, 102, 240);
+        let convert = |space, height| {
+            convert_solid(VideoSurfaceFormat::NV12, HostLayout::Packed, space, Range::MPEG, height, yuv)
+        };
+
+        assert_eq!(convert(Space::Unspecified, 720), convert(Space::BT709, 720));
+        assert_eq!(convert(Space::Unspecified, 480), convert(Space::SMPTE170M, 480));
+        assert_ne!(convert(Space::Unspecified, 480), convert(Space::BT709, 480));
+    }
+
+    #[test]
+    fn luma_only() {
+        let convert = |format, yuv| {
+            convert_solid(format, HostLayout::Packed, Space::BT709, Range::MPEG, HEIGHT, yuv)
+        };
+
+        assert_close(convert(VideoSurfaceFormat::Y8, (235, 0, 0)), [255, 255, 255]);
+        assert_close(convert(VideoSurfaceFormat::Y16, (126 << 8, 0, 0)), [128, 128, 128]);
+    }
+
+    #[test]
+    fn channel_orders() {
+        let (data, pitch) = solid(VideoSurfaceFormat::NV12, HostLayout::Packed, HEIGHT, 0, (63, 102, 240));
+        let image = YuvImage {
+            data: &data,
+            layout: HostLayout::Packed,
+            surface_format: VideoSurfaceFormat::NV12,
+            color: ColorDescription { space: Space::BT709, range: Range::MPEG, ..Default::default() },
+            width: WIDTH,
+            height: HEIGHT,
+            pitch,
+        };
+
+        let rgb = to_rgb8(&image, RgbFormat::RGB).unwrap();
+        let bgr = to_rgb8(&image, RgbFormat::BGR).unwrap();
+        let rgba = to_rgb8(&image, RgbFormat::RGBA).unwrap();
+        assert_eq!(bgr[..3], [rgb[2], rgb[1], rgb[0]]);
+        assert_eq!(rgba[..4], [rgb[0], rgb[1], rgb[2], 255]);
+
+        let float = to_rgb_f32(&image, RgbFormat::RGB).unwrap();
+        assert!((float[0] - rgb[0] as f32 / 255.).abs() < 1. / 255.);
+    }
+
+    #[test]
+    fn short_buffer() {
+        let (data, pitch) = solid(VideoSurfaceFormat::NV12, HostLayout::Packed, HEIGHT, 0, (16, 128, 128));
+        let image = YuvImage {
+            data: &data[..data.len() - 1],
+            layout: HostLayout::Packed,
+            surface_format: VideoSurfaceFormat::NV12,
+            color: ColorDescription::default(),
+            width: WIDTH,
+            height: HEIGHT,
+            pitch,
+        };
+        assert!(matches!(to_rgb8(&image, RgbFormat::RGB), Err(NVCodecError::SurfaceShapeMismatch)));
+    }
+}
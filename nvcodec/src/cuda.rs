@@ -0,0 +1,260 @@
+//! The CUDA driver objects the codecs work with: devices, contexts, streams
+//! and pitched allocations. Every call goes through `nvcodec_sys`, so with
+//! `dynamic-loading` they need libcuda only once they are used.
+
+use crate::{error::NVCodecResult, ffi};
+use std::{ffi::CStr, fmt, marker::PhantomData, sync::Arc};
+
+pub type CuResult<T> = Result<T, CuError>;
+
+/// A `CUresult` other than `CUDA_SUCCESS`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CuError(pub ffi::CUresult);
+
+impl From<ffi::CUresult> for CuError {
+    fn from(res: ffi::CUresult) -> Self {
+        Self(res)
+    }
+}
+
+impl fmt::Display for CuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut name = std::ptr::null();
+        let res = unsafe { ffi::cuGetErrorName(self.0, &mut name) };
+        if res == ffi::cudaError_enum_CUDA_SUCCESS && !name.is_null() {
+            let name = unsafe { CStr::from_ptr(name) };
+            write!(f, "{} ({})", name.to_string_lossy(), self.0)
+        } else {
+            write!(f, "CUDA error {}", self.0)
+        }
+    }
+}
+
+impl std::error::Error for CuError {}
+
+/// Loads the driver libraries and initialises the driver API. Fails with
+/// `NVCodecError::LibraryError` when libcuda or libnvcuvid is missing.
+pub fn init() -> NVCodecResult<()> {
+    ffi::load()?;
+
+    let res = unsafe { ffi::cuInit(0) };
+    wrap!((), res)?;
+
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CuDevice {
+    device: ffi::CUdevice,
+}
+
+impl CuDevice {
+    pub fn new(ordinal: i32) -> CuResult<Self> {
+        let mut device = 0;
+        let res = unsafe { ffi::cuDeviceGet(&mut device, ordinal) };
+        wrap!(Self { device }, res)
+    }
+
+    pub fn get_raw(&self) -> ffi::CUdevice {
+        self.device
+    }
+}
+
+/// Releases a primary context once the last `CuContext` retaining it is gone.
+#[derive(Debug)]
+struct PrimaryContext {
+    device: ffi::CUdevice,
+}
+
+impl Drop for PrimaryContext {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::cuDevicePrimaryCtxRelease_v2(self.device);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CuContext {
+    ctx: ffi::CUcontext,
+    /// Set when this handle keeps a primary context retained.
+    _primary: Option<Arc<PrimaryContext>>,
+}
+
+unsafe impl Send for CuContext {}
+unsafe impl Sync for CuContext {}
+
+impl CuContext {
+    /// Retains the device's primary context until every clone is dropped.
+    pub fn retain_primary_context(device: &CuDevice) -> CuResult<Self> {
+        let mut ctx = std::ptr::null_mut();
+        let res = unsafe { ffi::cuDevicePrimaryCtxRetain(&mut ctx, device.device) };
+        wrap!((), res)?;
+
+        Ok(Self {
+            ctx,
+            _primary: Some(Arc::new(PrimaryContext { device: device.device })),
+        })
+    }
+
+    /// Makes the context current on this thread until the guard is dropped.
+    pub fn guard(self) -> CuResult<CuContextGuard> {
+        let res = unsafe { ffi::cuCtxPushCurrent_v2(self.ctx) };
+        wrap!((), res)?;
+
+        Ok(CuContextGuard { _ctx: self, _thread: PhantomData })
+    }
+
+    /// # Safety
+    ///
+    /// The handle is only valid while the context is alive.
+    pub unsafe fn get_raw(&self) -> ffi::CUcontext {
+        self.ctx
+    }
+}
+
+/// Pops its context off the current thread's stack when dropped.
+pub struct CuContextGuard {
+    _ctx: CuContext,
+    // The context stack is per thread.
+    _thread: PhantomData<*const ()>,
+}
+
+impl Drop for CuContextGuard {
+    fn drop(&mut self) {
+        let mut ctx = std::ptr::null_mut();
+        unsafe {
+            ffi::cuCtxPopCurrent_v2(&mut ctx);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct StreamInner {
+    stream: ffi::CUstream,
+}
+
+unsafe impl Send for StreamInner {}
+unsafe impl Sync for StreamInner {}
+
+impl Drop for StreamInner {
+    fn drop(&mut self) {
+        if !self.stream.is_null() {
+            unsafe {
+                ffi::cuStreamDestroy_v2(self.stream);
+            }
+        }
+    }
+}
+
+/// A CUDA stream, destroyed with its last clone. It must not outlive its
+/// context.
+#[derive(Clone, Debug)]
+pub struct CuStream {
+    inner: Arc<StreamInner>,
+}
+
+impl CuStream {
+    /// A new stream on the current context.
+    pub fn new() -> CuResult<Self> {
+        let mut stream = std::ptr::null_mut();
+        let res = unsafe { ffi::cuStreamCreate(&mut stream, 0) };
+        wrap!((), res)?;
+
+        Ok(Self { inner: Arc::new(StreamInner { stream }) })
+    }
+
+    /// The legacy default stream, standing for the context current when it
+    /// is used. Needs no driver to create.
+    pub fn null() -> Self {
+        Self { inner: Arc::new(StreamInner { stream: std::ptr::null_mut() }) }
+    }
+
+    /// The context the stream belongs to. It is not retained, so the
+    /// stream's owner must keep it alive.
+    pub fn get_context(&self) -> CuResult<CuContext> {
+        let mut ctx = std::ptr::null_mut();
+        let res = unsafe { ffi::cuStreamGetCtx(self.inner.stream, &mut ctx) };
+        wrap!(CuContext { ctx, _primary: None }, res)
+    }
+
+    pub fn synchronize(&self) -> CuResult<()> {
+        let res = unsafe { ffi::cuStreamSynchronize(self.inner.stream) };
+        wrap!((), res)
+    }
+
+    /// # Safety
+    ///
+    /// The handle is only valid while a clone of the stream is alive.
+    pub unsafe fn get_raw(&self) -> ffi::CUstream {
+        self.inner.stream
+    }
+}
+
+/// A 2D device allocation of `height` rows of `width` bytes, each starting
+/// `pitch` bytes after the previous one.
+#[derive(Debug)]
+pub struct PitchedDeviceMemory {
+    pub ptr: ffi::CUdeviceptr,
+    pub pitch: usize,
+    pub width: usize,
+    pub height: usize,
+    ctx: CuContext,
+}
+
+impl PitchedDeviceMemory {
+    /// Allocates on the context of `stream`.
+    pub fn new(width: usize, height: usize, stream: &CuStream) -> CuResult<Self> {
+        let ctx = stream.get_context()?;
+        let _guard = ctx.clone().guard()?;
+
+        let (mut ptr, mut pitch) = (0, 0);
+        let res = unsafe { ffi::cuMemAllocPitch_v2(&mut ptr, &mut pitch, width, height, 16) };
+        wrap!(Self { ptr, pitch, width, height, ctx }, res)
+    }
+
+    /// Copies `height` rows of `width` bytes from device memory at `src`.
+    /// Without `is_async`, returns once the copy is done.
+    pub fn copy_from_raw(
+        &self,
+        src: ffi::CUdeviceptr,
+        src_pitch: usize,
+        width: usize,
+        height: usize,
+        is_async: bool,
+        stream: Option<&CuStream>,
+    ) -> CuResult<()> {
+        let _guard = self.ctx.clone().guard()?;
+
+        let mut params: ffi::CUDA_MEMCPY2D = unsafe { std::mem::zeroed() };
+        params.srcMemoryType = ffi::CUmemorytype_enum_CU_MEMORYTYPE_DEVICE;
+        params.srcDevice = src;
+        params.srcPitch = src_pitch;
+        params.dstMemoryType = ffi::CUmemorytype_enum_CU_MEMORYTYPE_DEVICE;
+        params.dstDevice = self.ptr;
+        params.dstPitch = self.pitch;
+        params.WidthInBytes = width;
+        params.Height = height;
+
+        let raw_stream = stream.map_or(std::ptr::null_mut(), |stream| unsafe { stream.get_raw() });
+        let res = unsafe { ffi::cuMemcpy2DAsync_v2(&params, raw_stream) };
+        wrap!((), res)?;
+
+        if !is_async {
+            let res = unsafe { ffi::cuStreamSynchronize(raw_stream) };
+            wrap!((), res)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for PitchedDeviceMemory {
+    fn drop(&mut self) {
+        if let Ok(_guard) = self.ctx.clone().guard() {
+            unsafe {
+                ffi::cuMemFree_v2(self.ptr);
+            }
+        }
+    }
+}
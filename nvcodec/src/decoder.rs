@@ -1,26 +1,33 @@
 use crate::{
+    caps::{self, DecoderCaps},
+    channel::ChannelStream,
     codec::CuVideoCodecType,
+    color::ColorDescription,
+    cuda::{CuContext, CuStream, PitchedDeviceMemory},
     demuxer::ffmpeg::Packet,
+    device::DeviceSession,
     error::{NVCodecResult, NVCodecError},
+    event::{Fence, FenceReady},
     ffi,
-    surface::VideoSurfaceFormat,
-};
-use cuda_rs::{
-    context::CuContext,
-    stream::CuStream,
-    memory::PitchedDeviceMemory,
+    geometry::{OutputGeometry, OutputRequest, StreamGeometry},
+    host::{Download, HostLayout},
+    kernel::HighBytes,
+    muxer::annexb,
+    stats::{DecoderEvent, DecoderStats},
+    surface::{ChromaFormat, PlaneLayout, SurfacePreference, VideoSurfaceFormat},
 };
+pub use crate::geometry::{DisplayArea, Size};
+
 use ffmpeg_next::util::color::{Range, Space};
-use futures::{
-    stream::Stream,
-    task::AtomicWaker,
-};
+use futures::stream::Stream;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     pin::Pin,
-    sync::{Arc, Mutex},
+    sync::Mutex,
     task::{Context, Poll},
+    time::Instant,
 };
+#[cfg(feature = "npp")]
 use npp::{
     color::{PixelFormat, ColorSpace, ColorRange},
     image::DeviceImage,
@@ -28,38 +35,65 @@ use npp::{
 
 struct Inner {
     ctx: CuContext,
+    device: i32,
     stream: CuStream,
-    display_area: DisplayArea,
-    target_size: Size,
+    output_request: OutputRequest,
+    pending_output: Option<OutputRequest>,
+    // How the last request was applied, until the next picture is decoded,
+    // then by the index of that picture until it is displayed.
+    output_change: Option<OutputChange>,
+    output_change_pictures: HashMap<i32, OutputChange>,
     keyframe_only: bool,
 
-    waker: Arc<AtomicWaker>,
-
     ctx_lock: ffi::CUvideoctxlock,
     parser: ffi::CUvideoparser,
+    codec_type: CuVideoCodecType,
 
     decoder: ffi::CUvideodecoder,
     video_fmt: Option<ffi::CUVIDEOFORMAT>,
+    surface_preference: SurfacePreference,
+    color_override: ColorDescription,
+    bitstream_color: ColorDescription,
+    // What NVDEC produces, and what is handed out after any bit depth conversion.
+    decode_fmt: VideoSurfaceFormat,
     surface_fmt: VideoSurfaceFormat,
-    bpp: u32,
-    num_chroma_planes: u32,
-    width: u32,
-    luma_height: u32,
-    chroma_height: u32,
+    high_bytes: Option<HighBytes>,
+    geometry: OutputGeometry,
     max_width: u32,
     max_height: u32,
 
     packet_map: Mutex<HashMap<i64, PacketData>>,
+    // Pictures decoded but not yet displayed, and those orphaned by a
+    // decoder re-creation that must not be mapped on the new decoder.
+    pending_pictures: HashSet<i32>,
+    stale_pictures: HashSet<i32>,
+    // Set while `NVDecoder::reset` drains the parser.
+    discarding: bool,
+    generation: u64,
+
+    stats: DecoderStats,
+    decode_times: HashMap<i32, Instant>,
+    event_hook: Option<Box<dyn FnMut(&DecoderEvent) + Send>>,
 
     sender: Option<flume::Sender<NVCodecResult<DecodedFrame>>>,
 }
 
 pub struct NVDecoder {
     inner: Box<Inner>,
-    eos: bool,
     pub receiver: flume::Receiver<NVCodecResult<DecodedFrame>>,
+    frames: ChannelStream<NVCodecResult<DecodedFrame>>,
+    discontinuity: bool,
+    // An error `decode_blocking` found after some frames, returned by the
+    // next call.
+    blocking_error: Option<NVCodecError>,
+    // Counts the decoder against its device while it lives.
+    session: Option<DeviceSession>,
 }
 
+// The parser and decoder are only used through `&mut self`, always with the
+// context pushed, so moving them to another thread is fine.
+unsafe impl Send for NVDecoder {}
+
 #[derive(Debug)]
 pub struct PacketData {
     pub key: bool,
@@ -73,14 +107,90 @@ pub struct PacketData {
 
 pub struct DecodedFrame {
     pub buf: PitchedDeviceMemory,
+    /// The decoder's stream, on which `buf` was filled.
+    pub stream: CuStream,
     pub width: usize,
     pub height: usize,
     pub packet_data: Option<PacketData>,
     pub surface_format: VideoSurfaceFormat,
+    /// Bits per luma sample of the coded stream. 16-bit surfaces hold 10- and
+    /// 12-bit samples alike, in the high bits.
+    pub bit_depth: u32,
+    /// Caller override, then bitstream, then container values, field by field.
+    pub color: ColorDescription,
+    /// Set on the first frame decoded after a `NVDecoder::set_output` request took effect.
+    pub output_change: Option<OutputChange>,
+    /// Recorded on `stream` right after the copy into `buf`.
+    pub copied: Fence,
+    /// Number of `NVDecoder::reset_for_new_stream` calls before the frame's
+    /// packet was sent, telling apart frames of consecutive inputs.
+    pub generation: u64,
+}
+
+/// How a `NVDecoder::set_output` request was applied.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OutputChange {
+    /// The new request resolved to the geometry already in use.
+    Unchanged,
+    /// Applied in place with `cuvidReconfigureDecoder`.
+    Reconfigured,
+    /// The decoder had to be destroyed and created again.
+    Recreated,
+}
+
+pub struct DevicePlane {
+    /// Device address of the plane's first sample.
+    pub ptr: ffi::CUdeviceptr,
+    pub layout: PlaneLayout,
+}
+
+impl DecodedFrame {
+    /// Planes of `buf`, in memory order.
+    pub fn planes(&self) -> Vec<DevicePlane> {
+        self.surface_format
+            .planes(self.width, self.height, self.buf.pitch as _)
+            .into_iter()
+            .map(|layout| DevicePlane {
+                ptr: self.buf.ptr as ffi::CUdeviceptr + layout.offset as ffi::CUdeviceptr,
+                layout,
+            })
+            .collect()
+    }
+
+    /// Makes work submitted to `stream` from now on wait for the copy into
+    /// `buf`, without blocking the host.
+    pub fn wait_on(&self, stream: &CuStream) -> NVCodecResult<()> {
+        self.copied.wait_on(stream)
+    }
+
+    /// Resolves once `buf` holds the frame.
+    pub fn ready(&self) -> FenceReady<'_> {
+        self.copied.ready()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.copied.is_ready()
+    }
+
+    /// Blocks until `buf` holds the frame.
+    pub fn wait(&self) -> NVCodecResult<()> {
+        self.copied.wait()
+    }
+
+    /// Starts copying the frame to host memory on the decoder's stream,
+    /// without blocking the host.
+    pub fn download(&self, layout: HostLayout) -> NVCodecResult<Download> {
+        Download::start(self, layout)
+    }
 }
 
-impl From<DecodedFrame> for DeviceImage {
-    fn from(frame: DecodedFrame) -> Self {
+/// Copies the frame into memory of its own once the copy into `buf` is
+/// done, as NPP images hold a cuda-rs allocation.
+#[cfg(feature = "npp")]
+impl TryFrom<DecodedFrame> for DeviceImage {
+    type Error = NVCodecError;
+
+    fn try_from(frame: DecodedFrame) -> NVCodecResult<Self> {
         let pixel_format = match frame.surface_format {
             VideoSurfaceFormat::NV12 => {
                 PixelFormat::NV12
@@ -94,42 +204,56 @@ impl From<DecodedFrame> for DeviceImage {
             VideoSurfaceFormat::YUV444_16Bit => {
                 PixelFormat::YUV444_16Bit
             }
-        };
-
-        let color_space = match frame.packet_data {
-            Some(ref packet_data) => {
-                match packet_data.color_space {
-                    Space::BT470BG | Space::SMPTE170M => ColorSpace::BT601,
-                    Space::BT709 => ColorSpace::BT709,
-                    _ => ColorSpace::UNSPEC,
-                }
+            #[cfg(nvcodec_sdk_13_0)]
+            VideoSurfaceFormat::NV16 | VideoSurfaceFormat::P216 => {
+                let msg = format!(
+                    "{:?} surfaces have no NPP pixel format",
+                    frame.surface_format
+                );
+                return Err(NVCodecError::NotSupported(msg));
             }
-            None => {
-                ColorSpace::UNSPEC
+            VideoSurfaceFormat::Y8 |
+            VideoSurfaceFormat::Y16 => {
+                let msg = format!(
+                    "{:?} surfaces have no NPP pixel format",
+                    frame.surface_format
+                );
+                return Err(NVCodecError::NotSupported(msg));
             }
         };
 
-        let color_range = match frame.packet_data {
-            Some(ref packet_data) => {
-                match packet_data.color_range {
-                    Range::MPEG => ColorRange::MPEG,
-                    Range::JPEG => ColorRange::JPEG,
-                    _ => ColorRange::UDEF,
-                }
-            }
-            None => {
-                ColorRange::UDEF
-            }
+        // The same defaults as the CPU reference in `convert`.
+        let color = frame.color.for_conversion(frame.height);
+        let color_space = match color.space {
+            Space::BT470BG | Space::SMPTE170M => ColorSpace::BT601,
+            Space::BT709 => ColorSpace::BT709,
+            Space::BT2020NCL | Space::BT2020CL => ColorSpace::BT2020,
+            Space::SMPTE240M => ColorSpace::SMPTE240M,
+            _ => ColorSpace::UNSPEC,
+        };
+
+        let color_range = match color.range {
+            Range::MPEG => ColorRange::MPEG,
+            Range::JPEG => ColorRange::JPEG,
+            _ => ColorRange::UDEF,
         };
 
-        Self {
-            mem: frame.buf,
+        frame.wait()?;
+        let ctx = frame.stream.get_context()?;
+        let _guard = ctx.guard()?;
+        let stream = cuda_rs::stream::CuStream::new()?;
+        let buf = &frame.buf;
+        let mem = cuda_rs::memory::PitchedDeviceMemory::new(buf.width, buf.height, &stream)?;
+        mem.copy_from_raw(buf.ptr as _, buf.pitch, buf.width, buf.height, false, Some(&stream))?;
+
+        Ok(Self {
+            mem,
             width: frame.width,
             height: frame.height,
             pixel_format,
             color_space,
             color_range,
-        }
+        })
     }
 }
 
@@ -141,10 +265,17 @@ impl NVDecoder {
         target_size: Option<Size>,
         keyframe_only: bool,
     ) -> NVCodecResult<Self> {
+        ffi::load()?;
+
+        let output_request = OutputRequest::new(display_area, target_size)?;
+
         let ctx = stream.get_context()?;
         let _guard = ctx.clone().guard()?;
 
-        let mut parser = std::ptr::null_mut();
+        let mut device = 0;
+        let res = unsafe { ffi::cuCtxGetDevice(&mut device) };
+        wrap!((), res)?;
+
         let mut ctx_lock = std::ptr::null_mut();
 
         let res = unsafe {
@@ -155,51 +286,138 @@ impl NVDecoder {
         let (tx, rx) =
             flume::unbounded::<NVCodecResult<DecodedFrame>>();
 
-        let waker = Arc::new(AtomicWaker::new());
         let mut inner = Box::new(Inner {
             ctx,
+            device: device as _,
             stream: stream.clone(),
-            display_area: display_area.unwrap_or_default(),
-            target_size: target_size.unwrap_or_default(),
+            output_request,
+            pending_output: None,
+            output_change: None,
+            output_change_pictures: HashMap::new(),
             keyframe_only,
-            waker,
             ctx_lock,
-            parser,
+            parser: std::ptr::null_mut(),
+            codec_type,
             decoder: std::ptr::null_mut(),
             video_fmt: None,
+            surface_preference: SurfacePreference::default(),
+            color_override: ColorDescription::default(),
+            bitstream_color: ColorDescription::default(),
+            decode_fmt: VideoSurfaceFormat::NV12,
             surface_fmt: VideoSurfaceFormat::NV12,
-            bpp: 1,
-            num_chroma_planes: 0,
-            width: 0,
-            luma_height: 0,
-            chroma_height: 0,
+            high_bytes: None,
+            geometry: OutputGeometry::default(),
             max_width: 0,
             max_height: 0,
             packet_map: Mutex::new(HashMap::new()),
+            pending_pictures: HashSet::new(),
+            stale_pictures: HashSet::new(),
+            discarding: false,
+            generation: 0,
+            stats: DecoderStats::default(),
+            decode_times: HashMap::new(),
+            event_hook: None,
             sender: Some(tx),
         });
 
-        let mut params: ffi::CUVIDPARSERPARAMS = unsafe { std::mem::zeroed() };
-        params.CodecType = codec_type as _;
-        params.ulMaxNumDecodeSurfaces = 1;
-        params.ulMaxDisplayDelay = 1;
-        params.pUserData = (&mut *inner as *mut Inner) as *mut std::os::raw::c_void;
-        params.pfnSequenceCallback = Some(handle_video_sequence_proc);
-        params.pfnDecodePicture = Some(handle_picture_decode_proc);
-        params.pfnDisplayPicture = Some(handle_picture_display_proc);
+        inner.create_parser(codec_type)?;
 
-        unsafe {
-            let res = ffi::cuvidCreateVideoParser(&mut parser, &mut params);
-            wrap!(res, res)?;
-        }
-        inner.parser = parser;
+        Ok(Self {
+            inner,
+            frames: ChannelStream::new(rx.clone()),
+            receiver: rx,
+            discontinuity: false,
+            blocking_error: None,
+            session: None,
+        })
+    }
+
+    /// Counters since the decoder was created.
+    pub fn stats(&self) -> DecoderStats {
+        let mut stats = self.inner.stats.clone();
+        stats.surfaces_in_use = self.inner.pending_pictures.len();
+
+        stats
+    }
+
+    /// Calls `hook` on the decoding thread for every `DecoderEvent`, e.g. to
+    /// feed a metrics backend. Keep it cheap, it runs inside the parser
+    /// callbacks.
+    pub fn set_event_hook(&mut self, hook: impl FnMut(&DecoderEvent) + Send + 'static) {
+        self.inner.event_hook = Some(Box::new(hook));
+    }
+
+    /// Ordinal of the device the decoder runs on.
+    pub fn device(&self) -> i32 {
+        self.inner.device
+    }
+
+    pub(crate) fn attach_session(&mut self, session: DeviceSession) {
+        self.session = Some(session);
+    }
+
+    /// Changes the crop and output size of a running decoder.
+    ///
+    /// The request is validated immediately and applied at the next sequence
+    /// header, or before the next random access point: an IDR, CRA or BLA
+    /// access unit for H.264 and HEVC, a keyframe for other codecs. In the
+    /// latter case every picture decoded so far is output first, so none is
+    /// decoded with one geometry and output with another. The change goes
+    /// through `cuvidReconfigureDecoder` when possible and re-creates the
+    /// decoder otherwise. The first frame decoded with the new geometry
+    /// reports the path taken in `DecodedFrame::output_change`. A request that
+    /// does not fit the stream is reported on the frame stream and the
+    /// previous output is kept.
+    ///
+    /// Recovery point SEI messages are not treated as random access points, so
+    /// an H.264 stream without IDR pictures, such as an intra-refresh stream,
+    /// only applies the request at its next sequence header.
+    pub fn set_output(
+        &mut self,
+        display_area: Option<DisplayArea>,
+        target_size: Option<Size>,
+    ) -> NVCodecResult<()> {
+        self.inner.pending_output = Some(OutputRequest::new(display_area, target_size)?);
+
+        Ok(())
+    }
+
+    /// Asks for a surface format or bit depth other than the stream's native one,
+    /// e.g. 8-bit NV12 from 10-bit HEVC.
+    ///
+    /// Takes effect when the decoder is next created, so call it before the
+    /// first packet. The format actually produced is reported in
+    /// `DecodedFrame::surface_format`.
+    pub fn set_surface_preference(&mut self, preference: SurfacePreference) {
+        self.inner.surface_preference = preference;
+    }
+
+    /// Sizes the hardware decoder for at least `width` x `height` coded
+    /// samples when it is created, so later streams up to that size are
+    /// handled by reconfiguring it instead of re-creating it.
+    pub fn set_max_size(&mut self, width: u32, height: u32) {
+        self.inner.max_width = self.inner.max_width.max(width);
+        self.inner.max_height = self.inner.max_height.max(height);
+    }
 
-        Ok(Self { inner, eos: false, receiver: rx })
+    /// Overrides the color description of every frame. Unspecified fields keep
+    /// the values signalled in the bitstream, or failing that the container.
+    pub fn set_color_override(&mut self, color: ColorDescription) {
+        self.inner.color_override = color;
     }
 
     pub fn decode(&mut self, packet: Option<&Packet>) -> NVCodecResult<()> {
         let _guard = self.inner.ctx.clone().guard()?;
 
+        if let Some(packet) = packet {
+            let apply_output = self.inner.pending_output.is_some() &&
+                self.inner.video_fmt.is_some() &&
+                is_random_access_point(self.inner.codec_type, packet);
+            if apply_output {
+                self.apply_pending_output()?;
+            }
+        }
+
         let mut params: ffi::CUVIDSOURCEDATAPACKET = unsafe { std::mem::zeroed() };
         params.flags = ffi::CUvideopacketflags_CUVID_PKT_TIMESTAMP as _;
         match packet {
@@ -207,8 +425,18 @@ impl NVDecoder {
                 let data = packet.data();
                 match data {
                     Some(data) => {
+                        if std::mem::take(&mut self.discontinuity) {
+                            params.flags |= ffi::CUvideopacketflags_CUVID_PKT_DISCONTINUITY as u64;
+                        }
+                        if self.inner.codec_type == CuVideoCodecType::JPEG {
+                            // Every packet holds one whole image, so there is
+                            // no need to wait for the next one to find its end.
+                            params.flags |= ffi::CUvideopacketflags_CUVID_PKT_ENDOFPICTURE as u64;
+                        }
                         params.payload_size = data.len() as _;
                         params.payload = data.as_ptr() as _;
+                        self.inner.stats.packets_in += 1;
+                        self.inner.stats.bytes_in += data.len() as u64;
                         let pts = packet.pts().unwrap_or(-1);
                         params.timestamp = pts;
                         self.inner.packet_map.lock().unwrap().insert(
@@ -226,19 +454,146 @@ impl NVDecoder {
                     }
                     None => {
                         params.flags |= ffi::CUvideopacketflags_CUVID_PKT_ENDOFSTREAM as u64;
-                        self.eos = true;
                     }
                 }
             }
             None => {
                 params.flags |= ffi::CUvideopacketflags_CUVID_PKT_ENDOFSTREAM as u64;
-                self.eos = true;
             }
         }
 
+        self.parse(&mut params)?;
+
+        if params.flags & ffi::CUvideopacketflags_CUVID_PKT_ENDOFSTREAM as u64 != 0 {
+            // The parser has displayed every remaining picture by now, so
+            // closing the channel ends the frame stream right after them.
+            self.inner.sender = None;
+        }
+
+        Ok(())
+    }
+
+    /// Outputs every picture still held by the parser and decoder, keeping
+    /// the decoder usable.
+    ///
+    /// Unlike `decode(None)` the frame stream stays open. The next packet is
+    /// sent as a discontinuity, so call this before feeding packets from a
+    /// new position after a seek to keep the frames before it.
+    pub fn flush(&mut self) -> NVCodecResult<()> {
+        let _guard = self.inner.ctx.clone().guard()?;
+
+        self.drain()?;
+        self.discontinuity = true;
+
+        Ok(())
+    }
+
+    /// Outputs every picture still held by the parser and decoder without
+    /// marking the next packet as a discontinuity, for callers that carry on
+    /// with the same stream.
+    fn drain(&mut self) -> NVCodecResult<()> {
+        let mut params: ffi::CUVIDSOURCEDATAPACKET = unsafe { std::mem::zeroed() };
+        params.flags = ffi::CUvideopacketflags_CUVID_PKT_ENDOFSTREAM as _;
+        self.parse(&mut params)
+    }
+
+    /// Drops every picture still held by the parser and decoder, frames not
+    /// yet taken from the frame stream and the metadata of queued packets.
+    ///
+    /// The next packet is sent as a discontinuity. Also reopens the frame
+    /// stream after end of stream, so one decoder can serve any number of
+    /// random-access requests.
+    pub fn reset(&mut self) -> NVCodecResult<()> {
+        let _guard = self.inner.ctx.clone().guard()?;
+
+        let mut params: ffi::CUVIDSOURCEDATAPACKET = unsafe { std::mem::zeroed() };
+        params.flags = ffi::CUvideopacketflags_CUVID_PKT_ENDOFSTREAM as _;
+        self.inner.discarding = true;
+        let res = self.parse(&mut params);
+        self.inner.discarding = false;
+        res?;
+
+        self.receiver.try_iter().for_each(drop);
+        self.blocking_error = None;
+        self.inner.packet_map.lock().unwrap().clear();
+        self.inner.pending_pictures.clear();
+        self.inner.decode_times.clear();
+        self.inner.output_change_pictures.clear();
+        self.discontinuity = true;
+
+        if self.inner.sender.is_none() {
+            self.open_frame_stream();
+        }
+
+        Ok(())
+    }
+
+    /// Prepares the decoder for a new input, possibly of another codec.
+    ///
+    /// Discards everything still queued like `reset`, then replaces the
+    /// parser, which is cheap. The hardware decoder is kept and reconfigured
+    /// at the new input's first sequence header when the codec, chroma format
+    /// and bit depth match and the coded size fits the one it was created
+    /// for, and is re-created otherwise. Frames of the new input carry the
+    /// next `DecodedFrame::generation`.
+    pub fn reset_for_new_stream(&mut self, codec_type: CuVideoCodecType) -> NVCodecResult<()> {
+        self.reset()?;
+
+        let _guard = self.inner.ctx.clone().guard()?;
+
+        let res = unsafe { ffi::cuvidDestroyVideoParser(self.inner.parser) };
+        self.inner.parser = std::ptr::null_mut();
+        wrap!((), res)?;
+
+        self.inner.create_parser(codec_type)?;
+        self.inner.generation += 1;
+        self.discontinuity = false;
+
+        Ok(())
+    }
+
+    /// Ends the current input without losing frames and prepares the decoder
+    /// for a new one, possibly of another codec.
+    ///
+    /// Outputs every picture still held like `decode(None)`, so the frame
+    /// stream ends once they are taken, then goes on like
+    /// `reset_for_new_stream` with a new frame stream. Frames of the previous
+    /// input stay readable through clones of `receiver` taken before.
+    pub fn flush_for_new_stream(&mut self, codec_type: CuVideoCodecType) -> NVCodecResult<()> {
+        self.decode(None)?;
+        self.open_frame_stream();
+
+        self.reset_for_new_stream(codec_type)
+    }
+
+    /// Replaces the frame channel, leaving what was sent so far to the
+    /// readers of the previous one.
+    fn open_frame_stream(&mut self) {
+        let (tx, rx) = flume::unbounded::<NVCodecResult<DecodedFrame>>();
+        self.inner.sender = Some(tx);
+        self.frames = ChannelStream::new(rx.clone());
+        self.receiver = rx;
+    }
+
+    /// Sends the next packet as a discontinuity, for callers that seek
+    /// without flushing or resetting.
+    pub fn mark_discontinuity(&mut self) {
+        self.discontinuity = true;
+    }
+
+    /// Outputs every picture the parser holds with the current geometry, then
+    /// applies the pending `set_output` request on the idle decoder. The
+    /// random access point that follows continues the same stream, so it is
+    /// not sent as a discontinuity.
+    fn apply_pending_output(&mut self) -> NVCodecResult<()> {
+        self.drain()?;
+        self.inner.apply_pending_output()
+    }
+
+    fn parse(&mut self, params: &mut ffi::CUVIDSOURCEDATAPACKET) -> NVCodecResult<()> {
         let res = unsafe {
             ffi::cuvidParseVideoData(
-                self.inner.parser, &mut params as *mut _
+                self.inner.parser, params as *mut _
             )
         };
 
@@ -246,30 +601,84 @@ impl NVDecoder {
 
         Ok(())
     }
+
+    /// Decodes `packet` and returns the frames the parser displayed while
+    /// doing so, for callers without an async runtime.
+    ///
+    /// Frames come from the same channel the `Stream` impl reads, so use one
+    /// or the other. Frames queued before the first error reported by the
+    /// callbacks, or by `decode` itself, are returned first and the error on
+    /// the next call. That call still decodes its packet but leaves the
+    /// frames queued after the error for the call after it.
+    pub fn decode_blocking(&mut self, packet: &Packet) -> NVCodecResult<Vec<DecodedFrame>> {
+        let res = self.decode(Some(packet));
+
+        take_frames(&self.receiver, res.err(), &mut self.blocking_error)
+    }
+
+    /// Sends end of stream and returns the frames still held by the decoder,
+    /// like `decode_blocking`.
+    pub fn flush_blocking(&mut self) -> NVCodecResult<Vec<DecodedFrame>> {
+        let res = self.decode(None);
+
+        take_frames(&self.receiver, res.err(), &mut self.blocking_error)
+    }
 }
 
-impl Stream for NVDecoder {
-    type Item = NVCodecResult<DecodedFrame>;
+/// Takes the items queued on `receiver` for `decode_blocking`. An error held
+/// back by the previous call comes first, leaving the queue untouched. An
+/// error found after some items is held back in `pending` so those items are
+/// not lost.
+fn take_frames<T>(
+    receiver: &flume::Receiver<NVCodecResult<T>>,
+    fallback: Option<NVCodecError>,
+    pending: &mut Option<NVCodecError>,
+) -> NVCodecResult<Vec<T>> {
+    if let Some(err) = pending.take() {
+        *pending = fallback;
+        return Err(err);
+    }
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.inner.waker.register(cx.waker());
+    match take_until_error(receiver, fallback) {
+        (items, None) => Ok(items),
+        (items, Some(err)) if items.is_empty() => Err(err),
+        (items, Some(err)) => {
+            *pending = Some(err);
+            Ok(items)
+        }
+    }
+}
 
-        match self.receiver.try_recv() {
-            Ok(v) => {
-                Poll::Ready(Some(v))
-            }
-            Err(flume::TryRecvError::Empty) => {
-                if self.eos {
-                    Poll::Ready(None)
-                } else {
-                    Poll::Pending
+/// Takes the items queued on `receiver` up to and including the first error,
+/// which is returned in place of `fallback`.
+fn take_until_error<T>(
+    receiver: &flume::Receiver<NVCodecResult<T>>,
+    fallback: Option<NVCodecError>,
+) -> (Vec<T>, Option<NVCodecError>) {
+    let mut items = vec![];
+    for item in receiver.try_iter() {
+        match item {
+            Ok(item) => items.push(item),
+            Err(err) => {
+                if let Some(fallback) = fallback {
+                    tracing::debug!("Dropping {:?} in favour of the error that caused it: {:?}", fallback, err);
                 }
-            }
-            Err(flume::TryRecvError::Disconnected) => {
-                Poll::Ready(None)
+                return (items, Some(err));
             }
         }
     }
+
+    (items, fallback)
+}
+
+impl Stream for NVDecoder {
+    type Item = NVCodecResult<DecodedFrame>;
+
+    /// Yields frames as the parser displays them, and ends once the
+    /// end-of-stream packet has been parsed and every frame before it yielded.
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.frames).poll_next(cx)
+    }
 }
 
 impl Drop for NVDecoder {
@@ -291,17 +700,35 @@ impl Drop for NVDecoder {
 }
 
 impl Inner {
+    /// Must be called on the boxed `Inner`, whose address the callbacks keep.
+    fn create_parser(&mut self, codec_type: CuVideoCodecType) -> NVCodecResult<()> {
+        let mut params: ffi::CUVIDPARSERPARAMS = unsafe { std::mem::zeroed() };
+        params.CodecType = codec_type as _;
+        params.ulMaxNumDecodeSurfaces = 1;
+        params.ulMaxDisplayDelay = 1;
+        params.pUserData = (self as *mut Inner) as *mut std::os::raw::c_void;
+        params.pfnSequenceCallback = Some(handle_video_sequence_proc);
+        params.pfnDecodePicture = Some(handle_picture_decode_proc);
+        params.pfnDisplayPicture = Some(handle_picture_display_proc);
+
+        let mut parser = std::ptr::null_mut();
+        unsafe {
+            let res = ffi::cuvidCreateVideoParser(&mut parser, &mut params);
+            wrap!(res, res)?;
+        }
+        self.parser = parser;
+        self.codec_type = codec_type;
+
+        Ok(())
+    }
+
     fn sequence_callback(&mut self, video_format: *mut ffi::CUVIDEOFORMAT) -> i32 {
         match self.sequence_callback_impl(video_format) {
             Ok(num_decode_surfaces) => num_decode_surfaces,
             Err(err) => {
                 tracing::error!("Error in sequence callback: {:?}", err);
 
-                if let Some(sender) = self.sender.as_ref() {
-                    if sender.send(Err(err)).is_ok() {
-                        self.waker.wake();
-                    }
-                }
+                self.send_error(err);
 
                 0
             }
@@ -313,80 +740,67 @@ impl Inner {
         let fmt = unsafe { &*video_format };
         let _guard = self.ctx.clone().guard()?;
 
-        tracing::error!(
-            "Video Input Information
-
-            Codec: {}
-            Frame Rate : {}/{}
-            Sequence: {}
-            Coded Size {}x{}
-            Display Area: {}x{}x{}x{}
-            Chroma :{}
-            Bit Depth: {}
-            Minimum Surfaces: {}",
-            fmt.codec,
-            fmt.frame_rate.numerator,
-            fmt.frame_rate.denominator,
-            fmt.progressive_sequence,
-            fmt.coded_width,
-            fmt.coded_height,
-            fmt.display_area.top,
-            fmt.display_area.left,
-            fmt.display_area.bottom,
-            fmt.display_area.right,
-            fmt.chroma_format,
-            fmt.bit_depth_chroma_minus8,
-            fmt.min_num_decode_surfaces,
+        tracing::debug!(
+            codec = fmt.codec,
+            frame_rate = format!("{}/{}", fmt.frame_rate.numerator, fmt.frame_rate.denominator),
+            progressive = fmt.progressive_sequence,
+            coded_width = fmt.coded_width,
+            coded_height = fmt.coded_height,
+            display_area = format!(
+                "{},{},{},{}",
+                fmt.display_area.left,
+                fmt.display_area.top,
+                fmt.display_area.right,
+                fmt.display_area.bottom,
+            ),
+            chroma_format = fmt.chroma_format,
+            bit_depth = fmt.bit_depth_luma_minus8 + 8,
+            min_decode_surfaces = fmt.min_num_decode_surfaces,
+            "Sequence header",
         );
+        self.emit(DecoderEvent::SequenceHeader {
+            codec: fmt.codec,
+            coded_width: fmt.coded_width,
+            coded_height: fmt.coded_height,
+            chroma_format: chroma_format(fmt).ok(),
+            bit_depth: fmt.bit_depth_luma_minus8 + 8,
+            min_decode_surfaces: fmt.min_num_decode_surfaces,
+        });
 
-        let mut decode_caps: ffi::CUVIDDECODECAPS = unsafe { std::mem::zeroed() };
-        decode_caps.eCodecType = fmt.codec;
-        decode_caps.eChromaFormat = fmt.chroma_format;
-        decode_caps.nBitDepthMinus8 = fmt.bit_depth_chroma_minus8 as _;
-
-        let res = unsafe { ffi::cuvidGetDecoderCaps(&mut decode_caps) };
-        wrap!((), res)?;
-
-        if decode_caps.bIsSupported == 0 {
-            let msg = format!(
-                "Codec {} not supported for this GPU",
-                fmt.codec
-            );
-            return Err(NVCodecError::NotSupported(msg));
-        }
-
-        if decode_caps.nMaxWidth < fmt.coded_width || decode_caps.nMaxHeight < fmt.coded_height {
-            let msg = format!(
-                "Resolution (wxh) {}x{} if greater than max resolution {}x{} for this GPU",
-                fmt.coded_width,
-                fmt.coded_height,
-                decode_caps.nMaxWidth,
-                decode_caps.nMaxHeight
-            );
-            return Err(NVCodecError::NotSupported(msg));
-        }
+        let decode_caps = query_decode_caps(fmt)?;
 
-        if decode_caps.nMaxMBCount < (fmt.coded_width >> 4) * (fmt.coded_height >> 4) {
-            let msg = format!(
-                "bitrate {} if greater than max bitrate {} for the GPU",
-                (fmt.coded_width >> 4) * (fmt.coded_height >> 4),
-                decode_caps.nMaxMBCount
-            );
-            return Err(NVCodecError::NotSupported(msg));
-        }
+        let signal = &fmt.video_signal_description;
+        self.bitstream_color = ColorDescription::from_code_points(
+            signal.matrix_coefficients,
+            signal.color_primaries,
+            signal.transfer_characteristics,
+            signal.video_full_range_flag() != 0,
+        );
+        let (geometry, output_changed) = self.plan_output(fmt)?;
 
         if self.video_fmt.is_some() {
-            match self.reconfigure_decoder(fmt) {
+            let previous = self.geometry;
+            match self.reconfigure_decoder(fmt, &geometry) {
                 Ok(num_decode_surfaces) => {
                     self.video_fmt = Some(*fmt);
+                    if output_changed {
+                        self.output_change = Some(if previous == geometry {
+                            OutputChange::Unchanged
+                        } else {
+                            OutputChange::Reconfigured
+                        });
+                    }
                     return Ok(num_decode_surfaces);
                 },
                 Err(err) => {
                     match err {
-                        NVCodecError::ReconfigureFailed => unsafe {
-                            let res = ffi::cuvidDestroyDecoder(self.decoder);
-                            wrap!((), res)?;
-                            self.decoder = std::ptr::null_mut();
+                        NVCodecError::ReconfigureFailed => {
+                            self.destroy_decoder()?;
+                            self.stats.recreations += 1;
+                            self.emit(DecoderEvent::Recreated);
+                            if output_changed {
+                                self.output_change = Some(OutputChange::Recreated);
+                            }
                         }
                         _ => {
                             tracing::error!("Error in reconfigure decoder: {:?}", err);
@@ -397,46 +811,39 @@ impl Inner {
             }
         }
 
-        let mut output_format =
-            match fmt.chroma_format {
-                ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_420 => {
-                    if fmt.bit_depth_luma_minus8 > 0 {
-                        VideoSurfaceFormat::P016
-                    } else {
-                        VideoSurfaceFormat::NV12
-                    }
-                }
-                ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_444 => {
-                    if fmt.bit_depth_luma_minus8 > 0 {
-                        VideoSurfaceFormat::YUV444_16Bit
-                    } else {
-                        VideoSurfaceFormat::YUV444
-                    }
-                }
-                _ => {
-                    VideoSurfaceFormat::NV12
-                }
-            };
+        self.create_decoder(fmt, &decode_caps, geometry)
+    }
+
+    fn create_decoder(
+        &mut self,
+        fmt: &ffi::CUVIDEOFORMAT,
+        decode_caps: &DecoderCaps,
+        geometry: OutputGeometry,
+    ) -> NVCodecResult<i32> {
+        let chroma_format = chroma_format(fmt)?;
+        let native_format =
+            VideoSurfaceFormat::for_chroma_format(chroma_format, fmt.bit_depth_luma_minus8 as _)
+                .ok_or_else(|| {
+                    let msg = format!(
+                        "{:?} {:?} needs Video Codec SDK 13.0 or later",
+                        self.codec_type,
+                        chroma_format
+                    );
+                    NVCodecError::NotSupported(msg)
+                })?;
 
         // Check if output format supported. If not, check falback options
-        if decode_caps.nOutputFormatMask & (1 << (output_format as u16)) == 0 {
-            if decode_caps.nOutputFormatMask & (1 << (VideoSurfaceFormat::NV12 as u16)) != 0 {
-                output_format = VideoSurfaceFormat::NV12;
-            } else if decode_caps.nOutputFormatMask & (1 << (VideoSurfaceFormat::P016 as u16)) != 0 {
-                output_format = VideoSurfaceFormat::P016;
-            } else if decode_caps.nOutputFormatMask & (1 << (VideoSurfaceFormat::YUV444 as u16)) != 0 {
-                output_format = VideoSurfaceFormat::YUV444;
-            } else if decode_caps.nOutputFormatMask & (1 << (VideoSurfaceFormat::YUV444_16Bit as u16)) != 0 {
-                output_format = VideoSurfaceFormat::YUV444_16Bit;
-            } else {
+        let (decode_format, output_format) = self.surface_preference
+            .resolve(native_format, decode_caps.output_format_mask)
+            .ok_or_else(|| {
                 let msg = format!(
                     "No supported output format found. Supported formats: {}",
-                    decode_caps.nOutputFormatMask
+                    decode_caps.output_format_mask
                 );
-                return Err(NVCodecError::NotSupported(msg));
-            }
-        }
+                NVCodecError::NotSupported(msg)
+            })?;
 
+        self.decode_fmt = decode_format;
         self.surface_fmt = output_format;
         self.video_fmt = Some(*fmt);
 
@@ -449,7 +856,7 @@ impl Inner {
         video_decode_create_info.ulIntraDecodeOnly = if self.keyframe_only { 1 } else { 0 };
         video_decode_create_info.CodecType = fmt.codec;
         video_decode_create_info.ChromaFormat = fmt.chroma_format;
-        video_decode_create_info.OutputFormat = output_format as _;
+        video_decode_create_info.OutputFormat = decode_format.cuvid_format() as _;
         video_decode_create_info.bitDepthMinus8 = fmt.bit_depth_chroma_minus8 as _;
         video_decode_create_info.DeinterlaceMode = if fmt.progressive_sequence != 0 {
             ffi::cudaVideoDeinterlaceMode_enum_cudaVideoDeinterlaceMode_Weave
@@ -475,42 +882,14 @@ impl Inner {
         video_decode_create_info.ulMaxWidth = self.max_width as _;
         video_decode_create_info.ulMaxHeight = self.max_height as _;
 
-        if (self.display_area.right == 0 || self.display_area.bottom == 0) &&
-            (self.target_size.width == 0 || self.target_size.height == 0) {
-            self.width = (fmt.display_area.right - fmt.display_area.left) as _;
-            self.luma_height = (fmt.display_area.bottom - fmt.display_area.top) as _;
-        } else {
-            if self.target_size.width != 0 && self.target_size.height != 0 {
-                video_decode_create_info.display_area.left = fmt.display_area.left as _;
-                video_decode_create_info.display_area.top = fmt.display_area.top as _;
-                video_decode_create_info.display_area.right = fmt.display_area.right as _;
-                video_decode_create_info.display_area.bottom = fmt.display_area.bottom as _;
-                self.width = self.target_size.width as _;
-                self.luma_height = self.target_size.height as _;
-            }
-
-            if self.display_area.right != 0 && self.display_area.bottom != 0 {
-                video_decode_create_info.display_area.left = self.display_area.left as _;
-                video_decode_create_info.display_area.top = self.display_area.top as _;
-                video_decode_create_info.display_area.right = self.display_area.right as _;
-                video_decode_create_info.display_area.bottom = self.display_area.bottom as _;
-                self.width = (self.display_area.right - self.display_area.left) as _;
-                self.luma_height = (self.display_area.bottom - self.display_area.top) as _;
-            }
-        }
+        video_decode_create_info.display_area.left = geometry.display_area.left as _;
+        video_decode_create_info.display_area.top = geometry.display_area.top as _;
+        video_decode_create_info.display_area.right = geometry.display_area.right as _;
+        video_decode_create_info.display_area.bottom = geometry.display_area.bottom as _;
+        video_decode_create_info.ulTargetWidth = geometry.width as _;
+        video_decode_create_info.ulTargetHeight = geometry.luma_height as _;
 
-        video_decode_create_info.ulTargetWidth = self.width as _;
-        video_decode_create_info.ulTargetHeight = self.luma_height as _;
-
-        self.bpp = if fmt.bit_depth_luma_minus8 > 0 { 2 } else { 1 };
-        self.chroma_height = (
-            self.luma_height as f32 * get_chroma_height_factor(fmt.chroma_format)
-        ) as u32;
-        self.num_chroma_planes = get_chroma_plane_count(fmt.chroma_format);
-        self.display_area.left = video_decode_create_info.display_area.left as _;
-        self.display_area.top = video_decode_create_info.display_area.top as _;
-        self.display_area.right = video_decode_create_info.display_area.right as _;
-        self.display_area.bottom = video_decode_create_info.display_area.bottom as _;
+        self.geometry = geometry;
 
         let res = unsafe {
             ffi::cuvidCreateDecoder(
@@ -518,11 +897,14 @@ impl Inner {
             )
         };
         wrap!((), res)?;
+        self.stats.decode_surfaces = num_decode_surfaces as _;
 
         Ok(num_decode_surfaces)
     }
 
-    fn reconfigure_decoder(&mut self, fmt: &ffi::CUVIDEOFORMAT) -> NVCodecResult<i32> {
+    fn reconfigure_decoder(
+        &mut self, fmt: &ffi::CUVIDEOFORMAT, geometry: &OutputGeometry
+    ) -> NVCodecResult<i32> {
         let old_fmt = self.video_fmt.as_ref().unwrap();
 
         let is_bit_depth_change = old_fmt.bit_depth_chroma_minus8 != fmt.bit_depth_chroma_minus8 ||
@@ -532,6 +914,11 @@ impl Inner {
             return Err(NVCodecError::ReconfigureFailed);
         }
 
+        if old_fmt.codec != fmt.codec {
+            tracing::debug!("Reconfigure Not supported for codec change. Re-creating decoder.");
+            return Err(NVCodecError::ReconfigureFailed);
+        }
+
         let is_chroma_format_change = old_fmt.chroma_format != fmt.chroma_format;
         if is_chroma_format_change {
             tracing::debug!("Reconfigure Not supported for chroma format change. Re-creating decoder.");
@@ -551,50 +938,160 @@ impl Inner {
 
         let is_decode_res_change = old_fmt.coded_width != fmt.coded_width ||
             old_fmt.coded_height != fmt.coded_height;
-        let is_display_area_change = old_fmt.display_area.top != fmt.display_area.top ||
-            old_fmt.display_area.left != fmt.display_area.left ||
-            old_fmt.display_area.bottom != fmt.display_area.bottom ||
-            old_fmt.display_area.right != fmt.display_area.right;
-
-        if !is_decode_res_change {
-            // if the coded_width/coded_height hasn't changed but display resolution has
-            // changed, then need to update width/height for correct output without
-            // cropping. Example : 1920x1080 vs 1920x1088
-            if is_display_area_change {
-                self.width = (fmt.display_area.right - fmt.display_area.left) as _;
-                self.luma_height = (fmt.display_area.bottom - fmt.display_area.top) as _;
-                self.chroma_height = (
-                    self.luma_height as f32 * get_chroma_height_factor(fmt.chroma_format)
-                ) as u32;
-            }
+        // The decoder crops and scales in hardware, so a display area change
+        // alone (e.g. 1920x1088 coded, 1920x1080 shown) still needs a reconfigure.
+        let is_geometry_change = *geometry != self.geometry;
 
+        if !is_decode_res_change && !is_geometry_change {
             return Ok(num_decode_surfaces)
         }
 
         let mut params: ffi::CUVIDRECONFIGUREDECODERINFO = unsafe { std::mem::zeroed() };
         params.ulWidth = fmt.coded_width as _;
         params.ulHeight = fmt.coded_height as _;
-        params.ulTargetWidth = self.width as _;
-        params.ulTargetHeight = self.luma_height as _;
+        params.ulTargetWidth = geometry.width as _;
+        params.ulTargetHeight = geometry.luma_height as _;
+        params.display_area.left = geometry.display_area.left as _;
+        params.display_area.top = geometry.display_area.top as _;
+        params.display_area.right = geometry.display_area.right as _;
+        params.display_area.bottom = geometry.display_area.bottom as _;
+        params.ulNumDecodeSurfaces = num_decode_surfaces as _;
 
-        if is_decode_res_change {
-            self.width = (fmt.display_area.right - fmt.display_area.left) as _;
-            self.luma_height = (fmt.display_area.bottom - fmt.display_area.top) as _;
-            self.chroma_height = (
-                self.luma_height as f32 * get_chroma_height_factor(fmt.chroma_format)
-            ) as u32;
+        let res = unsafe {
+            ffi::cuvidReconfigureDecoder(
+                self.decoder, &mut params
+            )
+        };
+        if let Err(err) = wrap!((), res) {
+            tracing::debug!("cuvidReconfigureDecoder failed: {:?}. Re-creating decoder.", err);
+            return Err(NVCodecError::ReconfigureFailed);
         }
 
-        params.ulNumDecodeSurfaces = num_decode_surfaces as _;
+        self.geometry = *geometry;
+        self.stats.reconfigurations += 1;
+        self.stats.decode_surfaces = num_decode_surfaces as _;
+        self.emit(DecoderEvent::Reconfigured);
+
+        Ok(num_decode_surfaces)
+    }
+
+    /// Plans the output for `fmt`, preferring a pending `set_output` request.
+    /// A pending request that does not fit the stream is reported and dropped.
+    fn plan_output(&mut self, fmt: &ffi::CUVIDEOFORMAT) -> NVCodecResult<(OutputGeometry, bool)> {
+        let stream = stream_geometry(fmt)?;
+
+        if let Some(request) = self.pending_output.take() {
+            match OutputGeometry::plan(&stream, &request) {
+                Ok(geometry) => {
+                    self.output_request = request;
+                    return Ok((geometry, true));
+                }
+                Err(err) => {
+                    tracing::error!("Discarding output request {:?}: {:?}", request, err);
+                    self.send_error(err.into());
+                }
+            }
+        }
+
+        Ok((OutputGeometry::plan(&stream, &self.output_request)?, false))
+    }
+
+    /// Applies a pending `set_output` request between sequence headers, once
+    /// the parser has output every picture decoded so far.
+    fn apply_pending_output(&mut self) -> NVCodecResult<()> {
+        let fmt = match self.video_fmt {
+            Some(fmt) => fmt,
+            None => return Ok(()),
+        };
+
+        let (geometry, output_changed) = self.plan_output(&fmt)?;
+        if !output_changed {
+            return Ok(());
+        }
+
+        let previous = self.geometry;
+        let output_change = match self.reconfigure_decoder(&fmt, &geometry) {
+            Ok(_) if previous == geometry => OutputChange::Unchanged,
+            Ok(_) => OutputChange::Reconfigured,
+            Err(NVCodecError::ReconfigureFailed) => {
+                self.destroy_decoder()?;
+                let decode_caps = query_decode_caps(&fmt)?;
+                self.create_decoder(&fmt, &decode_caps, geometry)?;
+                OutputChange::Recreated
+            }
+            Err(err) => return Err(err),
+        };
+        self.output_change = Some(output_change);
+
+        Ok(())
+    }
 
+    /// Pictures decoded but not yet displayed cannot be mapped from the next
+    /// decoder. They are counted as dropped when the parser displays them,
+    /// and reported on the frame stream now.
+    fn destroy_decoder(&mut self) -> NVCodecResult<()> {
         unsafe {
-            let res = ffi::cuvidReconfigureDecoder(
-                self.decoder, &mut params
-            );
+            let res = ffi::cuvidDestroyDecoder(self.decoder);
             wrap!((), res)?;
         }
+        self.decoder = std::ptr::null_mut();
+        if !self.pending_pictures.is_empty() {
+            tracing::warn!(
+                "{} decoded pictures lost by re-creating the decoder",
+                self.pending_pictures.len()
+            );
+            self.send_error(NVCodecError::PicturesLost(self.pending_pictures.len()));
+        }
+        self.stale_pictures.extend(self.pending_pictures.drain());
 
-        Ok(num_decode_surfaces)
+        Ok(())
+    }
+
+    /// Converts a 16-bit surface to its 8-bit twin by keeping the high byte of
+    /// every little-endian sample, with a kernel loaded on first use. It is
+    /// slower than having NVDEC output 8-bit directly and is only used when
+    /// the hardware cannot.
+    fn copy_high_bytes(
+        &mut self,
+        src_ptr: ffi::CUdeviceptr,
+        src_pitch: u32,
+        dst: &PitchedDeviceMemory,
+        width: u32,
+        height: u32,
+    ) -> NVCodecResult<()> {
+        if self.high_bytes.is_none() {
+            self.high_bytes = Some(HighBytes::load(&self.ctx)?);
+        }
+        let high_bytes = self.high_bytes.as_ref().unwrap();
+
+        // Every row holds `width` samples, including interleaved UV rows.
+        high_bytes.launch(
+            src_ptr,
+            src_pitch as _,
+            dst.ptr as _,
+            dst.pitch as _,
+            width,
+            height,
+            &self.stream,
+        )
+    }
+
+    fn emit(&mut self, event: DecoderEvent) {
+        if let Some(hook) = self.event_hook.as_mut() {
+            hook(&event);
+        }
+    }
+
+    fn drop_picture(&mut self, picture_index: i32) {
+        self.decode_times.remove(&picture_index);
+        self.stats.frames_dropped += 1;
+        self.emit(DecoderEvent::FrameDropped);
+    }
+
+    fn send_error(&self, err: NVCodecError) {
+        if let Some(sender) = self.sender.as_ref() {
+            let _ = sender.send(Err(err));
+        }
     }
 
     fn picture_decode_callback(&mut self, pic_params: *mut ffi::CUVIDPICPARAMS) -> i32 {
@@ -603,11 +1100,7 @@ impl Inner {
             Err(err) => {
                 tracing::error!("Error in picture decode callback: {:?}", err);
 
-                if let Some(sender) = self.sender.as_ref() {
-                    if sender.send(Err(err)).is_ok() {
-                        self.waker.wake();
-                    }
-                }
+                self.send_error(err);
 
                 0
             }
@@ -623,11 +1116,27 @@ impl Inner {
 
         let _guard = self.ctx.clone().guard()?;
 
+        let picture_index = unsafe { (*pic_params).CurrPicIdx };
+
         let res = unsafe {
             ffi::cuvidDecodePicture(self.decoder, pic_params)
         };
         wrap!((), res)?;
 
+        // A reused index can no longer belong to a picture of a previous decoder.
+        self.stale_pictures.remove(&picture_index);
+        self.pending_pictures.insert(picture_index);
+        match self.output_change.take() {
+            Some(output_change) => {
+                self.output_change_pictures.insert(picture_index, output_change);
+            }
+            None => {
+                self.output_change_pictures.remove(&picture_index);
+            }
+        }
+        self.decode_times.insert(picture_index, Instant::now());
+        self.stats.pictures_decoded += 1;
+
         Ok(1)
     }
 
@@ -637,11 +1146,7 @@ impl Inner {
             Err(err) => {
                 tracing::error!("Error in picture display callback: {:?}", err);
 
-                if let Some(sender) = self.sender.as_ref() {
-                    if sender.send(Err(err)).is_ok() {
-                        self.waker.wake();
-                    }
-                }
+                self.send_error(err);
 
                 0
             }
@@ -663,15 +1168,25 @@ impl Inner {
         }
 
         if display_info.is_null() {
-            if let Some(sender) = self.sender.take() {
-                std::mem::drop(sender);
-                self.waker.wake();
-            }
+            // End of stream; `NVDecoder::decode` closes the channel itself.
             return Ok(1);
         }
 
         let display_info = unsafe { &*display_info };
 
+        self.pending_pictures.remove(&display_info.picture_index);
+        let output_change = self.output_change_pictures.remove(&display_info.picture_index);
+        if self.stale_pictures.remove(&display_info.picture_index) {
+            // Decoded by a decoder that has since been re-created.
+            self.packet_map.lock().unwrap().remove(&display_info.timestamp);
+            self.drop_picture(display_info.picture_index);
+            return Ok(1);
+        }
+        if self.discarding {
+            self.drop_picture(display_info.picture_index);
+            return Ok(1);
+        }
+
         let _guard = self.ctx.clone().guard()?;
 
         let mut params: ffi::CUVIDPROCPARAMS = unsafe { std::mem::zeroed() };
@@ -704,39 +1219,77 @@ impl Inner {
             );
             wrap!((), res)?;
 
+            let concealed = decode_status.decodeStatus ==
+                ffi::cuvidDecodeStatus_enum_cuvidDecodeStatus_Error_Concealed;
+            if concealed {
+                self.stats.frames_concealed += 1;
+                self.emit(DecoderEvent::FrameConcealed);
+            }
             if decode_status.decodeStatus == ffi::cuvidDecodeStatus_enum_cuvidDecodeStatus_Error ||
-                decode_status.decodeStatus == ffi::cuvidDecodeStatus_enum_cuvidDecodeStatus_Error_Concealed
+                concealed
             {
+                ffi::cuvidUnmapVideoFrame64(self.decoder, src_ptr);
+                self.drop_picture(display_info.picture_index);
                 return Err(NVCodecError::DecodeError);
             }
         }
 
-        let buffer_height = self.luma_height + self.chroma_height * self.num_chroma_planes;
+        // Both copies are queued on the stream the picture was mapped on,
+        // ahead of the unmap, and `copied` is recorded right behind them.
+        let width = self.geometry.width;
+        let buffer_height = self.surface_fmt.buffer_height(self.geometry.luma_height);
         let surface_buffer = PitchedDeviceMemory::new(
-            (self.width * self.bpp) as _,
+            (width * self.surface_fmt.bytes_per_sample()) as _,
             buffer_height as _,
             &self.stream,
         )?;
-        surface_buffer.copy_from_raw(
-            src_ptr,
-            src_pitch as _,
-            self.width as _,
-            buffer_height as _,
-            false,
-            None,
-        )?;
+        if self.decode_fmt == self.surface_fmt {
+            surface_buffer.copy_from_raw(
+                src_ptr,
+                src_pitch as _,
+                (width * self.surface_fmt.bytes_per_sample()) as _,
+                buffer_height as _,
+                true,
+                Some(&self.stream),
+            )?;
+        } else {
+            self.copy_high_bytes(src_ptr, src_pitch, &surface_buffer, width, buffer_height)?;
+        }
+        let copied = Fence::record(&self.stream)?;
 
         if let Some(sender) = self.sender.as_ref() {
+            let packet_data = self.packet_map.lock().unwrap().remove(&display_info.timestamp);
+            let container_color = match packet_data {
+                Some(ref packet_data) => ColorDescription {
+                    space: packet_data.color_space,
+                    range: packet_data.color_range,
+                    ..Default::default()
+                },
+                None => ColorDescription::default(),
+            };
             let frame = DecodedFrame {
                 buf: surface_buffer,
-                width: self.width as _,
-                height: self.luma_height as _,
-                packet_data: self.packet_map.lock().unwrap().remove(&display_info.timestamp),
+                stream: self.stream.clone(),
+                width: width as _,
+                height: self.geometry.luma_height as _,
+                packet_data,
+                color: self.color_override.or(self.bitstream_color).or(container_color),
                 surface_format: self.surface_fmt,
+                bit_depth: self.video_fmt.map_or(8, |fmt| fmt.bit_depth_luma_minus8 as u32 + 8),
+                output_change,
+                copied,
+                generation: self.generation,
             };
 
             if sender.send(Ok(frame)).is_ok() {
-                self.waker.wake();
+                self.stats.queue_high_water = self.stats.queue_high_water.max(sender.len());
+            }
+
+            self.stats.pictures_displayed += 1;
+            if let Some(decoded_at) = self.decode_times.remove(&display_info.picture_index) {
+                let latency = decoded_at.elapsed();
+                self.stats.decode_to_display.record(latency);
+                self.emit(DecoderEvent::FrameDisplayed { latency });
             }
         }
 
@@ -749,24 +1302,58 @@ impl Inner {
     }
 }
 
-fn get_chroma_height_factor(chroma_format: ffi::cudaVideoChromaFormat) -> f32 {
-    match chroma_format {
-        ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_Monochrome => 0.,
-        ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_420 => 0.5,
-        ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_422 => 1.,
-        ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_444 => 1.,
-        _ => 0.5,
-    }
+fn query_decode_caps(fmt: &ffi::CUVIDEOFORMAT) -> NVCodecResult<DecoderCaps> {
+    let codec = CuVideoCodecType::from_raw(fmt.codec).ok_or_else(|| {
+        NVCodecError::NotSupported(format!("Codec {} not supported", fmt.codec))
+    })?;
+    let caps = caps::query_current(
+        codec,
+        chroma_format(fmt)?,
+        fmt.bit_depth_chroma_minus8 + 8,
+    )?;
+
+    caps.check_size(fmt.coded_width, fmt.coded_height).map_err(NVCodecError::NotSupported)?;
+
+    Ok(caps)
 }
 
-fn get_chroma_plane_count(chroma_format: ffi::cudaVideoChromaFormat) -> u32 {
-    match chroma_format {
-        ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_420 => 1,
-        ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_444 => 2,
-        _ => 0,
+/// Whether decoding can start at `packet` without any earlier picture. The
+/// demuxer hands out H.264 and HEVC in Annex B, so their NAL unit types are
+/// checked rather than trusting the container's keyframe flag, which is also
+/// set on plain intra pictures.
+fn is_random_access_point(codec_type: CuVideoCodecType, packet: &Packet) -> bool {
+    let data = packet.data().unwrap_or_default();
+    match codec_type {
+        // IDR
+        CuVideoCodecType::H264 => annexb::nal_units(data).any(|nal| nal[0] & 0x1f == 5),
+        // BLA, IDR and CRA
+        CuVideoCodecType::HEVC => annexb::nal_units(data).any(|nal| (16..=21).contains(&((nal[0] >> 1) & 0x3f))),
+        _ => packet.is_key(),
     }
 }
 
+fn chroma_format(fmt: &ffi::CUVIDEOFORMAT) -> NVCodecResult<ChromaFormat> {
+    ChromaFormat::from_raw(fmt.chroma_format).ok_or_else(|| {
+        NVCodecError::NotSupported(format!("Chroma format {} not supported", fmt.chroma_format))
+    })
+}
+
+fn stream_geometry(fmt: &ffi::CUVIDEOFORMAT) -> NVCodecResult<StreamGeometry> {
+    let chroma_format = chroma_format(fmt)?;
+
+    Ok(StreamGeometry {
+        coded_width: fmt.coded_width,
+        coded_height: fmt.coded_height,
+        display_area: DisplayArea {
+            top: fmt.display_area.top,
+            left: fmt.display_area.left,
+            bottom: fmt.display_area.bottom,
+            right: fmt.display_area.right,
+        },
+        chroma_format,
+    })
+}
+
 pub unsafe extern "C" fn handle_video_sequence_proc(
     user_data: *mut std::os::raw::c_void,
     video_format: *mut ffi::CUVIDEOFORMAT,
@@ -797,16 +1384,92 @@ pub unsafe extern "C" fn handle_picture_display_proc(
     decoder.picture_display_callback(display_info)
 }
 
-#[derive(Clone, Copy, Debug, Default)]
-pub struct DisplayArea {
-    pub top: i32,
-    pub left: i32,
-    pub bottom: i32,
-    pub right: i32,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-#[derive(Clone, Copy, Debug, Default)]
-pub struct Size {
-    pub width: i32,
-    pub height: i32,
+    fn queue(items: Vec<NVCodecResult<u32>>) -> flume::Receiver<NVCodecResult<u32>> {
+        let (tx, rx) = flume::unbounded();
+        for item in items {
+            tx.send(item).unwrap();
+        }
+        rx
+    }
+
+    #[test]
+    fn takes_every_frame_without_errors() {
+        let rx = queue(vec![Ok(1), Ok(2), Ok(3)]);
+        let (frames, err) = take_until_error(&rx, None);
+        assert_eq!(frames, [1, 2, 3]);
+        assert!(err.is_none());
+    }
+
+    #[test]
+    fn keeps_frames_before_an_error_and_leaves_the_rest() {
+        let rx = queue(vec![Ok(1), Ok(2), Err(NVCodecError::DecodeError), Ok(3), Err(NVCodecError::ParserError)]);
+
+        let (frames, err) = take_until_error(&rx, None);
+        assert_eq!(frames, [1, 2]);
+        assert!(matches!(err, Some(NVCodecError::DecodeError)));
+
+        let (frames, err) = take_until_error(&rx, None);
+        assert_eq!(frames, [3]);
+        assert!(matches!(err, Some(NVCodecError::ParserError)));
+
+        let (frames, err) = take_until_error(&rx, None);
+        assert!(frames.is_empty() && err.is_none());
+    }
+
+    #[test]
+    fn falls_back_to_the_decode_error() {
+        let rx = queue(vec![Ok(1)]);
+        let (frames, err) = take_until_error(&rx, Some(NVCodecError::ParserError));
+        assert_eq!(frames, [1]);
+        assert!(matches!(err, Some(NVCodecError::ParserError)));
+
+        let rx = queue(vec![Ok(1), Err(NVCodecError::DecodeError)]);
+        let (frames, err) = take_until_error(&rx, Some(NVCodecError::ParserError));
+        assert_eq!(frames, [1]);
+        assert!(matches!(err, Some(NVCodecError::DecodeError)));
+    }
+
+    #[test]
+    fn returns_the_frames_before_an_error_then_the_error() {
+        let rx = queue(vec![Ok(1), Err(NVCodecError::DecodeError), Ok(2)]);
+        let mut pending = None;
+
+        assert_eq!(take_frames(&rx, None, &mut pending).unwrap(), [1]);
+        assert!(matches!(take_frames(&rx, None, &mut pending), Err(NVCodecError::DecodeError)));
+        assert_eq!(take_frames(&rx, None, &mut pending).unwrap(), [2]);
+        assert!(take_frames(&rx, None, &mut pending).unwrap().is_empty());
+    }
+
+    #[test]
+    fn returns_a_leading_error_at_once() {
+        let rx = queue(vec![Err(NVCodecError::DecodeError), Ok(1)]);
+        let mut pending = None;
+
+        assert!(matches!(take_frames(&rx, None, &mut pending), Err(NVCodecError::DecodeError)));
+        assert!(pending.is_none());
+        assert_eq!(take_frames(&rx, None, &mut pending).unwrap(), [1]);
+    }
+
+    #[test]
+    fn holds_back_the_decode_error_behind_its_frames() {
+        let rx = queue(vec![Ok(1)]);
+        let mut pending = None;
+
+        assert_eq!(take_frames(&rx, Some(NVCodecError::ParserError), &mut pending).unwrap(), [1]);
+        assert!(matches!(take_frames(&rx, None, &mut pending), Err(NVCodecError::ParserError)));
+
+        // A held-back error goes first, and a new decode error waits behind it.
+        let rx = queue(vec![Ok(1), Err(NVCodecError::DecodeError), Ok(2)]);
+        assert_eq!(take_frames(&rx, None, &mut pending).unwrap(), [1]);
+        assert!(matches!(
+            take_frames(&rx, Some(NVCodecError::ParserError), &mut pending),
+            Err(NVCodecError::DecodeError)
+        ));
+        assert!(matches!(take_frames(&rx, None, &mut pending), Err(NVCodecError::ParserError)));
+        assert_eq!(take_frames(&rx, None, &mut pending).unwrap(), [2]);
+    }
 }
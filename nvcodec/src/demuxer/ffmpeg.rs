@@ -1,14 +1,10 @@
-use crate::error::NVCodecResult;
-use futures::{
-    stream::Stream,
-    task::AtomicWaker,
-};
+use crate::{channel::ChannelStream, error::NVCodecResult};
+use futures::stream::Stream;
 use std::{
     io,
     ffi::CString,
     path::Path,
     pin::Pin,
-    sync::{Arc, mpsc::{self, Receiver}},
     task::{Context, Poll},
     thread,
 };
@@ -18,7 +14,10 @@ use ffmpeg_next::{
         packet::{Packet as AVPacket, Mut},
         Parameters,
     },
-    util::color::{Range, Space},
+    util::{
+        color::{Range, Space},
+        rational::Rational,
+    },
 };
 
 pub struct Packet {
@@ -76,17 +75,21 @@ impl Packet {
 pub struct FFmpegDemuxStream {
     pub codec_id: CodecId,
     pub total_frames: i64,
+    /// Unit of packet timestamps and durations.
+    pub time_base: Rational,
+    /// pts of the first frame, 0 when unknown.
+    pub start_time: i64,
+    pub width: u32,
+    pub height: u32,
     pub color_space: Space,
     pub color_range: Range,
-    waker: Arc<AtomicWaker>,
-    rx: Receiver<NVCodecResult<Packet>>,
+    packets: ChannelStream<NVCodecResult<Packet>>,
 }
 
 impl FFmpegDemuxStream {
     pub fn new<P: AsRef<Path>>(path: &P) -> NVCodecResult<Self> {
-        let waker = Arc::new(AtomicWaker::new());
         let (tx, rx) =
-            mpsc::sync_channel::<NVCodecResult<Packet>>(8);
+            flume::bounded::<NVCodecResult<Packet>>(8);
 
         let mut ctx = ffmpeg_next::format::input(path)?;
 
@@ -96,21 +99,28 @@ impl FFmpegDemuxStream {
             .ok_or(ffmpeg_next::Error::StreamNotFound)?;
         let video_stream_index = stream.index();
         let total_frames = stream.frames();
+        let time_base = stream.time_base();
+        let start_time = match stream.start_time() {
+            ffmpeg_next::ffi::AV_NOPTS_VALUE => 0,
+            start_time => start_time,
+        };
         let stream_params = stream.parameters();
         let codec_ctx = stream.codec();
         let codec_id = codec_ctx.id();
 
-        let (color_range, color_space) = unsafe {
+        let (color_range, color_space, width, height) = unsafe {
             let params = *stream_params.as_ptr();
-            (params.color_range, params.color_space)
+            (params.color_range, params.color_space, params.width, params.height)
         };
 
-        let waker_in_thread: Arc<AtomicWaker> = waker.clone();
-
         thread::spawn(move || {
             let bsf_name = match codec_id {
                 CodecId::H264 => Some("h264_mp4toannexb"),
                 CodecId::HEVC => Some("hevc_mp4toannexb"),
+                // AVI MJPEG often omits the Huffman tables and relies on the
+                // standard ones, which NVDEC's JPEG parser does not assume.
+                // This adds them and keeps every other APPn/DHT segment.
+                CodecId::MJPEG => Some("mjpeg2jpeg"),
                 _ => None,
             };
             let bsf_ctx = match bsf_name {
@@ -118,10 +128,7 @@ impl FFmpegDemuxStream {
                     match BSFContext::new(name, stream_params) {
                         Ok(ctx) => Some(ctx),
                         Err(e) => {
-                            if tx.send(Err(e)).is_err() {
-                                return;
-                            }
-                            waker_in_thread.wake();
+                            let _ = tx.send(Err(e));
                             return;
                         }
                     }
@@ -136,20 +143,14 @@ impl FFmpegDemuxStream {
                             match bsf_ctx.send_packet(&mut packet) {
                                 Ok(_) => (),
                                 Err(e) => {
-                                    if tx.send(Err(e)).is_err() {
-                                        return;
-                                    }
-                                    waker_in_thread.wake();
+                                    let _ = tx.send(Err(e));
                                     return;
                                 }
                             }
                             match bsf_ctx.receive_packet() {
                                 Ok(packet) => packet,
                                 Err(e) => {
-                                    if tx.send(Err(e)).is_err() {
-                                        return;
-                                    }
-                                    waker_in_thread.wake();
+                                    let _ = tx.send(Err(e));
                                     return;
                                 }
                             }
@@ -165,43 +166,38 @@ impl FFmpegDemuxStream {
                     if tx.send(Ok(packet)).is_err() {
                         return;
                     }
-                    waker_in_thread.wake();
                 }
             }
-
-            std::mem::drop(tx);
-            waker_in_thread.wake();
         });
 
         Ok(Self {
             codec_id,
             total_frames,
+            time_base,
+            start_time,
+            width: width as _,
+            height: height as _,
             color_space: color_space.into(),
             color_range: color_range.into(),
-            waker,
-            rx,
+            packets: ChannelStream::new(rx),
         })
     }
 
+    /// Blocking counterpart of the `Stream` impl, for callers without an
+    /// async runtime. Both draw from the same demuxing thread.
+    pub fn packets(&self) -> impl Iterator<Item = NVCodecResult<Packet>> + '_ {
+        self.packets.receiver().iter()
+    }
+
 }
 
 impl Stream for FFmpegDemuxStream {
     type Item = NVCodecResult<Packet>;
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.waker.register(cx.waker());
-
-        match self.rx.try_recv() {
-            Ok(v) => {
-                Poll::Ready(Some(v))
-            }
-            Err(mpsc::TryRecvError::Empty) => {
-                Poll::Pending
-            }
-            Err(mpsc::TryRecvError::Disconnected) => {
-                Poll::Ready(None)
-            }
-        }
+    /// Ends after the last video packet of the file, or right after the
+    /// first error.
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.packets).poll_next(cx)
     }
 }
 
@@ -280,3 +276,115 @@ impl Drop for BSFContext {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{channel::tests::collect_within, error::NVCodecError};
+    use futures::task::noop_waker;
+    use std::{sync::mpsc, time::Duration};
+
+    fn packet(pts: i64) -> Packet {
+        let mut av_packet = AVPacket::copy(&[0, 0, 1, 0x65]);
+        av_packet.set_pts(Some(pts));
+        Packet {
+            av_packet,
+            color_space: Space::Unspecified,
+            color_range: Range::Unspecified,
+        }
+    }
+
+    /// A demuxer fed by `producer` in place of the FFmpeg demuxing thread,
+    /// through a channel of the same capacity.
+    fn stub_demuxer<F>(producer: F) -> FFmpegDemuxStream
+    where
+        F: FnOnce(flume::Sender<NVCodecResult<Packet>>) + Send + 'static,
+    {
+        let (tx, rx) = flume::bounded(8);
+        thread::spawn(move || producer(tx));
+
+        FFmpegDemuxStream {
+            codec_id: CodecId::H264,
+            total_frames: 0,
+            time_base: Rational::new(1, 90000),
+            start_time: 0,
+            width: 64,
+            height: 64,
+            color_space: Space::Unspecified,
+            color_range: Range::Unspecified,
+            packets: ChannelStream::new(rx),
+        }
+    }
+
+    #[test]
+    fn yields_every_packet_in_order() {
+        let demuxer = stub_demuxer(|tx| {
+            for pts in 0..1000 {
+                tx.send(Ok(packet(pts))).unwrap();
+                if pts % 100 == 0 {
+                    thread::sleep(Duration::from_millis(1));
+                } else {
+                    thread::yield_now();
+                }
+            }
+        });
+
+        let items = collect_within(demuxer, Duration::from_secs(30));
+        let pts: Vec<_> = items.into_iter().map(|item| item.unwrap().pts().unwrap()).collect();
+        assert_eq!(pts, (0..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ends_right_after_an_error() {
+        let demuxer = stub_demuxer(|tx| {
+            for pts in 0..3 {
+                tx.send(Ok(packet(pts))).unwrap();
+            }
+            let err = io::Error::new(io::ErrorKind::InvalidData, "corrupt input");
+            let _ = tx.send(Err(err.into()));
+        });
+
+        let items = collect_within(demuxer, Duration::from_secs(30));
+        assert_eq!(items.len(), 4);
+        assert!(items[..3].iter().all(|item| item.is_ok()));
+        assert!(matches!(items[3], Err(NVCodecError::IOError(_))));
+    }
+
+    #[test]
+    fn stays_open_until_the_demuxing_thread_ends() {
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let mut demuxer = stub_demuxer(move |tx| {
+            tx.send(Ok(packet(0))).unwrap();
+            release_rx.recv().unwrap();
+            tx.send(Ok(packet(1))).unwrap();
+        });
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let first = loop {
+            match Pin::new(&mut demuxer).poll_next(&mut cx) {
+                Poll::Ready(item) => break item,
+                Poll::Pending => thread::yield_now(),
+            }
+        };
+        assert_eq!(first.unwrap().unwrap().pts(), Some(0));
+        assert!(Pin::new(&mut demuxer).poll_next(&mut cx).is_pending());
+
+        release_tx.send(()).unwrap();
+        let rest = collect_within(demuxer, Duration::from_secs(30));
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].as_ref().unwrap().pts(), Some(1));
+    }
+
+    #[test]
+    fn blocking_packets_drain_the_same_channel() {
+        let demuxer = stub_demuxer(|tx| {
+            for pts in 0..20 {
+                tx.send(Ok(packet(pts))).unwrap();
+            }
+        });
+
+        let pts: Vec<_> = demuxer.packets().map(|item| item.unwrap().pts().unwrap()).collect();
+        assert_eq!(pts, (0..20).collect::<Vec<_>>());
+    }
+}
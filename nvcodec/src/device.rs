@@ -0,0 +1,287 @@
+use crate::{
+    codec::CuVideoCodecType,
+    cuda::{CuContext, CuDevice, CuStream},
+    decoder::{DisplayArea, NVDecoder, Size},
+    error::{NVCodecError, NVCodecResult},
+    ffi,
+};
+use std::sync::{Arc, Mutex};
+
+/// How `DeviceSelector` picks a GPU.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DevicePolicy {
+    Ordinal(i32),
+    /// Devices in turn, across all calls on the selector and its clones.
+    RoundRobin,
+    /// The device running the fewest decoders created by the selector.
+    LeastSessions,
+    /// The device with the most free memory right now, measured on every
+    /// device through its primary context, which is retained on first use
+    /// and kept like for a decoder.
+    MostFreeMemory,
+}
+
+struct SelectorState {
+    device_count: i32,
+    next: usize,
+    sessions: Vec<usize>,
+    // Primary contexts, retained on first use and kept for the selector's lifetime.
+    contexts: Vec<Option<CuContext>>,
+}
+
+/// Places decoders on the GPUs of a multi-GPU machine.
+///
+/// Sets up each device's primary context and a stream per decoder, so
+/// callers never touch contexts. Expects `cuda::init` to have been called.
+#[derive(Clone)]
+pub struct DeviceSelector {
+    state: Arc<Mutex<SelectorState>>,
+}
+
+impl DeviceSelector {
+    pub fn new() -> NVCodecResult<Self> {
+        ffi::load()?;
+
+        let mut device_count = 0;
+        let res = unsafe { ffi::cuDeviceGetCount(&mut device_count) };
+        wrap!((), res)?;
+
+        if device_count == 0 {
+            return Err(NVCodecError::NotSupported("No CUDA device found".to_string()));
+        }
+
+        Ok(Self {
+            state: Arc::new(Mutex::new(SelectorState {
+                device_count: device_count as _,
+                next: 0,
+                sessions: vec![0; device_count as _],
+                contexts: vec![None; device_count as _],
+            })),
+        })
+    }
+
+    pub fn device_count(&self) -> i32 {
+        self.state.lock().unwrap().device_count
+    }
+
+    /// Decoders created by the selector that are still alive on `device`.
+    pub fn sessions(&self, device: i32) -> usize {
+        let state = self.state.lock().unwrap();
+        state.sessions.get(device as usize).copied().unwrap_or(0)
+    }
+
+    pub fn select(&self, policy: DevicePolicy) -> NVCodecResult<i32> {
+        let free_memory = self.free_memory(policy)?;
+
+        select_locked(&mut self.state.lock().unwrap(), policy, &free_memory)
+    }
+
+    /// The device's primary context, retained on first use without holding
+    /// the lock.
+    pub fn context(&self, device: i32) -> NVCodecResult<CuContext> {
+        let retained = {
+            let state = self.state.lock().unwrap();
+            let slot = state.contexts.get(device as usize).ok_or_else(|| {
+                NVCodecError::NotSupported(format!("Device {} not found", device))
+            })?;
+            slot.clone()
+        };
+        if let Some(ctx) = retained {
+            return Ok(ctx);
+        }
+
+        let cu_device = CuDevice::new(device as _)?;
+        let ctx = CuContext::retain_primary_context(&cu_device)?;
+
+        // Another caller may have retained it meanwhile; keep the first.
+        let mut state = self.state.lock().unwrap();
+        Ok(state.contexts[device as usize].get_or_insert(ctx).clone())
+    }
+
+    /// A new stream on the device's primary context.
+    pub fn stream(&self, device: i32) -> NVCodecResult<CuStream> {
+        let ctx = self.context(device)?;
+        let _guard = ctx.guard()?;
+
+        Ok(CuStream::new()?)
+    }
+
+    /// Creates a decoder on a device picked by `policy`, on a stream of its
+    /// own. `NVDecoder::device` tells which device was picked.
+    pub fn create_decoder(
+        &self,
+        policy: DevicePolicy,
+        codec_type: CuVideoCodecType,
+        display_area: Option<DisplayArea>,
+        target_size: Option<Size>,
+        keyframe_only: bool,
+    ) -> NVCodecResult<NVDecoder> {
+        // Count the session before creating it, so concurrent callers using
+        // `LeastSessions` spread out.
+        let free_memory = self.free_memory(policy)?;
+        let session = {
+            let mut state = self.state.lock().unwrap();
+            let device = select_locked(&mut state, policy, &free_memory)?;
+            state.sessions[device as usize] += 1;
+            DeviceSession { state: self.state.clone(), device }
+        };
+
+        let stream = self.stream(session.device)?;
+        let mut decoder = NVDecoder::new(
+            &stream,
+            codec_type,
+            display_area,
+            target_size,
+            keyframe_only,
+        )?;
+        decoder.attach_session(session);
+
+        Ok(decoder)
+    }
+
+    /// Free memory of every device for `MostFreeMemory`, measured without
+    /// holding the lock; nothing for the other policies.
+    fn free_memory(&self, policy: DevicePolicy) -> NVCodecResult<Vec<usize>> {
+        if policy != DevicePolicy::MostFreeMemory {
+            return Ok(vec![]);
+        }
+
+        (0..self.device_count())
+            .map(|device| {
+                let _guard = self.context(device)?.guard()?;
+                let (mut free, mut total) = (0, 0);
+                let res = unsafe { ffi::cuMemGetInfo_v2(&mut free, &mut total) };
+                wrap!((), res)?;
+                Ok(free)
+            })
+            .collect()
+    }
+}
+
+/// Picks a device for `policy`. `free_memory` holds the free memory of each
+/// device for `MostFreeMemory`, see `DeviceSelector::free_memory`.
+fn select_locked(state: &mut SelectorState, policy: DevicePolicy, free_memory: &[usize]) -> NVCodecResult<i32> {
+    match policy {
+        DevicePolicy::Ordinal(device) => {
+            if device < 0 || device >= state.device_count {
+                let msg = format!(
+                    "Device {} not found, {} devices present",
+                    device,
+                    state.device_count
+                );
+                return Err(NVCodecError::NotSupported(msg));
+            }
+            Ok(device)
+        }
+        DevicePolicy::RoundRobin => {
+            let device = state.next % state.device_count as usize;
+            state.next = state.next.wrapping_add(1);
+            Ok(device as _)
+        }
+        DevicePolicy::LeastSessions => Ok(first_min_by_key(&state.sessions, |&sessions| sessions)),
+        DevicePolicy::MostFreeMemory => Ok(first_min_by_key(free_memory, |&free| std::cmp::Reverse(free))),
+    }
+}
+
+/// The index of the first item with the smallest key, 0 for no items.
+fn first_min_by_key<T, K: Ord>(items: &[T], key: impl Fn(&T) -> K) -> i32 {
+    (0..items.len()).min_by_key(|&i| key(&items[i])).unwrap_or(0) as _
+}
+
+/// Counts a decoder against its device in the selector that created it.
+pub(crate) struct DeviceSession {
+    state: Arc<Mutex<SelectorState>>,
+    device: i32,
+}
+
+impl Drop for DeviceSession {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.sessions[self.device as usize] -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(device_count: i32) -> SelectorState {
+        SelectorState {
+            device_count,
+            next: 0,
+            sessions: vec![0; device_count as _],
+            contexts: vec![None; device_count as _],
+        }
+    }
+
+    fn select(state: &mut SelectorState, policy: DevicePolicy) -> i32 {
+        select_locked(state, policy, &[]).unwrap()
+    }
+
+    #[test]
+    fn checks_ordinals() {
+        let mut state = state(2);
+        assert_eq!(select(&mut state, DevicePolicy::Ordinal(1)), 1);
+        assert!(select_locked(&mut state, DevicePolicy::Ordinal(2), &[]).is_err());
+        assert!(select_locked(&mut state, DevicePolicy::Ordinal(-1), &[]).is_err());
+    }
+
+    #[test]
+    fn round_robin_cycles_through_devices() {
+        let mut state = state(3);
+        let picked = (0..7).map(|_| select(&mut state, DevicePolicy::RoundRobin)).collect::<Vec<_>>();
+        assert_eq!(picked, [0, 1, 2, 0, 1, 2, 0]);
+
+        // Other policies don't advance the cycle.
+        select(&mut state, DevicePolicy::LeastSessions);
+        assert_eq!(select(&mut state, DevicePolicy::RoundRobin), 1);
+    }
+
+    #[test]
+    fn round_robin_survives_wrapping() {
+        let mut state = state(3);
+        state.next = usize::MAX;
+        assert_eq!(select(&mut state, DevicePolicy::RoundRobin), (usize::MAX % 3) as i32);
+        assert_eq!(select(&mut state, DevicePolicy::RoundRobin), 0);
+    }
+
+    #[test]
+    fn least_sessions_prefers_the_first_idle_device() {
+        let mut state = state(3);
+        assert_eq!(select(&mut state, DevicePolicy::LeastSessions), 0);
+
+        state.sessions = vec![2, 1, 1];
+        assert_eq!(select(&mut state, DevicePolicy::LeastSessions), 1);
+
+        state.sessions = vec![2, 1, 0];
+        assert_eq!(select(&mut state, DevicePolicy::LeastSessions), 2);
+    }
+
+    #[test]
+    fn most_free_memory_picks_the_largest() {
+        let mut state = state(3);
+        let pick = |state: &mut SelectorState, free: &[usize]| {
+            select_locked(state, DevicePolicy::MostFreeMemory, free).unwrap()
+        };
+        assert_eq!(pick(&mut state, &[1 << 30, 4 << 30, 2 << 30]), 1);
+        assert_eq!(pick(&mut state, &[4 << 30, 4 << 30, 2 << 30]), 0);
+    }
+
+    #[test]
+    fn sessions_are_released_on_drop() {
+        let state = Arc::new(Mutex::new(state(2)));
+
+        let sessions = (0..3)
+            .map(|_| {
+                let mut locked = state.lock().unwrap();
+                let device = select_locked(&mut locked, DevicePolicy::LeastSessions, &[]).unwrap();
+                locked.sessions[device as usize] += 1;
+                DeviceSession { state: state.clone(), device }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(state.lock().unwrap().sessions, [2, 1]);
+
+        drop(sessions);
+        assert_eq!(state.lock().unwrap().sessions, [0, 0]);
+    }
+}
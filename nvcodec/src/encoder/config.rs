@@ -0,0 +1,500 @@
+use thiserror::Error;
+
+/// `NVENC_INFINITE_GOPLENGTH`.
+pub const INFINITE_GOP_LENGTH: u32 = 0xffff_ffff;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EncodeCodec {
+    H264,
+    HEVC,
+    AV1,
+}
+
+impl EncodeCodec {
+    /// Largest QP the codec's rate control accepts.
+    pub fn max_qp(&self) -> u32 {
+        match self {
+            EncodeCodec::H264 | EncodeCodec::HEVC => 51,
+            EncodeCodec::AV1 => 255,
+        }
+    }
+}
+
+/// Layout of the device memory handed to the encoder.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InputFormat {
+    /// 8-bit 4:2:0, luma plane followed by interleaved chroma.
+    NV12,
+    /// 10-bit 4:2:0 in 16-bit samples, data in the high bits. This is what
+    /// NVDEC writes to P016 surfaces for 10-bit streams.
+    P010,
+    /// 8-bit 4:4:4, three full-size planes.
+    YUV444,
+    /// 8-bit packed, B, G, R, A in memory order.
+    ARGB,
+}
+
+impl InputFormat {
+    /// `NV_ENC_BUFFER_FORMAT` value.
+    pub fn buffer_format(&self) -> u32 {
+        match self {
+            InputFormat::NV12 => 0x1,
+            InputFormat::P010 => 0x10000,
+            InputFormat::YUV444 => 0x1000,
+            InputFormat::ARGB => 0x1000000,
+        }
+    }
+
+    pub fn bit_depth(&self) -> u32 {
+        match self {
+            InputFormat::P010 => 10,
+            _ => 8,
+        }
+    }
+
+    /// Chroma format of the encoded stream; NVENC converts RGB input to 4:2:0.
+    pub fn chroma_format_idc(&self) -> u32 {
+        match self {
+            InputFormat::YUV444 => 3,
+            _ => 1,
+        }
+    }
+
+    fn is_subsampled(&self) -> bool {
+        matches!(self, InputFormat::NV12 | InputFormat::P010)
+    }
+}
+
+/// Speed against quality, `P1` being the fastest.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Preset {
+    P1,
+    P2,
+    P3,
+    #[default]
+    P4,
+    P5,
+    P6,
+    P7,
+}
+
+/// What the preset is tuned for, as `NV_ENC_TUNING_INFO` values.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TuningInfo {
+    #[default]
+    HighQuality = 1,
+    LowLatency = 2,
+    UltraLowLatency = 3,
+    Lossless = 4,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RateControl {
+    /// Fixed QP per picture type.
+    ConstQp { intra: u32, inter_p: u32, inter_b: u32 },
+    /// Average bits per second, with peaks up to `max_bitrate` when set.
+    Vbr { average_bitrate: u32, max_bitrate: Option<u32> },
+    /// Constant bits per second.
+    Cbr { bitrate: u32 },
+}
+
+impl RateControl {
+    /// `NV_ENC_PARAMS_RC_MODE` value.
+    pub fn mode(&self) -> u32 {
+        match self {
+            RateControl::ConstQp { .. } => 0x0,
+            RateControl::Vbr { .. } => 0x1,
+            RateControl::Cbr { .. } => 0x2,
+        }
+    }
+}
+
+#[derive(Error, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EncoderConfigError {
+    #[error("Invalid frame size {width}x{height}")]
+    InvalidSize { width: u32, height: u32 },
+    #[error("Frame size {width}x{height} must be even for {format:?} input")]
+    OddSize { width: u32, height: u32, format: InputFormat },
+    #[error("Invalid frame rate {0}/{1}")]
+    InvalidFrameRate(u32, u32),
+    #[error("{codec:?} cannot be encoded from {format:?} input")]
+    UnsupportedInput { codec: EncodeCodec, format: InputFormat },
+    #[error("GOP length must be at least 1")]
+    InvalidGopLength,
+    #[error("{b_frames} B-frames do not fit in a GOP of {gop_length}")]
+    TooManyBFrames { b_frames: u32, gop_length: u32 },
+    #[error("B-frames add reordering delay, which {0:?} tuning rules out")]
+    BFramesWithLowLatency(TuningInfo),
+    #[error("Lossless tuning needs constant QP 0")]
+    LossyLossless,
+    #[error("QP {qp} exceeds the maximum of {max}")]
+    QpOutOfRange { qp: u32, max: u32 },
+    #[error("Bitrate must be positive")]
+    InvalidBitrate,
+    #[error("Maximum bitrate {max} is below the average bitrate {average}")]
+    MaxBelowAverage { average: u32, max: u32 },
+    #[error("{0:?} encoding is not in the Video Codec SDK this was built against")]
+    CodecNotInSdk(EncodeCodec),
+}
+
+/// What to encode and how, independent of any device.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EncoderConfig {
+    pub codec: EncodeCodec,
+    pub input_format: InputFormat,
+    pub width: u32,
+    pub height: u32,
+    /// Frames per second, as numerator and denominator.
+    pub frame_rate: (u32, u32),
+    pub preset: Preset,
+    pub tuning: TuningInfo,
+    pub rate_control: RateControl,
+    /// Frames from one IDR frame to the next, `None` for only the first.
+    pub gop_length: Option<u32>,
+    /// B-frames between consecutive reference frames.
+    pub b_frames: u32,
+}
+
+impl EncoderConfig {
+    /// 30 fps, preset P4 tuned for quality, 5 Mbit/s VBR, an IDR frame every
+    /// 250 frames and no B-frames.
+    pub fn new(codec: EncodeCodec, input_format: InputFormat, width: u32, height: u32) -> Self {
+        Self {
+            codec,
+            input_format,
+            width,
+            height,
+            frame_rate: (30, 1),
+            preset: Preset::default(),
+            tuning: TuningInfo::default(),
+            rate_control: RateControl::Vbr { average_bitrate: 5_000_000, max_bitrate: None },
+            gop_length: Some(250),
+            b_frames: 0,
+        }
+    }
+
+    /// Validates the config and works out the values NVENC is configured with.
+    pub fn plan(&self) -> Result<EncodePlan, EncoderConfigError> {
+        let (width, height) = (self.width, self.height);
+        if width == 0 || height == 0 {
+            return Err(EncoderConfigError::InvalidSize { width, height });
+        }
+        if self.input_format.is_subsampled() && (width % 2 != 0 || height % 2 != 0) {
+            return Err(EncoderConfigError::OddSize { width, height, format: self.input_format });
+        }
+
+        let (num, den) = self.frame_rate;
+        if num == 0 || den == 0 {
+            return Err(EncoderConfigError::InvalidFrameRate(num, den));
+        }
+
+        if self.codec == EncodeCodec::AV1 && !cfg!(nvcodec_sdk_12_0) {
+            return Err(EncoderConfigError::CodecNotInSdk(self.codec));
+        }
+        // No 10-bit H.264 and no 4:4:4 AV1 on NVENC.
+        let unsupported = matches!(
+            (self.codec, self.input_format),
+            (EncodeCodec::H264, InputFormat::P010) | (EncodeCodec::AV1, InputFormat::YUV444)
+        );
+        if unsupported {
+            return Err(EncoderConfigError::UnsupportedInput {
+                codec: self.codec,
+                format: self.input_format,
+            });
+        }
+
+        let gop_length = match self.gop_length {
+            Some(0) => return Err(EncoderConfigError::InvalidGopLength),
+            Some(gop_length) => gop_length,
+            None => INFINITE_GOP_LENGTH,
+        };
+        if self.b_frames > 0 {
+            if self.b_frames >= gop_length {
+                return Err(EncoderConfigError::TooManyBFrames {
+                    b_frames: self.b_frames,
+                    gop_length,
+                });
+            }
+            if matches!(self.tuning, TuningInfo::LowLatency | TuningInfo::UltraLowLatency) {
+                return Err(EncoderConfigError::BFramesWithLowLatency(self.tuning));
+            }
+        }
+
+        let mut const_qp = [0; 3];
+        let (mut average_bitrate, mut max_bitrate) = (0, 0);
+        match self.rate_control {
+            RateControl::ConstQp { intra, inter_p, inter_b } => {
+                let max = self.codec.max_qp();
+                if let Some(&qp) = [intra, inter_p, inter_b].iter().find(|&&qp| qp > max) {
+                    return Err(EncoderConfigError::QpOutOfRange { qp, max });
+                }
+                const_qp = [intra, inter_p, inter_b];
+            }
+            RateControl::Vbr { average_bitrate: average, max_bitrate: max } => {
+                if average == 0 {
+                    return Err(EncoderConfigError::InvalidBitrate);
+                }
+                if let Some(max) = max.filter(|&max| max < average) {
+                    return Err(EncoderConfigError::MaxBelowAverage { average, max });
+                }
+                average_bitrate = average;
+                max_bitrate = max.unwrap_or(0);
+            }
+            RateControl::Cbr { bitrate } => {
+                if bitrate == 0 {
+                    return Err(EncoderConfigError::InvalidBitrate);
+                }
+                average_bitrate = bitrate;
+                max_bitrate = bitrate;
+            }
+        }
+        let lossless_qp = matches!(self.rate_control, RateControl::ConstQp { .. }) && const_qp == [0; 3];
+        if self.tuning == TuningInfo::Lossless && !lossless_qp {
+            return Err(EncoderConfigError::LossyLossless);
+        }
+
+        Ok(EncodePlan {
+            codec: self.codec,
+            preset: self.preset,
+            tuning_info: self.tuning as u32,
+            buffer_format: self.input_format.buffer_format(),
+            width,
+            height,
+            frame_rate_num: num,
+            frame_rate_den: den,
+            gop_length,
+            idr_period: gop_length,
+            frame_interval_p: self.b_frames + 1,
+            chroma_format_idc: self.input_format.chroma_format_idc(),
+            bit_depth: self.input_format.bit_depth(),
+            rc_mode: self.rate_control.mode(),
+            const_qp,
+            average_bitrate,
+            max_bitrate,
+        })
+    }
+}
+
+/// The values an `EncoderConfig` puts into NVENC's structures, on top of the
+/// preset's defaults.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EncodePlan {
+    pub codec: EncodeCodec,
+    pub preset: Preset,
+    /// `NV_ENC_TUNING_INFO` value.
+    pub tuning_info: u32,
+    /// `NV_ENC_BUFFER_FORMAT` value.
+    pub buffer_format: u32,
+    pub width: u32,
+    pub height: u32,
+    pub frame_rate_num: u32,
+    pub frame_rate_den: u32,
+    pub gop_length: u32,
+    pub idr_period: u32,
+    /// B-frames plus one.
+    pub frame_interval_p: u32,
+    pub chroma_format_idc: u32,
+    pub bit_depth: u32,
+    /// `NV_ENC_PARAMS_RC_MODE` value.
+    pub rc_mode: u32,
+    /// Intra, P and B QPs, all 0 unless `rc_mode` is constant QP.
+    pub const_qp: [u32; 3],
+    /// Bits per second, 0 unless `rc_mode` targets a bitrate.
+    pub average_bitrate: u32,
+    /// 0 leaves the limit to the driver.
+    pub max_bitrate: u32,
+}
+
+impl EncodePlan {
+    /// Pictures the encoder holds back before the first packet comes out.
+    pub fn reorder_delay(&self) -> u32 {
+        self.frame_interval_p - 1
+    }
+
+    /// The plan as the `NV_ENC_CONFIG` members it overrides.
+    pub fn config_values(&self) -> ConfigValues {
+        ConfigValues {
+            gop_length: self.gop_length,
+            frame_interval_p: self.frame_interval_p as i32,
+            rate_control_mode: self.rc_mode,
+            qp_inter_p: self.const_qp[1],
+            qp_inter_b: self.const_qp[2],
+            qp_intra: self.const_qp[0],
+            average_bit_rate: self.average_bitrate,
+            max_bit_rate: self.max_bitrate,
+            idr_period: self.idr_period,
+            chroma_format_idc: self.chroma_format_idc,
+            repeat_headers: true,
+            bit_depth: self.bit_depth,
+            pixel_bit_depth_minus8: self.bit_depth - 8,
+        }
+    }
+}
+
+/// Members of `NV_ENC_CONFIG` and of its codec config, in the header's types,
+/// that an `EncodePlan` writes over the preset's.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ConfigValues {
+    pub gop_length: u32,
+    pub frame_interval_p: i32,
+    /// `rcParams.rateControlMode`.
+    pub rate_control_mode: u32,
+    /// `rcParams.constQP`.
+    pub qp_inter_p: u32,
+    pub qp_inter_b: u32,
+    pub qp_intra: u32,
+    /// `rcParams.averageBitRate` and `rcParams.maxBitRate`.
+    pub average_bit_rate: u32,
+    pub max_bit_rate: u32,
+    pub idr_period: u32,
+    pub chroma_format_idc: u32,
+    /// `repeatSPSPPS` for H.264 and HEVC, `repeatSeqHdr` for AV1, so each
+    /// GOP can be decoded on its own.
+    pub repeat_headers: bool,
+    /// `inputBitDepth` and `outputBitDepth` since SDK 12.2.
+    pub bit_depth: u32,
+    /// The bit depth bitfields of earlier SDKs.
+    pub pixel_bit_depth_minus8: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(codec: EncodeCodec, input_format: InputFormat) -> EncoderConfig {
+        EncoderConfig::new(codec, input_format, 1920, 1080)
+    }
+
+    #[test]
+    fn default_plan() {
+        let plan = config(EncodeCodec::HEVC, InputFormat::P010).plan().unwrap();
+        assert_eq!(plan.buffer_format, 0x10000);
+        assert_eq!(plan.tuning_info, 1);
+        assert_eq!((plan.frame_rate_num, plan.frame_rate_den), (30, 1));
+        assert_eq!((plan.gop_length, plan.idr_period), (250, 250));
+        assert_eq!(plan.reorder_delay(), 0);
+
+        let values = plan.config_values();
+        assert_eq!(values.frame_interval_p, 1);
+        assert_eq!(values.rate_control_mode, 0x1);
+        assert_eq!((values.average_bit_rate, values.max_bit_rate), (5_000_000, 0));
+        assert_eq!((values.qp_inter_p, values.qp_inter_b, values.qp_intra), (0, 0, 0));
+        assert_eq!(values.chroma_format_idc, 1);
+        assert_eq!((values.bit_depth, values.pixel_bit_depth_minus8), (10, 2));
+        assert!(values.repeat_headers);
+    }
+
+    #[test]
+    fn const_qp_and_b_frames() {
+        let mut config = config(EncodeCodec::H264, InputFormat::YUV444);
+        config.rate_control = RateControl::ConstQp { intra: 20, inter_p: 23, inter_b: 25 };
+        config.gop_length = None;
+        config.b_frames = 2;
+        let plan = config.plan().unwrap();
+        assert_eq!(plan.reorder_delay(), 2);
+        assert_eq!(plan.gop_length, INFINITE_GOP_LENGTH);
+
+        let values = plan.config_values();
+        assert_eq!(values.frame_interval_p, 3);
+        assert_eq!(values.rate_control_mode, 0x0);
+        assert_eq!((values.qp_inter_p, values.qp_inter_b, values.qp_intra), (23, 25, 20));
+        assert_eq!((values.average_bit_rate, values.max_bit_rate), (0, 0));
+        assert_eq!(values.chroma_format_idc, 3);
+        assert_eq!(values.bit_depth, 8);
+    }
+
+    #[test]
+    fn cbr() {
+        let mut config = config(EncodeCodec::HEVC, InputFormat::ARGB);
+        config.rate_control = RateControl::Cbr { bitrate: 8_000_000 };
+        let values = config.plan().unwrap().config_values();
+        assert_eq!(values.rate_control_mode, 0x2);
+        assert_eq!((values.average_bit_rate, values.max_bit_rate), (8_000_000, 8_000_000));
+        assert_eq!(values.chroma_format_idc, 1);
+    }
+
+    #[test]
+    fn invalid_frames() {
+        let mut config = config(EncodeCodec::H264, InputFormat::NV12);
+        config.width = 0;
+        assert_eq!(config.plan(), Err(EncoderConfigError::InvalidSize { width: 0, height: 1080 }));
+
+        config.width = 1919;
+        assert_eq!(
+            config.plan(),
+            Err(EncoderConfigError::OddSize { width: 1919, height: 1080, format: InputFormat::NV12 })
+        );
+        config.input_format = InputFormat::YUV444;
+        assert!(config.plan().is_ok());
+
+        config.frame_rate = (30, 0);
+        assert_eq!(config.plan(), Err(EncoderConfigError::InvalidFrameRate(30, 0)));
+    }
+
+    #[test]
+    fn unsupported_input() {
+        for (codec, format) in [
+            (EncodeCodec::H264, InputFormat::P010),
+            (EncodeCodec::AV1, InputFormat::YUV444),
+        ] {
+            let plan = config(codec, format).plan();
+            if codec == EncodeCodec::AV1 && !cfg!(nvcodec_sdk_12_0) {
+                assert_eq!(plan, Err(EncoderConfigError::CodecNotInSdk(codec)));
+            } else {
+                assert_eq!(plan, Err(EncoderConfigError::UnsupportedInput { codec, format }));
+            }
+        }
+    }
+
+    #[test]
+    fn gop_structure() {
+        let mut config = config(EncodeCodec::HEVC, InputFormat::NV12);
+        config.gop_length = Some(0);
+        assert_eq!(config.plan(), Err(EncoderConfigError::InvalidGopLength));
+
+        config.gop_length = Some(4);
+        config.b_frames = 4;
+        assert_eq!(
+            config.plan(),
+            Err(EncoderConfigError::TooManyBFrames { b_frames: 4, gop_length: 4 })
+        );
+
+        config.b_frames = 3;
+        assert!(config.plan().is_ok());
+        for tuning in [TuningInfo::LowLatency, TuningInfo::UltraLowLatency] {
+            config.tuning = tuning;
+            assert_eq!(config.plan(), Err(EncoderConfigError::BFramesWithLowLatency(tuning)));
+        }
+    }
+
+    #[test]
+    fn rate_control() {
+        let mut config = config(EncodeCodec::H264, InputFormat::NV12);
+        config.rate_control = RateControl::ConstQp { intra: 0, inter_p: 52, inter_b: 0 };
+        assert_eq!(config.plan(), Err(EncoderConfigError::QpOutOfRange { qp: 52, max: 51 }));
+
+        config.rate_control = RateControl::Vbr { average_bitrate: 0, max_bitrate: None };
+        assert_eq!(config.plan(), Err(EncoderConfigError::InvalidBitrate));
+        config.rate_control = RateControl::Cbr { bitrate: 0 };
+        assert_eq!(config.plan(), Err(EncoderConfigError::InvalidBitrate));
+
+        config.rate_control = RateControl::Vbr { average_bitrate: 2_000, max_bitrate: Some(1_000) };
+        assert_eq!(
+            config.plan(),
+            Err(EncoderConfigError::MaxBelowAverage { average: 2_000, max: 1_000 })
+        );
+    }
+
+    #[test]
+    fn lossless() {
+        let mut config = config(EncodeCodec::HEVC, InputFormat::YUV444);
+        config.tuning = TuningInfo::Lossless;
+        assert_eq!(config.plan(), Err(EncoderConfigError::LossyLossless));
+
+        config.rate_control = RateControl::ConstQp { intra: 0, inter_p: 1, inter_b: 0 };
+        assert_eq!(config.plan(), Err(EncoderConfigError::LossyLossless));
+
+        config.rate_control = RateControl::ConstQp { intra: 0, inter_p: 0, inter_b: 0 };
+        assert_eq!(config.plan().unwrap().tuning_info, 4);
+    }
+}
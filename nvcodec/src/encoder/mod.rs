@@ -0,0 +1,143 @@
+mod config;
+#[cfg(feature = "nvenc")]
+mod session;
+#[cfg(any(feature = "nvenc", test))]
+mod timestamps;
+
+pub use config::*;
+#[cfg(feature = "nvenc")]
+pub use session::*;
+
+use crate::{
+    cuda::PitchedDeviceMemory,
+    decoder::DecodedFrame,
+    error::{NVCodecError, NVCodecResult},
+    event::Fence,
+    surface::VideoSurfaceFormat,
+};
+#[cfg(feature = "npp")]
+use crate::cuda::CuStream;
+#[cfg(feature = "npp")]
+use npp::{color::PixelFormat, image::DeviceImage};
+
+/// A picture in device memory, kept alive until the encoder is done with it.
+pub struct InputFrame {
+    pub mem: PitchedDeviceMemory,
+    pub width: usize,
+    pub height: usize,
+    pub format: InputFormat,
+    pub pts: i64,
+    /// Work that must finish before the encoder reads `mem`. Without one,
+    /// `mem` must already be filled.
+    pub ready: Option<Fence>,
+}
+
+impl InputFrame {
+    pub fn new(
+        mem: PitchedDeviceMemory,
+        width: usize,
+        height: usize,
+        format: InputFormat,
+        pts: i64,
+    ) -> Self {
+        Self { mem, width, height, format, pts, ready: None }
+    }
+}
+
+/// Takes the pts of the frame's packet and waits for the copy into `buf`.
+/// P016 surfaces convert only from streams of up to 10 bits, whose samples
+/// fit P010.
+impl TryFrom<DecodedFrame> for InputFrame {
+    type Error = NVCodecError;
+
+    fn try_from(frame: DecodedFrame) -> NVCodecResult<Self> {
+        let format = match frame.surface_format {
+            VideoSurfaceFormat::NV12 => InputFormat::NV12,
+            VideoSurfaceFormat::P016 if frame.bit_depth <= 10 => InputFormat::P010,
+            VideoSurfaceFormat::P016 => {
+                let msg = format!(
+                    "{}-bit samples cannot be encoded, NVENC takes up to 10",
+                    frame.bit_depth
+                );
+                return Err(NVCodecError::NotSupported(msg));
+            }
+            VideoSurfaceFormat::YUV444 => InputFormat::YUV444,
+            surface_format => {
+                let msg = format!("{:?} surfaces cannot be encoded", surface_format);
+                return Err(NVCodecError::NotSupported(msg));
+            }
+        };
+
+        Ok(Self {
+            mem: frame.buf,
+            width: frame.width,
+            height: frame.height,
+            format,
+            pts: frame.packet_data.map(|data| data.pts).unwrap_or_default(),
+            ready: Some(frame.copied),
+        })
+    }
+}
+
+impl InputFrame {
+    /// Copies `image` into memory the encoder can keep, asynchronously on
+    /// `stream`, whose context must be current. `ready` fires once the copy
+    /// is done; keep `image` alive until then. The pts is 0; set it as
+    /// needed.
+    #[cfg(feature = "npp")]
+    pub fn from_device_image(image: &DeviceImage, stream: &CuStream) -> NVCodecResult<Self> {
+        let format = match image.pixel_format {
+            PixelFormat::NV12 => InputFormat::NV12,
+            PixelFormat::YUV444 => InputFormat::YUV444,
+            _ => {
+                // A P016 image does not say how many of its bits are used.
+                let msg = "Only NV12 and YUV444 images convert directly, build P010 \
+                    and packed ARGB input with InputFrame::new".to_string();
+                return Err(NVCodecError::NotSupported(msg));
+            }
+        };
+
+        let src = &image.mem;
+        let mem = PitchedDeviceMemory::new(src.width as _, src.height as _, stream)?;
+        mem.copy_from_raw(src.ptr as _, src.pitch as _, src.width as _, src.height as _, true, Some(stream))?;
+
+        Ok(Self {
+            ready: Some(Fence::record(stream)?),
+            ..Self::new(mem, image.width, image.height, format, 0)
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PictureType {
+    IDR,
+    I,
+    P,
+    B,
+    Other,
+}
+
+impl PictureType {
+    /// From an `NV_ENC_PIC_TYPE` value.
+    pub fn from_raw(pic_type: u32) -> Self {
+        match pic_type {
+            0x3 => PictureType::IDR,
+            0x2 => PictureType::I,
+            0x0 | 0x7 => PictureType::P,
+            0x1 | 0x4 => PictureType::B,
+            _ => PictureType::Other,
+        }
+    }
+}
+
+/// One coded picture, Annex B for H.264 and HEVC and OBUs for AV1.
+#[derive(Clone, Debug)]
+pub struct EncodedPacket {
+    pub data: Vec<u8>,
+    /// The pts of the input frame.
+    pub pts: i64,
+    pub dts: i64,
+    /// Whether decoding can start here, true for IDR pictures.
+    pub key: bool,
+    pub picture_type: PictureType,
+}
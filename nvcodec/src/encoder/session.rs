@@ -0,0 +1,502 @@
+use super::{
+    timestamps::DtsQueue, EncodeCodec, EncodePlan, EncodedPacket, EncoderConfig, InputFrame,
+    PictureType, Preset,
+};
+use crate::{
+    cuda::{CuContext, CuStream},
+    error::{NVCodecError, NVCodecResult},
+    ffi,
+};
+use std::{collections::VecDeque, ffi::CStr, os::raw::c_void};
+
+/// Calls an entry of the NVENC function list, reporting missing ones as
+/// `NV_ENC_ERR_UNIMPLEMENTED`.
+macro_rules! nvenc {
+    ($api:expr, $name:ident($($arg:expr),* $(,)?)) => {
+        match $api.$name {
+            Some(function) => function($($arg),*),
+            None => ffi::_NVENCSTATUS_NV_ENC_ERR_UNIMPLEMENTED,
+        }
+    };
+}
+
+fn codec_guid(codec: EncodeCodec) -> ffi::GUID {
+    match codec {
+        EncodeCodec::H264 => ffi::NV_ENC_CODEC_H264_GUID,
+        EncodeCodec::HEVC => ffi::NV_ENC_CODEC_HEVC_GUID,
+        EncodeCodec::AV1 => ffi::NV_ENC_CODEC_AV1_GUID,
+    }
+}
+
+fn preset_guid(preset: Preset) -> ffi::GUID {
+    match preset {
+        Preset::P1 => ffi::NV_ENC_PRESET_P1_GUID,
+        Preset::P2 => ffi::NV_ENC_PRESET_P2_GUID,
+        Preset::P3 => ffi::NV_ENC_PRESET_P3_GUID,
+        Preset::P4 => ffi::NV_ENC_PRESET_P4_GUID,
+        Preset::P5 => ffi::NV_ENC_PRESET_P5_GUID,
+        Preset::P6 => ffi::NV_ENC_PRESET_P6_GUID,
+        Preset::P7 => ffi::NV_ENC_PRESET_P7_GUID,
+    }
+}
+
+/// Writes `plan` over the preset's config.
+fn apply_plan(plan: &EncodePlan, config: &mut ffi::NV_ENC_CONFIG) {
+    let values = plan.config_values();
+    config.profileGUID = ffi::NV_ENC_CODEC_PROFILE_AUTOSELECT_GUID;
+    config.gopLength = values.gop_length;
+    config.frameIntervalP = values.frame_interval_p;
+
+    let rc = &mut config.rcParams;
+    rc.rateControlMode = values.rate_control_mode;
+    rc.constQP.qpInterP = values.qp_inter_p;
+    rc.constQP.qpInterB = values.qp_inter_b;
+    rc.constQP.qpIntra = values.qp_intra;
+    rc.averageBitRate = values.average_bit_rate;
+    rc.maxBitRate = values.max_bit_rate;
+
+    let codec_config = &mut config.encodeCodecConfig;
+    let repeat_headers = values.repeat_headers as u32;
+    unsafe {
+        match plan.codec {
+            EncodeCodec::H264 => {
+                let h264 = &mut codec_config.h264Config;
+                h264.idrPeriod = values.idr_period;
+                h264.chromaFormatIDC = values.chroma_format_idc;
+                h264.set_repeatSPSPPS(repeat_headers);
+            }
+            EncodeCodec::HEVC => {
+                let hevc = &mut codec_config.hevcConfig;
+                hevc.idrPeriod = values.idr_period;
+                hevc.set_chromaFormatIDC(values.chroma_format_idc);
+                hevc.set_repeatSPSPPS(repeat_headers);
+                #[cfg(nvcodec_sdk_12_2)]
+                {
+                    hevc.inputBitDepth = values.bit_depth;
+                    hevc.outputBitDepth = values.bit_depth;
+                }
+                #[cfg(not(nvcodec_sdk_12_2))]
+                hevc.set_pixelBitDepthMinus8(values.pixel_bit_depth_minus8);
+            }
+            #[cfg(nvcodec_sdk_12_0)]
+            EncodeCodec::AV1 => {
+                let av1 = &mut codec_config.av1Config;
+                av1.idrPeriod = values.idr_period;
+                av1.set_chromaFormatIDC(values.chroma_format_idc);
+                av1.set_repeatSeqHdr(repeat_headers);
+                #[cfg(nvcodec_sdk_12_2)]
+                {
+                    av1.inputBitDepth = values.bit_depth;
+                    av1.outputBitDepth = values.bit_depth;
+                }
+                #[cfg(not(nvcodec_sdk_12_2))]
+                {
+                    av1.set_inputPixelBitDepthMinus8(values.pixel_bit_depth_minus8);
+                    av1.set_pixelBitDepthMinus8(values.pixel_bit_depth_minus8);
+                }
+            }
+            #[cfg(not(nvcodec_sdk_12_0))]
+            EncodeCodec::AV1 => unreachable!("EncoderConfig::plan rejects AV1 before SDK 12.0"),
+        }
+    }
+}
+
+struct InFlight {
+    // Registered and mapped until its packet is out.
+    frame: InputFrame,
+    registered: ffi::NV_ENC_REGISTERED_PTR,
+    mapped: ffi::NV_ENC_INPUT_PTR,
+    bitstream: ffi::NV_ENC_OUTPUT_PTR,
+}
+
+/// An NVENC session encoding device frames in synchronous mode.
+///
+/// Packets come out in coded order. With B-frames the encoder holds frames
+/// back, so `encode` returns nothing for a while and then several packets.
+pub struct NVEncoder {
+    ctx: CuContext,
+    stream: CuStream,
+    // NVENC keeps the address of the stream handle.
+    raw_stream: Box<ffi::CUstream>,
+    api: Box<ffi::NV_ENCODE_API_FUNCTION_LIST>,
+    encoder: *mut c_void,
+    plan: EncodePlan,
+    bitstreams: Vec<ffi::NV_ENC_OUTPUT_PTR>,
+    free_bitstreams: Vec<ffi::NV_ENC_OUTPUT_PTR>,
+    in_flight: VecDeque<InFlight>,
+    dts: DtsQueue,
+    frame_idx: u32,
+}
+
+// The session is only used through `&mut self`, always with the context
+// pushed.
+unsafe impl Send for NVEncoder {}
+
+impl NVEncoder {
+    /// Opens a session on `stream`'s context. Input is read on `stream`.
+    pub fn new(stream: &CuStream, config: &EncoderConfig) -> NVCodecResult<Self> {
+        ffi::load_nvenc()?;
+
+        let plan = config.plan()?;
+
+        let ctx = stream.get_context()?;
+        let _guard = ctx.clone().guard()?;
+
+        let mut max_version = 0;
+        let status = unsafe { ffi::NvEncodeAPIGetMaxSupportedVersion(&mut max_version) };
+        if status != ffi::_NVENCSTATUS_NV_ENC_SUCCESS {
+            let message = "NvEncodeAPIGetMaxSupportedVersion failed".to_string();
+            return Err(NVCodecError::EncodeError { status: status as _, message });
+        }
+        let version = (ffi::NVENCAPI_MAJOR_VERSION << 4) | ffi::NVENCAPI_MINOR_VERSION;
+        if max_version < version {
+            let msg = format!(
+                "The driver supports NVENC API {}.{}, built against {}.{}",
+                max_version >> 4,
+                max_version & 0xf,
+                ffi::NVENCAPI_MAJOR_VERSION,
+                ffi::NVENCAPI_MINOR_VERSION,
+            );
+            return Err(NVCodecError::NotSupported(msg));
+        }
+
+        let mut api: Box<ffi::NV_ENCODE_API_FUNCTION_LIST> = Box::new(unsafe { std::mem::zeroed() });
+        api.version = ffi::NV_ENCODE_API_FUNCTION_LIST_VER;
+        let status = unsafe { ffi::NvEncodeAPICreateInstance(&mut *api) };
+        if status != ffi::_NVENCSTATUS_NV_ENC_SUCCESS {
+            let message = "NvEncodeAPICreateInstance failed".to_string();
+            return Err(NVCodecError::EncodeError { status: status as _, message });
+        }
+
+        let mut params: ffi::NV_ENC_OPEN_ENCODE_SESSION_EX_PARAMS = unsafe { std::mem::zeroed() };
+        params.version = ffi::NV_ENC_OPEN_ENCODE_SESSION_EX_PARAMS_VER;
+        params.deviceType = ffi::_NV_ENC_DEVICE_TYPE_NV_ENC_DEVICE_TYPE_CUDA;
+        params.device = unsafe { ctx.get_raw() as _ };
+        params.apiVersion = ffi::NVENCAPI_VERSION;
+
+        let mut encoder = std::ptr::null_mut();
+        let status = unsafe { nvenc!(api, nvEncOpenEncodeSessionEx(&mut params, &mut encoder)) };
+        if status != ffi::_NVENCSTATUS_NV_ENC_SUCCESS {
+            let message = "nvEncOpenEncodeSessionEx failed".to_string();
+            return Err(NVCodecError::EncodeError { status: status as _, message });
+        }
+
+        // From here on, dropping `encoder` closes the session.
+        let mut encoder = Self {
+            ctx: ctx.clone(),
+            stream: stream.clone(),
+            raw_stream: Box::new(unsafe { stream.get_raw() as _ }),
+            api,
+            encoder,
+            plan,
+            bitstreams: vec![],
+            free_bitstreams: vec![],
+            in_flight: VecDeque::new(),
+            dts: DtsQueue::new(plan.reorder_delay()),
+            frame_idx: 0,
+        };
+        encoder.initialize()?;
+
+        Ok(encoder)
+    }
+
+    fn initialize(&mut self) -> NVCodecResult<()> {
+        let plan = self.plan;
+        let encode_guid = codec_guid(plan.codec);
+        let preset_guid = preset_guid(plan.preset);
+
+        let mut preset: Box<ffi::NV_ENC_PRESET_CONFIG> = Box::new(unsafe { std::mem::zeroed() });
+        preset.version = ffi::NV_ENC_PRESET_CONFIG_VER;
+        preset.presetCfg.version = ffi::NV_ENC_CONFIG_VER;
+        let status = unsafe {
+            nvenc!(self.api, nvEncGetEncodePresetConfigEx(
+                self.encoder,
+                encode_guid,
+                preset_guid,
+                plan.tuning_info as _,
+                &mut *preset,
+            ))
+        };
+        self.check(status)?;
+
+        let mut config = preset.presetCfg;
+        config.version = ffi::NV_ENC_CONFIG_VER;
+        apply_plan(&plan, &mut config);
+
+        let mut params: ffi::NV_ENC_INITIALIZE_PARAMS = unsafe { std::mem::zeroed() };
+        params.version = ffi::NV_ENC_INITIALIZE_PARAMS_VER;
+        params.encodeGUID = encode_guid;
+        params.presetGUID = preset_guid;
+        params.encodeWidth = plan.width;
+        params.encodeHeight = plan.height;
+        params.darWidth = plan.width;
+        params.darHeight = plan.height;
+        params.frameRateNum = plan.frame_rate_num;
+        params.frameRateDen = plan.frame_rate_den;
+        params.enablePTD = 1;
+        params.encodeConfig = &mut config;
+        params.maxEncodeWidth = plan.width;
+        params.maxEncodeHeight = plan.height;
+        params.tuningInfo = plan.tuning_info as _;
+
+        let status = unsafe { nvenc!(self.api, nvEncInitializeEncoder(self.encoder, &mut params)) };
+        self.check(status)?;
+
+        let raw_stream = &mut *self.raw_stream as *mut ffi::CUstream as *mut c_void;
+        let status = unsafe {
+            nvenc!(self.api, nvEncSetIOCudaStreams(self.encoder, raw_stream as _, raw_stream as _))
+        };
+        self.check(status)?;
+
+        Ok(())
+    }
+
+    pub fn plan(&self) -> &EncodePlan {
+        &self.plan
+    }
+
+    /// Queues `frame` and returns the packets that became ready.
+    pub fn encode(&mut self, frame: InputFrame) -> NVCodecResult<Vec<EncodedPacket>> {
+        let plan = &self.plan;
+        if frame.width != plan.width as usize ||
+            frame.height != plan.height as usize ||
+            frame.format.buffer_format() != plan.buffer_format {
+            return Err(NVCodecError::SurfaceShapeMismatch);
+        }
+
+        let _guard = self.ctx.clone().guard()?;
+
+        if let Some(ready) = &frame.ready {
+            ready.wait_on(&self.stream)?;
+        }
+
+        let mut register: ffi::NV_ENC_REGISTER_RESOURCE = unsafe { std::mem::zeroed() };
+        register.version = ffi::NV_ENC_REGISTER_RESOURCE_VER;
+        register.resourceType = ffi::_NV_ENC_INPUT_RESOURCE_TYPE_NV_ENC_INPUT_RESOURCE_TYPE_CUDADEVICEPTR;
+        register.width = frame.width as _;
+        register.height = frame.height as _;
+        register.pitch = frame.mem.pitch as _;
+        register.resourceToRegister = frame.mem.ptr as ffi::CUdeviceptr as *mut c_void;
+        register.bufferFormat = plan.buffer_format as _;
+        register.bufferUsage = ffi::_NV_ENC_BUFFER_USAGE_NV_ENC_INPUT_IMAGE;
+        let status = unsafe { nvenc!(self.api, nvEncRegisterResource(self.encoder, &mut register)) };
+        self.check(status)?;
+
+        let mut map: ffi::NV_ENC_MAP_INPUT_RESOURCE = unsafe { std::mem::zeroed() };
+        map.version = ffi::NV_ENC_MAP_INPUT_RESOURCE_VER;
+        map.registeredResource = register.registeredResource;
+        let status = unsafe { nvenc!(self.api, nvEncMapInputResource(self.encoder, &mut map)) };
+        if let Err(err) = self.check(status) {
+            unsafe { nvenc!(self.api, nvEncUnregisterResource(self.encoder, register.registeredResource)) };
+            return Err(err);
+        }
+
+        let pts = frame.pts;
+        let entry = InFlight {
+            frame,
+            registered: register.registeredResource,
+            mapped: map.mappedResource,
+            bitstream: std::ptr::null_mut(),
+        };
+        let bitstream = match self.bitstream() {
+            Ok(bitstream) => bitstream,
+            Err(err) => {
+                self.release(entry);
+                return Err(err);
+            }
+        };
+        let entry = InFlight { bitstream, ..entry };
+
+        let mut params: ffi::NV_ENC_PIC_PARAMS = unsafe { std::mem::zeroed() };
+        params.version = ffi::NV_ENC_PIC_PARAMS_VER;
+        params.inputWidth = entry.frame.width as _;
+        params.inputHeight = entry.frame.height as _;
+        params.inputPitch = entry.frame.mem.pitch as _;
+        params.inputBuffer = entry.mapped;
+        params.outputBitstream = bitstream;
+        params.bufferFmt = map.mappedBufferFmt;
+        params.pictureStruct = ffi::_NV_ENC_PIC_STRUCT_NV_ENC_PIC_STRUCT_FRAME;
+        params.inputTimeStamp = pts as _;
+        params.frameIdx = self.frame_idx;
+        self.frame_idx = self.frame_idx.wrapping_add(1);
+
+        let status = unsafe { nvenc!(self.api, nvEncEncodePicture(self.encoder, &mut params)) };
+        if status != ffi::_NVENCSTATUS_NV_ENC_SUCCESS &&
+            status != ffi::_NVENCSTATUS_NV_ENC_ERR_NEED_MORE_INPUT {
+            let err = self.error(status);
+            self.release(entry);
+            return Err(err);
+        }
+
+        self.dts.push(pts);
+        self.in_flight.push_back(entry);
+
+        if status == ffi::_NVENCSTATUS_NV_ENC_ERR_NEED_MORE_INPUT {
+            return Ok(vec![]);
+        }
+
+        self.drain()
+    }
+
+    /// Sends end of stream and returns the packets still held back.
+    pub fn finish(mut self) -> NVCodecResult<Vec<EncodedPacket>> {
+        let _guard = self.ctx.clone().guard()?;
+
+        let mut params: ffi::NV_ENC_PIC_PARAMS = unsafe { std::mem::zeroed() };
+        params.version = ffi::NV_ENC_PIC_PARAMS_VER;
+        params.encodePicFlags = ffi::_NV_ENC_PIC_FLAGS_NV_ENC_PIC_FLAG_EOS as _;
+        let status = unsafe { nvenc!(self.api, nvEncEncodePicture(self.encoder, &mut params)) };
+        self.check(status)?;
+
+        self.drain()
+    }
+
+    /// The parameter sets (VPS, SPS and PPS) or the AV1 sequence header, as
+    /// repeated in-band before each IDR frame.
+    pub fn sequence_header(&self) -> NVCodecResult<Vec<u8>> {
+        // Far more than any header needs; parameter sets are tens of bytes.
+        const MAX_SIZE: usize = 64 * 1024;
+
+        let _guard = self.ctx.clone().guard()?;
+
+        let mut data = vec![0u8; 1024];
+        loop {
+            let mut size = 0u32;
+            let mut payload: ffi::NV_ENC_SEQUENCE_PARAM_PAYLOAD = unsafe { std::mem::zeroed() };
+            payload.version = ffi::NV_ENC_SEQUENCE_PARAM_PAYLOAD_VER;
+            payload.inBufferSize = data.len() as _;
+            payload.spsppsBuffer = data.as_mut_ptr() as _;
+            payload.outSPSPPSPayloadSize = &mut size;
+            let status = unsafe { nvenc!(self.api, nvEncGetSequenceParams(self.encoder, &mut payload)) };
+
+            let too_small = status == ffi::_NVENCSTATUS_NV_ENC_ERR_NOT_ENOUGH_BUFFER ||
+                (status == ffi::_NVENCSTATUS_NV_ENC_SUCCESS && size as usize > data.len());
+            if !too_small {
+                self.check(status)?;
+                data.truncate(size as _);
+                return Ok(data);
+            }
+
+            let needed = (size as usize).max(data.len() * 2);
+            if needed > MAX_SIZE {
+                let message = format!(
+                    "Sequence header does not fit in {} bytes, {} reported",
+                    data.len(),
+                    size,
+                );
+                return Err(NVCodecError::EncodeError { status: status as _, message });
+            }
+            data.resize(needed, 0);
+        }
+    }
+
+    fn bitstream(&mut self) -> NVCodecResult<ffi::NV_ENC_OUTPUT_PTR> {
+        if let Some(bitstream) = self.free_bitstreams.pop() {
+            return Ok(bitstream);
+        }
+
+        let mut params: ffi::NV_ENC_CREATE_BITSTREAM_BUFFER = unsafe { std::mem::zeroed() };
+        params.version = ffi::NV_ENC_CREATE_BITSTREAM_BUFFER_VER;
+        let status = unsafe { nvenc!(self.api, nvEncCreateBitstreamBuffer(self.encoder, &mut params)) };
+        self.check(status)?;
+
+        self.bitstreams.push(params.bitstreamBuffer);
+        Ok(params.bitstreamBuffer)
+    }
+
+    /// Reads every queued picture's packet, in coded order. Only valid once
+    /// `nvEncEncodePicture` has succeeded, which means all of them are done.
+    fn drain(&mut self) -> NVCodecResult<Vec<EncodedPacket>> {
+        let mut packets = Vec::with_capacity(self.in_flight.len());
+        while let Some(entry) = self.in_flight.pop_front() {
+            let packet = self.read(entry.bitstream);
+            self.release(entry);
+            packets.push(packet?);
+        }
+
+        Ok(packets)
+    }
+
+    fn read(&mut self, bitstream: ffi::NV_ENC_OUTPUT_PTR) -> NVCodecResult<EncodedPacket> {
+        let dts = self.dts.pop();
+
+        let mut lock: ffi::NV_ENC_LOCK_BITSTREAM = unsafe { std::mem::zeroed() };
+        lock.version = ffi::NV_ENC_LOCK_BITSTREAM_VER;
+        lock.outputBitstream = bitstream;
+        let status = unsafe { nvenc!(self.api, nvEncLockBitstream(self.encoder, &mut lock)) };
+        self.check(status)?;
+
+        let data = unsafe {
+            std::slice::from_raw_parts(
+                lock.bitstreamBufferPtr as *const u8,
+                lock.bitstreamSizeInBytes as _,
+            )
+        }.to_vec();
+        let picture_type = PictureType::from_raw(lock.pictureType as _);
+
+        let status = unsafe { nvenc!(self.api, nvEncUnlockBitstream(self.encoder, bitstream)) };
+        self.check(status)?;
+
+        Ok(EncodedPacket {
+            data,
+            pts: lock.outputTimeStamp as _,
+            dts,
+            key: picture_type == PictureType::IDR,
+            picture_type,
+        })
+    }
+
+    /// Gives back the input mapping and the output buffer. The frame itself
+    /// is dropped last.
+    fn release(&mut self, entry: InFlight) {
+        unsafe {
+            nvenc!(self.api, nvEncUnmapInputResource(self.encoder, entry.mapped));
+            nvenc!(self.api, nvEncUnregisterResource(self.encoder, entry.registered));
+        }
+        if !entry.bitstream.is_null() {
+            self.free_bitstreams.push(entry.bitstream);
+        }
+    }
+
+    fn check(&self, status: ffi::NVENCSTATUS) -> NVCodecResult<()> {
+        if status == ffi::_NVENCSTATUS_NV_ENC_SUCCESS {
+            Ok(())
+        } else {
+            Err(self.error(status))
+        }
+    }
+
+    fn error(&self, status: ffi::NVENCSTATUS) -> NVCodecError {
+        let message = match self.api.nvEncGetLastErrorString {
+            Some(last_error) => unsafe {
+                let message = last_error(self.encoder);
+                if message.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr(message).to_string_lossy().into_owned()
+                }
+            },
+            None => String::new(),
+        };
+
+        NVCodecError::EncodeError { status: status as _, message }
+    }
+}
+
+impl Drop for NVEncoder {
+    fn drop(&mut self) {
+        if let Ok(_guard) = self.ctx.clone().guard() {
+            while let Some(entry) = self.in_flight.pop_front() {
+                self.release(entry);
+            }
+
+            unsafe {
+                for bitstream in std::mem::take(&mut self.bitstreams) {
+                    nvenc!(self.api, nvEncDestroyBitstreamBuffer(self.encoder, bitstream));
+                }
+
+                nvenc!(self.api, nvEncDestroyEncoder(self.encoder));
+            }
+        }
+    }
+}
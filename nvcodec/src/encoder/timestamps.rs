@@ -0,0 +1,103 @@
+use std::collections::VecDeque;
+
+/// Decode timestamps for packets that come out in coded order.
+///
+/// With `delay` B-frames the n-th packet gets the pts of the (n - delay)-th
+/// input frame, so dts never passes pts and keeps increasing. The first
+/// `delay` packets have no such frame and step back from the first pts by the
+/// interval between the first two.
+pub(crate) struct DtsQueue {
+    delay: u64,
+    emitted: u64,
+    first: Option<i64>,
+    step: Option<i64>,
+    pending: VecDeque<i64>,
+}
+
+impl DtsQueue {
+    pub(crate) fn new(delay: u32) -> Self {
+        Self {
+            delay: delay as _,
+            emitted: 0,
+            first: None,
+            step: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Records the pts of a frame sent to the encoder.
+    pub(crate) fn push(&mut self, pts: i64) {
+        match self.first {
+            None => self.first = Some(pts),
+            Some(first) if self.step.is_none() => self.step = Some((pts - first).max(1)),
+            _ => {}
+        }
+        self.pending.push_back(pts);
+    }
+
+    /// The dts of the next packet out of the encoder.
+    pub(crate) fn pop(&mut self) -> i64 {
+        let dts = if self.emitted < self.delay {
+            let first = self.first.unwrap_or_default();
+            first - (self.delay - self.emitted) as i64 * self.step.unwrap_or(1)
+        } else {
+            self.pending.pop_front().unwrap_or_default()
+        };
+        self.emitted += 1;
+        dts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dts(delay: u32, pts: &[i64]) -> Vec<i64> {
+        let mut queue = DtsQueue::new(delay);
+        pts.iter().for_each(|&pts| queue.push(pts));
+        pts.iter().map(|_| queue.pop()).collect()
+    }
+
+    #[test]
+    fn without_reordering() {
+        assert_eq!(dts(0, &[0, 1, 2, 3]), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn reordered() {
+        // With 2 B-frames the first two packets step back from the first pts.
+        let pts = [0, 3000, 6000, 9000];
+        assert_eq!(dts(2, &pts), [-6000, -3000, 0, 3000]);
+    }
+
+    #[test]
+    fn interleaved() {
+        // The encoder holds frames back, so packets are popped as frames
+        // keep coming.
+        let mut queue = DtsQueue::new(1);
+        queue.push(100);
+        queue.push(110);
+        assert_eq!(queue.pop(), 90);
+        assert_eq!(queue.pop(), 100);
+        queue.push(120);
+        queue.push(130);
+        assert_eq!(queue.pop(), 110);
+        assert_eq!(queue.pop(), 120);
+    }
+
+    #[test]
+    fn single_frame() {
+        // No second pts to take the step from.
+        assert_eq!(dts(2, &[50]), [48]);
+    }
+
+    #[test]
+    fn increasing_and_not_past_pts() {
+        // Frames go in in display order and come out in coded order, here
+        // I P B B B with 3 B-frames.
+        let dts = dts(3, &[0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        let coded_pts = [0, 4, 1, 2, 3, 8, 5, 6, 7];
+        assert_eq!(dts, [-3, -2, -1, 0, 1, 2, 3, 4, 5]);
+        assert!(dts.iter().zip(coded_pts).all(|(&dts, pts)| dts <= pts));
+    }
+}
@@ -1,4 +1,4 @@
-use cuda_rs::error::CuError;
+use crate::{cuda::CuError, encoder::EncoderConfigError, ffi, geometry::GeometryError, muxer::annexb::BitstreamError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -7,8 +7,22 @@ pub enum NVCodecError {
     IOError(#[from] std::io::Error),
     #[error("FFmpeg Error: {0}")]
     FFmpegError(#[from] ffmpeg_next::Error),
+    #[error("Library Error: {0}")]
+    LibraryError(#[from] ffi::LoadError),
     #[error("CuError: {0}")]
     CuError(#[from] CuError),
+    /// From the cuda-rs calls of the NPP conversions.
+    #[cfg(feature = "npp")]
+    #[error("cuda-rs Error: {0}")]
+    CudaRsError(#[from] cuda_rs::error::CuError),
+    #[error("Geometry Error: {0}")]
+    GeometryError(#[from] GeometryError),
+    #[error("Encoder Config Error: {0}")]
+    EncoderConfigError(#[from] EncoderConfigError),
+    #[error("Bitstream Error: {0}")]
+    BitstreamError(#[from] BitstreamError),
+    #[error("Encode error {status}: {message}")]
+    EncodeError { status: u32, message: String },
     #[error("NotSupported Error: {0}")]
     NotSupported(String),
     #[error("Decoder not initialized")]
@@ -19,8 +33,12 @@ pub enum NVCodecError {
     ParserError,
     #[error("Surface shape mismatch")]
     SurfaceShapeMismatch,
+    #[error("{0} decoded pictures were lost when the decoder was re-created")]
+    PicturesLost(usize),
     #[error("Reconfigure failed")]
     ReconfigureFailed,
+    #[error("Decoder closed")]
+    DecoderClosed,
 }
 
 pub type NVCodecResult<T> = Result<T, NVCodecError>;
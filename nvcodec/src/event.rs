@@ -0,0 +1,138 @@
+use crate::{cuda::{CuContext, CuStream}, error::NVCodecResult, ffi};
+use futures::task::AtomicWaker;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+struct FenceState {
+    done: AtomicBool,
+    waker: AtomicWaker,
+}
+
+/// A point in a `CuStream`'s work queue.
+///
+/// Backed by a CUDA event, so other streams can wait on it without the host,
+/// plus a host function queued right after it that wakes async waiters.
+pub struct Fence {
+    ctx: CuContext,
+    event: ffi::CUevent,
+    state: Arc<FenceState>,
+}
+
+unsafe impl Send for Fence {}
+unsafe impl Sync for Fence {}
+
+impl Fence {
+    /// Records a fence after all work currently queued on `stream`.
+    pub fn record(stream: &CuStream) -> NVCodecResult<Self> {
+        let ctx = stream.get_context()?;
+        let _guard = ctx.clone().guard()?;
+
+        let mut event = std::ptr::null_mut();
+        let res = unsafe {
+            ffi::cuEventCreate(&mut event, ffi::CUevent_flags_enum_CU_EVENT_DISABLE_TIMING as _)
+        };
+        wrap!((), res)?;
+
+        let fence = Self {
+            ctx,
+            event,
+            state: Arc::new(FenceState {
+                done: AtomicBool::new(false),
+                waker: AtomicWaker::new(),
+            }),
+        };
+
+        let raw_stream = unsafe { stream.get_raw() as ffi::CUstream };
+
+        let res = unsafe { ffi::cuEventRecord(fence.event, raw_stream) };
+        wrap!((), res)?;
+
+        let user_data = Arc::into_raw(fence.state.clone()) as *mut std::os::raw::c_void;
+        let res = unsafe {
+            ffi::cuLaunchHostFunc(raw_stream, Some(handle_fence_proc), user_data)
+        };
+        if let Err(err) = wrap!((), res) {
+            drop(unsafe { Arc::from_raw(user_data as *const FenceState) });
+            return Err(err.into());
+        }
+
+        Ok(fence)
+    }
+
+    /// Whether the stream has passed the fence.
+    pub fn is_ready(&self) -> bool {
+        self.state.done.load(Ordering::Acquire)
+    }
+
+    /// Blocks the calling thread until the stream has passed the fence.
+    pub fn wait(&self) -> NVCodecResult<()> {
+        let res = unsafe { ffi::cuEventSynchronize(self.event) };
+        wrap!((), res)?;
+
+        Ok(())
+    }
+
+    /// Makes all work submitted to `stream` from now on wait for the fence,
+    /// without blocking the host.
+    pub fn wait_on(&self, stream: &CuStream) -> NVCodecResult<()> {
+        let _guard = self.ctx.clone().guard()?;
+
+        let res = unsafe {
+            ffi::cuStreamWaitEvent(stream.get_raw() as _, self.event, 0)
+        };
+        wrap!((), res)?;
+
+        Ok(())
+    }
+
+    /// Resolves once the stream has passed the fence.
+    pub fn ready(&self) -> FenceReady<'_> {
+        FenceReady { fence: self }
+    }
+
+    pub fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.state.waker.register(cx.waker());
+
+        if self.state.done.load(Ordering::Acquire) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Fence {
+    fn drop(&mut self) {
+        if let Ok(_guard) = self.ctx.clone().guard() {
+            unsafe {
+                ffi::cuEventDestroy_v2(self.event);
+            }
+        }
+    }
+}
+
+pub struct FenceReady<'a> {
+    fence: &'a Fence,
+}
+
+impl Future for FenceReady<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.fence.poll_ready(cx)
+    }
+}
+
+unsafe extern "C" fn handle_fence_proc(user_data: *mut std::os::raw::c_void) {
+    // Runs on a CUDA driver thread, which must not call back into CUDA.
+    let state = Arc::from_raw(user_data as *const FenceState);
+    state.done.store(true, Ordering::Release);
+    state.waker.wake();
+}
@@ -0,0 +1,366 @@
+use crate::surface::ChromaFormat;
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DisplayArea {
+    pub top: i32,
+    pub left: i32,
+    pub bottom: i32,
+    pub right: i32,
+}
+
+impl DisplayArea {
+    pub fn width(&self) -> i32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> i32 {
+        self.bottom - self.top
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width() <= 0 || self.height() <= 0
+    }
+
+    fn clamp_to(self, width: u32, height: u32) -> Self {
+        Self {
+            top: self.top,
+            left: self.left,
+            bottom: self.bottom.min(height as i32),
+            right: self.right.min(width as i32),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Size {
+    pub width: i32,
+    pub height: i32,
+}
+
+#[derive(Error, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GeometryError {
+    #[error("Display area {0:?} has negative coordinates")]
+    NegativeDisplayArea(DisplayArea),
+    #[error("Display area {0:?} is empty")]
+    EmptyDisplayArea(DisplayArea),
+    #[error("Display area {area:?} exceeds coded size {coded_width}x{coded_height}")]
+    DisplayAreaOutOfBounds {
+        area: DisplayArea,
+        coded_width: u32,
+        coded_height: u32,
+    },
+    #[error("Invalid target size {0:?}")]
+    InvalidTargetSize(Size),
+    #[error("{name} {value} is not a multiple of {alignment} as required by {chroma_format:?}")]
+    Misaligned {
+        name: &'static str,
+        value: i32,
+        alignment: u32,
+        chroma_format: ChromaFormat,
+    },
+}
+
+/// The crop and scale a caller asked for, independent of any stream.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct OutputRequest {
+    pub display_area: Option<DisplayArea>,
+    pub target_size: Option<Size>,
+}
+
+impl OutputRequest {
+    /// Validates everything that can be checked before the first sequence header.
+    /// An all-zero `DisplayArea` or `Size` means "not set".
+    pub fn new(
+        display_area: Option<DisplayArea>,
+        target_size: Option<Size>,
+    ) -> Result<Self, GeometryError> {
+        let display_area = display_area.filter(|area| *area != DisplayArea::default());
+        let target_size = target_size.filter(|size| *size != Size::default());
+
+        if let Some(area) = display_area {
+            if area.top < 0 || area.left < 0 || area.bottom < 0 || area.right < 0 {
+                return Err(GeometryError::NegativeDisplayArea(area));
+            }
+            if area.is_empty() {
+                return Err(GeometryError::EmptyDisplayArea(area));
+            }
+        }
+
+        if let Some(size) = target_size {
+            if size.width <= 0 || size.height <= 0 {
+                return Err(GeometryError::InvalidTargetSize(size));
+            }
+        }
+
+        Ok(Self { display_area, target_size })
+    }
+}
+
+/// The parts of a sequence header that determine the output geometry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StreamGeometry {
+    pub coded_width: u32,
+    pub coded_height: u32,
+    pub display_area: DisplayArea,
+    pub chroma_format: ChromaFormat,
+}
+
+impl StreamGeometry {
+    /// The visible area of the stream, snapped outwards to the chroma grid.
+    ///
+    /// Falls back to the full coded size when the bitstream carries no usable
+    /// display area. Coded sizes are macroblock aligned, so snapping never
+    /// leaves the coded frame.
+    pub fn visible_area(&self) -> DisplayArea {
+        let area = self.display_area;
+        let in_bounds = area.top >= 0 && area.left >= 0 &&
+            area.right <= self.coded_width as i32 && area.bottom <= self.coded_height as i32;
+        if area.is_empty() || !in_bounds {
+            return DisplayArea {
+                top: 0,
+                left: 0,
+                bottom: self.coded_height as _,
+                right: self.coded_width as _,
+            };
+        }
+
+        let (sx, sy) = self.chroma_format.subsampling();
+        let (sx, sy) = (sx as i32, sy as i32);
+        DisplayArea {
+            top: area.top - area.top % sy,
+            left: area.left - area.left % sx,
+            bottom: (area.bottom + sy - 1) / sy * sy,
+            right: (area.right + sx - 1) / sx * sx,
+        }.clamp_to(self.coded_width, self.coded_height)
+    }
+}
+
+/// Where the decoder crops and what size it scales the crop to.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct OutputGeometry {
+    /// Crop rectangle handed to the decoder, in coded samples.
+    pub display_area: DisplayArea,
+    /// Output surface width, in samples.
+    pub width: u32,
+    /// Output luma plane height, in rows.
+    pub luma_height: u32,
+}
+
+impl OutputGeometry {
+    pub fn plan(stream: &StreamGeometry, request: &OutputRequest) -> Result<Self, GeometryError> {
+        let chroma_format = stream.chroma_format;
+        let (sx, sy) = chroma_format.subsampling();
+
+        let display_area = match request.display_area {
+            Some(area) => {
+                if area.right > stream.coded_width as i32 || area.bottom > stream.coded_height as i32 {
+                    return Err(GeometryError::DisplayAreaOutOfBounds {
+                        area,
+                        coded_width: stream.coded_width,
+                        coded_height: stream.coded_height,
+                    });
+                }
+                check_alignment("display area left", area.left, sx, chroma_format)?;
+                check_alignment("display area right", area.right, sx, chroma_format)?;
+                check_alignment("display area top", area.top, sy, chroma_format)?;
+                check_alignment("display area bottom", area.bottom, sy, chroma_format)?;
+                area
+            }
+            None => stream.visible_area(),
+        };
+
+        let (width, luma_height) = match request.target_size {
+            Some(size) => {
+                check_alignment("target width", size.width, sx, chroma_format)?;
+                check_alignment("target height", size.height, sy, chroma_format)?;
+                (size.width, size.height)
+            }
+            None => (display_area.width(), display_area.height()),
+        };
+
+        Ok(Self {
+            display_area,
+            width: width as _,
+            luma_height: luma_height as _,
+        })
+    }
+}
+
+fn check_alignment(
+    name: &'static str,
+    value: i32,
+    alignment: u32,
+    chroma_format: ChromaFormat,
+) -> Result<(), GeometryError> {
+    if value % alignment as i32 != 0 {
+        return Err(GeometryError::Misaligned { name, value, alignment, chroma_format });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(left: i32, top: i32, right: i32, bottom: i32) -> DisplayArea {
+        DisplayArea { top, left, bottom, right }
+    }
+
+    fn stream(coded_width: u32, coded_height: u32, display_area: DisplayArea, chroma_format: ChromaFormat) -> StreamGeometry {
+        StreamGeometry { coded_width, coded_height, display_area, chroma_format }
+    }
+
+    fn crop(display_area: DisplayArea) -> OutputRequest {
+        OutputRequest::new(Some(display_area), None).unwrap()
+    }
+
+    fn scale(width: i32, height: i32) -> OutputRequest {
+        OutputRequest::new(None, Some(Size { width, height })).unwrap()
+    }
+
+    #[test]
+    fn zero_values_mean_unset() {
+        let request = OutputRequest::new(Some(DisplayArea::default()), Some(Size::default())).unwrap();
+        assert_eq!(request, OutputRequest::default());
+    }
+
+    #[test]
+    fn rejects_negative_crop() {
+        let negative = area(-2, 0, 100, 100);
+        assert_eq!(
+            OutputRequest::new(Some(negative), None),
+            Err(GeometryError::NegativeDisplayArea(negative)),
+        );
+    }
+
+    #[test]
+    fn rejects_empty_crop() {
+        for empty in [area(100, 0, 100, 100), area(0, 50, 100, 40)] {
+            assert_eq!(
+                OutputRequest::new(Some(empty), None),
+                Err(GeometryError::EmptyDisplayArea(empty)),
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_target_size() {
+        let size = Size { width: 0, height: 720 };
+        assert_eq!(OutputRequest::new(None, Some(size)), Err(GeometryError::InvalidTargetSize(size)));
+    }
+
+    #[test]
+    fn rejects_crop_out_of_bounds() {
+        let stream = stream(1920, 1088, area(0, 0, 1920, 1080), ChromaFormat::YUV420);
+        let outside = area(0, 0, 1922, 1080);
+        assert_eq!(
+            OutputGeometry::plan(&stream, &crop(outside)),
+            Err(GeometryError::DisplayAreaOutOfBounds {
+                area: outside,
+                coded_width: 1920,
+                coded_height: 1088,
+            }),
+        );
+    }
+
+    #[test]
+    fn odd_crop_misaligned_for_420() {
+        let stream = stream(1920, 1088, area(0, 0, 1920, 1080), ChromaFormat::YUV420);
+        assert_eq!(
+            OutputGeometry::plan(&stream, &crop(area(0, 1, 640, 480))),
+            Err(GeometryError::Misaligned {
+                name: "display area top",
+                value: 1,
+                alignment: 2,
+                chroma_format: ChromaFormat::YUV420,
+            }),
+        );
+        assert_eq!(
+            OutputGeometry::plan(&stream, &scale(641, 480)),
+            Err(GeometryError::Misaligned {
+                name: "target width",
+                value: 641,
+                alignment: 2,
+                chroma_format: ChromaFormat::YUV420,
+            }),
+        );
+    }
+
+    #[test]
+    fn odd_rows_allowed_for_422() {
+        let stream = stream(1920, 1088, area(0, 0, 1920, 1080), ChromaFormat::YUV422);
+        let plan = OutputGeometry::plan(&stream, &crop(area(0, 1, 640, 481))).unwrap();
+        assert_eq!(plan.display_area, area(0, 1, 640, 481));
+        assert_eq!((plan.width, plan.luma_height), (640, 480));
+
+        assert_eq!(
+            OutputGeometry::plan(&stream, &crop(area(1, 0, 640, 480))),
+            Err(GeometryError::Misaligned {
+                name: "display area left",
+                value: 1,
+                alignment: 2,
+                chroma_format: ChromaFormat::YUV422,
+            }),
+        );
+        assert_eq!(
+            OutputGeometry::plan(&stream, &scale(640, 361)).map(|plan| plan.luma_height),
+            Ok(361),
+        );
+    }
+
+    #[test]
+    fn visible_area_of_1080p() {
+        let stream = stream(1920, 1088, area(0, 0, 1920, 1080), ChromaFormat::YUV420);
+        assert_eq!(stream.visible_area(), area(0, 0, 1920, 1080));
+
+        let plan = OutputGeometry::plan(&stream, &OutputRequest::default()).unwrap();
+        assert_eq!(plan.display_area, area(0, 0, 1920, 1080));
+        assert_eq!((plan.width, plan.luma_height), (1920, 1080));
+    }
+
+    #[test]
+    fn visible_area_snaps_outwards() {
+        let stream = stream(1920, 1088, area(1, 1, 1919, 1079), ChromaFormat::YUV420);
+        assert_eq!(stream.visible_area(), area(0, 0, 1920, 1080));
+    }
+
+    #[test]
+    fn visible_area_falls_back_to_coded_size() {
+        let empty = stream(1920, 1088, DisplayArea::default(), ChromaFormat::YUV420);
+        assert_eq!(empty.visible_area(), area(0, 0, 1920, 1088));
+
+        let outside = stream(1280, 720, area(0, 0, 1920, 1080), ChromaFormat::YUV420);
+        assert_eq!(outside.visible_area(), area(0, 0, 1280, 720));
+    }
+
+    #[test]
+    fn scales_visible_area_to_target_size() {
+        let stream = stream(1920, 1088, area(0, 0, 1920, 1080), ChromaFormat::YUV420);
+        let plan = OutputGeometry::plan(&stream, &scale(1280, 720)).unwrap();
+        assert_eq!(plan.display_area, area(0, 0, 1920, 1080));
+        assert_eq!((plan.width, plan.luma_height), (1280, 720));
+    }
+
+    #[test]
+    fn replans_on_reconfigure() {
+        let request = OutputRequest::new(Some(area(0, 0, 640, 360)), Some(Size { width: 320, height: 180 })).unwrap();
+        let before = stream(1920, 1088, area(0, 0, 1920, 1080), ChromaFormat::YUV420);
+        let plan = OutputGeometry::plan(&before, &request).unwrap();
+        assert_eq!((plan.width, plan.luma_height), (320, 180));
+
+        // A smaller sequence still fits the crop, a tiny one does not.
+        let smaller = stream(1280, 720, area(0, 0, 1280, 720), ChromaFormat::YUV420);
+        assert_eq!(OutputGeometry::plan(&smaller, &request), Ok(plan));
+
+        let tiny = stream(320, 240, area(0, 0, 320, 240), ChromaFormat::YUV420);
+        assert!(matches!(
+            OutputGeometry::plan(&tiny, &request),
+            Err(GeometryError::DisplayAreaOutOfBounds { .. }),
+        ));
+
+        // Without a request the output follows the new visible area.
+        let native = OutputGeometry::plan(&smaller, &OutputRequest::default()).unwrap();
+        assert_eq!((native.width, native.luma_height), (1280, 720));
+    }
+}
@@ -0,0 +1,367 @@
+use crate::{
+    color::ColorDescription,
+    cuda::CuContext,
+    decoder::DecodedFrame,
+    error::NVCodecResult,
+    event::Fence,
+    ffi,
+    surface::{PlaneLayout, VideoSurfaceFormat},
+};
+use std::{
+    future::Future,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Page-locked host memory, which device copies can fill without blocking the host.
+pub struct PinnedBuffer {
+    ctx: CuContext,
+    ptr: *mut u8,
+    len: usize,
+}
+
+unsafe impl Send for PinnedBuffer {}
+unsafe impl Sync for PinnedBuffer {}
+
+impl PinnedBuffer {
+    pub fn new(ctx: &CuContext, len: usize) -> NVCodecResult<Self> {
+        let _guard = ctx.clone().guard()?;
+
+        let mut ptr = std::ptr::null_mut();
+        let res = unsafe { ffi::cuMemAllocHost_v2(&mut ptr, len) };
+        wrap!((), res)?;
+
+        Ok(Self { ctx: ctx.clone(), ptr: ptr as *mut u8, len })
+    }
+
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.ptr
+    }
+}
+
+impl Deref for PinnedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl DerefMut for PinnedBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl Drop for PinnedBuffer {
+    fn drop(&mut self) {
+        if let Ok(_guard) = self.ctx.clone().guard() {
+            unsafe {
+                ffi::cuMemFreeHost(self.ptr as _);
+            }
+        }
+    }
+}
+
+pub enum HostBuffer {
+    Pinned(PinnedBuffer),
+    Owned(Vec<u8>),
+}
+
+impl Deref for HostBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            HostBuffer::Pinned(buf) => &buf[..],
+            HostBuffer::Owned(buf) => &buf[..],
+        }
+    }
+}
+
+/// How a downloaded frame is laid out in host memory.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HostLayout {
+    /// The device surface byte for byte, rows padded to the device pitch.
+    Pitched,
+    /// The surface layout (e.g. NV12) with row padding removed.
+    Packed,
+    /// One plane per component (e.g. I420 from NV12, I444 from YUV444),
+    /// row padding removed.
+    Planar,
+}
+
+pub struct HostFrame {
+    /// Pinned for `Pitched` and `Packed`, which are copied straight from the device.
+    pub data: HostBuffer,
+    pub layout: HostLayout,
+    pub surface_format: VideoSurfaceFormat,
+    pub color: ColorDescription,
+    pub width: usize,
+    pub height: usize,
+    /// Bytes between the starts of consecutive luma rows.
+    pub pitch: usize,
+}
+
+impl HostFrame {
+    pub fn planes(&self) -> Vec<HostPlane<'_>> {
+        plane_layouts(self.layout, self.surface_format, self.width, self.height, self.pitch)
+            .into_iter()
+            .map(|layout| HostPlane {
+                data: &self.data[layout.offset..layout.offset + layout.size()],
+                layout,
+            })
+            .collect()
+    }
+}
+
+pub struct HostPlane<'a> {
+    /// Starts at the plane's first sample.
+    pub data: &'a [u8],
+    pub layout: PlaneLayout,
+}
+
+/// Planes of a host frame in `layout`, in memory order.
+pub fn plane_layouts(
+    layout: HostLayout,
+    surface_format: VideoSurfaceFormat,
+    width: usize,
+    height: usize,
+    pitch: usize,
+) -> Vec<PlaneLayout> {
+    let mut planes = surface_format.planes(width, height, pitch);
+
+    if layout == HostLayout::Planar && planes.len() == 2 {
+        let uv = planes.pop().unwrap();
+        let chroma_pitch = uv.width * uv.bytes_per_sample;
+        let u = PlaneLayout { pitch: chroma_pitch, components: 1, ..uv };
+        let v = PlaneLayout { offset: u.offset + chroma_pitch * u.height, ..u };
+        planes.push(u);
+        planes.push(v);
+    }
+
+    planes
+}
+
+/// A device-to-host copy in flight on the decoder's stream.
+///
+/// Await it, or call `wait` from synchronous code.
+pub struct Download {
+    fence: Fence,
+    staging: Option<PinnedBuffer>,
+    layout: HostLayout,
+    surface_format: VideoSurfaceFormat,
+    color: ColorDescription,
+    width: usize,
+    height: usize,
+    pitch: usize,
+}
+
+impl Download {
+    pub(crate) fn start(frame: &DecodedFrame, layout: HostLayout) -> NVCodecResult<Self> {
+        let ctx = frame.stream.get_context()?;
+        let _guard = ctx.clone().guard()?;
+
+        let surface_format = frame.surface_format;
+        let row_bytes = frame.width * surface_format.bytes_per_sample() as usize;
+        let (pitch, rows) = staging_layout(layout, surface_format, frame.width, frame.height, frame.buf.pitch);
+
+        let staging = PinnedBuffer::new(&ctx, pitch * rows)?;
+
+        let mut params: ffi::CUDA_MEMCPY2D = unsafe { std::mem::zeroed() };
+        params.srcMemoryType = ffi::CUmemorytype_enum_CU_MEMORYTYPE_DEVICE;
+        params.srcDevice = frame.buf.ptr as _;
+        params.srcPitch = frame.buf.pitch as _;
+        params.dstMemoryType = ffi::CUmemorytype_enum_CU_MEMORYTYPE_HOST;
+        params.dstHost = staging.as_ptr() as _;
+        params.dstPitch = pitch as _;
+        params.WidthInBytes = row_bytes as _;
+        params.Height = rows as _;
+
+        let res = unsafe {
+            ffi::cuMemcpy2DAsync_v2(&params, frame.stream.get_raw() as _)
+        };
+        wrap!((), res)?;
+
+        Ok(Self {
+            fence: Fence::record(&frame.stream)?,
+            staging: Some(staging),
+            layout,
+            surface_format,
+            color: frame.color,
+            width: frame.width,
+            height: frame.height,
+            pitch,
+        })
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.fence.is_ready()
+    }
+
+    /// Blocks until the copy has completed.
+    pub fn wait(mut self) -> NVCodecResult<HostFrame> {
+        self.fence.wait()?;
+
+        Ok(self.finish())
+    }
+
+    fn finish(&mut self) -> HostFrame {
+        let staging = self.staging.take().expect("download already finished");
+
+        let data = match self.layout {
+            HostLayout::Planar if self.surface_format.chroma_plane_count() == 1 => {
+                HostBuffer::Owned(split_chroma(&staging, self.surface_format, self.width, self.height))
+            }
+            _ => HostBuffer::Pinned(staging),
+        };
+
+        HostFrame {
+            data,
+            layout: self.layout,
+            surface_format: self.surface_format,
+            color: self.color,
+            width: self.width,
+            height: self.height,
+            pitch: self.pitch,
+        }
+    }
+}
+
+impl Future for Download {
+    type Output = NVCodecResult<HostFrame>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        match this.fence.poll_ready(cx) {
+            Poll::Ready(()) => Poll::Ready(Ok(this.finish())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Row pitch and row count of the pinned buffer a frame of `width` x
+/// `height` luma samples is copied into, from a surface whose rows are
+/// `device_pitch` bytes apart.
+fn staging_layout(
+    layout: HostLayout,
+    surface_format: VideoSurfaceFormat,
+    width: usize,
+    height: usize,
+    device_pitch: usize,
+) -> (usize, usize) {
+    let rows = surface_format.buffer_height(height as _) as usize;
+    let pitch = match layout {
+        HostLayout::Pitched => device_pitch,
+        HostLayout::Packed | HostLayout::Planar => width * surface_format.bytes_per_sample() as usize,
+    };
+
+    (pitch, rows)
+}
+
+/// Splits the interleaved UV plane of a packed semi-planar surface into
+/// separate U and V planes.
+fn split_chroma(
+    packed: &[u8],
+    surface_format: VideoSurfaceFormat,
+    width: usize,
+    height: usize,
+) -> Vec<u8> {
+    let bytes_per_sample = surface_format.bytes_per_sample() as usize;
+    let row_bytes = width * bytes_per_sample;
+    let luma_bytes = row_bytes * height;
+    let chroma_rows = surface_format.chroma_height(height as _) as usize;
+    let plane_bytes = row_bytes / 2 * chroma_rows;
+
+    let mut planar = vec![0u8; luma_bytes + plane_bytes * 2];
+    planar[..luma_bytes].copy_from_slice(&packed[..luma_bytes]);
+
+    let (u_plane, v_plane) = planar[luma_bytes..].split_at_mut(plane_bytes);
+    let samples = packed[luma_bytes..luma_bytes + row_bytes * chroma_rows]
+        .chunks_exact(bytes_per_sample * 2);
+    let planes = u_plane
+        .chunks_exact_mut(bytes_per_sample)
+        .zip(v_plane.chunks_exact_mut(bytes_per_sample));
+    for (pair, (u, v)) in samples.zip(planes) {
+        u.copy_from_slice(&pair[..bytes_per_sample]);
+        v.copy_from_slice(&pair[bytes_per_sample..]);
+    }
+
+    planar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bytes from the start of the buffer to the end of its last plane.
+    fn extent(planes: &[PlaneLayout]) -> usize {
+        planes.iter().map(|plane| plane.offset + plane.size()).max().unwrap()
+    }
+
+    #[test]
+    fn pitched_keeps_the_device_pitch() {
+        let (pitch, rows) = staging_layout(HostLayout::Pitched, VideoSurfaceFormat::NV12, 1920, 1080, 2048);
+        assert_eq!((pitch, rows), (2048, 1620));
+
+        let planes = plane_layouts(HostLayout::Pitched, VideoSurfaceFormat::NV12, 1920, 1080, pitch);
+        assert_eq!(planes[1].offset, 2048 * 1080);
+        assert!(extent(&planes) <= pitch * rows);
+    }
+
+    #[test]
+    fn packed_drops_row_padding() {
+        let (pitch, rows) = staging_layout(HostLayout::Packed, VideoSurfaceFormat::P016, 1280, 720, 4096);
+        assert_eq!((pitch, rows), (2560, 1080));
+
+        let planes = plane_layouts(HostLayout::Packed, VideoSurfaceFormat::P016, 1280, 720, pitch);
+        assert_eq!(extent(&planes), pitch * rows);
+    }
+
+    #[test]
+    fn planar_444_is_copied_as_is() {
+        let (pitch, rows) = staging_layout(HostLayout::Planar, VideoSurfaceFormat::YUV444, 640, 480, 1024);
+        assert_eq!((pitch, rows), (640, 1440));
+
+        let planes = plane_layouts(HostLayout::Planar, VideoSurfaceFormat::YUV444, 640, 480, pitch);
+        assert_eq!(planes.len(), 3);
+        assert_eq!(planes[2].offset, 640 * 480 * 2);
+        assert_eq!(extent(&planes), pitch * rows);
+    }
+
+    #[test]
+    fn planar_splits_the_uv_plane() {
+        let (pitch, rows) = staging_layout(HostLayout::Planar, VideoSurfaceFormat::NV12, 4, 2, 256);
+        assert_eq!((pitch, rows), (4, 3));
+
+        let packed = [0, 1, 2, 3, 4, 5, 6, 7, 10, 20, 11, 21];
+        let planar = split_chroma(&packed, VideoSurfaceFormat::NV12, 4, 2);
+        assert_eq!(planar, [0, 1, 2, 3, 4, 5, 6, 7, 10, 11, 20, 21]);
+
+        let planes = plane_layouts(HostLayout::Planar, VideoSurfaceFormat::NV12, 4, 2, pitch);
+        assert_eq!(planes.len(), 3);
+        assert_eq!((planes[1].offset, planes[1].pitch, planes[1].components), (8, 2, 1));
+        assert_eq!((planes[2].offset, planes[2].pitch, planes[2].components), (10, 2, 1));
+        assert_eq!(extent(&planes), planar.len());
+    }
+
+    #[test]
+    fn planar_splits_16_bit_samples_whole() {
+        let packed = [0u8, 0, 0, 0, 1, 2, 3, 4];
+        let planar = split_chroma(&packed, VideoSurfaceFormat::P016, 2, 1);
+        assert_eq!(planar, [0, 0, 0, 0, 1, 2, 3, 4]);
+
+        let packed = [0u8; 8].into_iter().chain([1, 2, 3, 4, 5, 6, 7, 8]).collect::<Vec<_>>();
+        let planar = split_chroma(&packed, VideoSurfaceFormat::P016, 4, 1);
+        assert_eq!(&planar[8..], [1, 2, 5, 6, 3, 4, 7, 8]);
+
+        let planes = plane_layouts(HostLayout::Planar, VideoSurfaceFormat::P016, 4, 1, 8);
+        assert_eq!(extent(&planes), planar.len());
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{cuda::{CuContext, CuStream}, error::NVCodecResult, ffi};
+use std::ffi::CString;
+
+/// `high_bytes(src, src_pitch, dst, dst_pitch, width, height)`: one thread
+/// per sample, storing the high byte of each little-endian 16-bit sample of
+/// `src` into `dst`. Written for `sm_50` and JIT-compiled by the driver for
+/// the device in use.
+const PTX: &str = r#"
+.version 6.0
+.target sm_50
+.address_size 64
+
+.visible .entry high_bytes(
+    .param .u64 src,
+    .param .u64 src_pitch,
+    .param .u64 dst,
+    .param .u64 dst_pitch,
+    .param .u32 width,
+    .param .u32 height
+)
+{
+    .reg .pred %p<3>;
+    .reg .b16 %rs<3>;
+    .reg .b32 %r<9>;
+    .reg .b64 %rd<11>;
+
+    ld.param.u64 %rd1, [src];
+    ld.param.u64 %rd2, [src_pitch];
+    ld.param.u64 %rd3, [dst];
+    ld.param.u64 %rd4, [dst_pitch];
+    ld.param.u32 %r1, [width];
+    ld.param.u32 %r2, [height];
+
+    mov.u32 %r3, %ctaid.x;
+    mov.u32 %r4, %ntid.x;
+    mov.u32 %r5, %tid.x;
+    mad.lo.s32 %r6, %r3, %r4, %r5;
+    mov.u32 %r3, %ctaid.y;
+    mov.u32 %r4, %ntid.y;
+    mov.u32 %r5, %tid.y;
+    mad.lo.s32 %r7, %r3, %r4, %r5;
+
+    setp.ge.u32 %p1, %r6, %r1;
+    setp.ge.u32 %p2, %r7, %r2;
+    or.pred %p1, %p1, %p2;
+    @%p1 bra DONE;
+
+    cvt.u64.u32 %rd5, %r6;
+    cvt.u64.u32 %rd6, %r7;
+    mad.lo.s64 %rd7, %rd6, %rd2, %rd1;
+    shl.b64 %rd8, %rd5, 1;
+    add.s64 %rd7, %rd7, %rd8;
+    ld.global.u16 %rs1, [%rd7];
+    shr.u16 %rs2, %rs1, 8;
+    mad.lo.s64 %rd9, %rd6, %rd4, %rd3;
+    add.s64 %rd10, %rd9, %rd5;
+    st.global.u8 [%rd10], %rs2;
+
+DONE:
+    ret;
+}
+"#;
+
+const BLOCK_WIDTH: u32 = 32;
+const BLOCK_HEIGHT: u32 = 8;
+
+/// Converts 16-bit surfaces to their 8-bit twin, for formats NVDEC can only
+/// output at 16 bits.
+pub(crate) struct HighBytes {
+    ctx: CuContext,
+    module: ffi::CUmodule,
+    function: ffi::CUfunction,
+}
+
+impl HighBytes {
+    /// Loads the kernel into `ctx`.
+    pub(crate) fn load(ctx: &CuContext) -> NVCodecResult<Self> {
+        let _guard = ctx.clone().guard()?;
+
+        let image = CString::new(PTX).unwrap();
+        let mut module = std::ptr::null_mut();
+        let res = unsafe { ffi::cuModuleLoadData(&mut module, image.as_ptr() as _) };
+        wrap!((), res)?;
+
+        let name = CString::new("high_bytes").unwrap();
+        let mut function = std::ptr::null_mut();
+        let res = unsafe { ffi::cuModuleGetFunction(&mut function, module, name.as_ptr()) };
+        if let Err(err) = wrap!((), res) {
+            unsafe { ffi::cuModuleUnload(module) };
+            return Err(err.into());
+        }
+
+        Ok(Self { ctx: ctx.clone(), module, function })
+    }
+
+    /// Queues the conversion of `height` rows of `width` samples on `stream`.
+    /// Interleaved UV rows count `width` samples too.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn launch(
+        &self,
+        src: ffi::CUdeviceptr,
+        src_pitch: usize,
+        dst: ffi::CUdeviceptr,
+        dst_pitch: usize,
+        width: u32,
+        height: u32,
+        stream: &CuStream,
+    ) -> NVCodecResult<()> {
+        let _guard = self.ctx.clone().guard()?;
+
+        let (mut src, mut src_pitch) = (src, src_pitch as u64);
+        let (mut dst, mut dst_pitch) = (dst, dst_pitch as u64);
+        let (mut width, mut height) = (width, height);
+        let mut params = [
+            &mut src as *mut u64 as *mut std::os::raw::c_void,
+            &mut src_pitch as *mut u64 as _,
+            &mut dst as *mut u64 as _,
+            &mut dst_pitch as *mut u64 as _,
+            &mut width as *mut u32 as _,
+            &mut height as *mut u32 as _,
+        ];
+
+        let res = unsafe {
+            ffi::cuLaunchKernel(
+                self.function,
+                width.div_ceil(BLOCK_WIDTH),
+                height.div_ceil(BLOCK_HEIGHT),
+                1,
+                BLOCK_WIDTH,
+                BLOCK_HEIGHT,
+                1,
+                0,
+                stream.get_raw() as _,
+                params.as_mut_ptr(),
+                std::ptr::null_mut(),
+            )
+        };
+        wrap!((), res)?;
+
+        Ok(())
+    }
+}
+
+impl Drop for HighBytes {
+    fn drop(&mut self) {
+        if let Ok(_guard) = self.ctx.clone().guard() {
+            unsafe {
+                ffi::cuModuleUnload(self.module);
+            }
+        }
+    }
+}
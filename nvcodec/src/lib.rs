@@ -3,8 +3,23 @@ pub extern crate nvcodec_sys as ffi;
 #[macro_use]
 mod macros;
 
+pub mod caps;
+mod channel;
 pub mod codec;
+pub mod color;
+pub mod convert;
+pub mod cuda;
 pub mod error;
 pub mod decoder;
 pub mod demuxer;
+pub mod device;
+pub mod encoder;
+pub mod event;
+pub mod geometry;
+pub mod host;
+mod kernel;
+pub mod muxer;
+pub mod pool;
+pub mod stats;
 pub mod surface;
+pub mod worker;
@@ -3,7 +3,7 @@ macro_rules! wrap {
         if $res == crate::ffi::cudaError_enum_CUDA_SUCCESS {
             Ok($val)
         } else {
-            use cuda_rs::error::CuError;
+            use crate::cuda::CuError;
             Err(CuError::from($res))
         }
     )
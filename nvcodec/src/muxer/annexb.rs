@@ -0,0 +1,450 @@
+use ffmpeg_next::codec::Id as CodecId;
+use thiserror::Error;
+
+#[derive(Error, Clone, Debug, Eq, PartialEq)]
+pub enum BitstreamError {
+    #[error("No {0} found in the access unit")]
+    MissingParameterSet(&'static str),
+    #[error("{0} ends early")]
+    Truncated(&'static str),
+    #[error("Access unit does not start with a start code")]
+    NotAnnexB,
+    #[error("Temporal unit is not a sequence of OBUs")]
+    NotObus,
+}
+
+/// NAL units of an Annex B byte stream, without start codes.
+pub struct NalUnits<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for NalUnits<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = find_start_code(self.data)?;
+            let rest = &self.data[start + 3..];
+            let end = find_start_code(rest).unwrap_or(rest.len());
+            self.data = &rest[end..];
+
+            // Drops the trailing zeros, including the first byte of a
+            // four-byte start code.
+            let len = rest[..end].iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+            if len > 0 {
+                return Some(&rest[..len]);
+            }
+        }
+    }
+}
+
+fn find_start_code(data: &[u8]) -> Option<usize> {
+    data.windows(3).position(|w| w == [0, 0, 1])
+}
+
+pub fn nal_units(data: &[u8]) -> NalUnits<'_> {
+    NalUnits { data }
+}
+
+/// Replaces start codes with four-byte big-endian lengths, as MP4, MOV and
+/// Matroska store H.264 and HEVC samples. Fails on data that is not Annex B,
+/// e.g. already length-prefixed, rather than dropping it.
+pub fn to_length_prefixed(data: &[u8]) -> Result<Vec<u8>, BitstreamError> {
+    match find_start_code(data) {
+        Some(start) if data[..start].iter().all(|&b| b == 0) => {}
+        _ => return Err(BitstreamError::NotAnnexB),
+    }
+
+    let mut out = Vec::with_capacity(data.len() + 16);
+    for nal in nal_units(data) {
+        out.extend_from_slice(&(nal.len() as u32).to_be_bytes());
+        out.extend_from_slice(nal);
+    }
+    Ok(out)
+}
+
+/// Parameter sets carried in-band, in stream order without repeats.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ParameterSets {
+    pub vps: Vec<Vec<u8>>,
+    pub sps: Vec<Vec<u8>>,
+    pub pps: Vec<Vec<u8>>,
+}
+
+impl ParameterSets {
+    /// Collects the sets of an H.264 or HEVC access unit; other codecs have
+    /// none.
+    pub fn collect(codec_id: CodecId, data: &[u8]) -> Self {
+        let mut sets = Self::default();
+        sets.add(codec_id, data);
+        sets
+    }
+
+    /// Adds the sets of another access unit that are not there yet.
+    pub fn add(&mut self, codec_id: CodecId, data: &[u8]) {
+        for nal in nal_units(data) {
+            let list = match codec_id {
+                CodecId::H264 => match nal[0] & 0x1f {
+                    7 => &mut self.sps,
+                    8 => &mut self.pps,
+                    _ => continue,
+                },
+                CodecId::HEVC => match (nal[0] >> 1) & 0x3f {
+                    32 => &mut self.vps,
+                    33 => &mut self.sps,
+                    34 => &mut self.pps,
+                    _ => continue,
+                },
+                _ => break,
+            };
+            if !list.iter().any(|set| set == nal) {
+                list.push(nal.to_vec());
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vps.is_empty() && self.sps.is_empty() && self.pps.is_empty()
+    }
+}
+
+/// Reads bits of a NAL unit payload with emulation prevention bytes removed,
+/// or of a payload that has none.
+pub(super) struct BitReader {
+    rbsp: Vec<u8>,
+    pos: usize,
+    what: &'static str,
+}
+
+impl BitReader {
+    fn new(nal_payload: &[u8], what: &'static str) -> Self {
+        let mut rbsp = Vec::with_capacity(nal_payload.len());
+        let mut zeros = 0;
+        for &b in nal_payload {
+            if zeros >= 2 && b == 3 {
+                zeros = 0;
+                continue;
+            }
+            zeros = if b == 0 { zeros + 1 } else { 0 };
+            rbsp.push(b);
+        }
+        Self { rbsp, pos: 0, what }
+    }
+
+    /// For payloads without emulation prevention, such as AV1 OBUs.
+    pub(super) fn raw(payload: &[u8], what: &'static str) -> Self {
+        Self { rbsp: payload.to_vec(), pos: 0, what }
+    }
+
+    pub(super) fn bit(&mut self) -> Result<u32, BitstreamError> {
+        let byte = self.rbsp.get(self.pos / 8).ok_or(BitstreamError::Truncated(self.what))?;
+        let bit = (byte >> (7 - self.pos % 8)) & 1;
+        self.pos += 1;
+        Ok(bit as _)
+    }
+
+    pub(super) fn bits(&mut self, n: u32) -> Result<u64, BitstreamError> {
+        let mut value = 0;
+        for _ in 0..n {
+            value = (value << 1) | self.bit()? as u64;
+        }
+        Ok(value)
+    }
+
+    /// Exp-Golomb `ue(v)`.
+    pub(super) fn ue(&mut self) -> Result<u32, BitstreamError> {
+        let mut zeros = 0;
+        while self.bit()? == 0 {
+            zeros += 1;
+            if zeros > 31 {
+                return Err(BitstreamError::Truncated(self.what));
+            }
+        }
+        Ok(((1u64 << zeros) - 1 + self.bits(zeros)?) as _)
+    }
+}
+
+/// Builds an `AVCDecoderConfigurationRecord` (ISO/IEC 14496-15, 5.3.3.1)
+/// with four-byte NAL lengths.
+pub fn avcc(sets: &ParameterSets) -> Result<Vec<u8>, BitstreamError> {
+    let sps = sets.sps.first().ok_or(BitstreamError::MissingParameterSet("SPS"))?;
+    if sets.pps.is_empty() {
+        return Err(BitstreamError::MissingParameterSet("PPS"));
+    }
+    if sps.len() < 4 {
+        return Err(BitstreamError::Truncated("SPS"));
+    }
+
+    let mut out = vec![1, sps[1], sps[2], sps[3], 0xff, 0xe0 | sets.sps.len() as u8];
+    for sps in &sets.sps {
+        out.extend_from_slice(&(sps.len() as u16).to_be_bytes());
+        out.extend_from_slice(sps);
+    }
+    out.push(sets.pps.len() as u8);
+    for pps in &sets.pps {
+        out.extend_from_slice(&(pps.len() as u16).to_be_bytes());
+        out.extend_from_slice(pps);
+    }
+
+    // Profiles whose SPS codes the chroma format and bit depths append them,
+    // as FFmpeg and current readers expect for all of these, 4:4:4 included.
+    let profile_idc = sps[1];
+    if matches!(profile_idc, 44 | 83 | 86 | 100 | 110 | 118 | 122 | 128 | 134 | 135 | 138 | 139 | 244) {
+        let mut reader = BitReader::new(&sps[4..], "SPS");
+        reader.ue()?; // seq_parameter_set_id
+        let chroma_format_idc = reader.ue()?;
+        if chroma_format_idc == 3 {
+            reader.bit()?; // separate_colour_plane_flag
+        }
+        let bit_depth_luma_minus8 = reader.ue()?;
+        let bit_depth_chroma_minus8 = reader.ue()?;
+
+        out.push(0xfc | chroma_format_idc as u8);
+        out.push(0xf8 | bit_depth_luma_minus8 as u8);
+        out.push(0xf8 | bit_depth_chroma_minus8 as u8);
+        out.push(0);
+    }
+
+    Ok(out)
+}
+
+/// Builds an `HEVCDecoderConfigurationRecord` (ISO/IEC 14496-15, 8.3.3.1)
+/// with four-byte NAL lengths, taking the profile, tier, level, chroma format
+/// and bit depths from the first SPS.
+pub fn hvcc(sets: &ParameterSets) -> Result<Vec<u8>, BitstreamError> {
+    let sps = sets.sps.first().ok_or(BitstreamError::MissingParameterSet("SPS"))?;
+    if sets.vps.is_empty() {
+        return Err(BitstreamError::MissingParameterSet("VPS"));
+    }
+    if sets.pps.is_empty() {
+        return Err(BitstreamError::MissingParameterSet("PPS"));
+    }
+    if sps.len() < 2 {
+        return Err(BitstreamError::Truncated("SPS"));
+    }
+
+    let mut reader = BitReader::new(&sps[2..], "SPS");
+    reader.bits(4)?; // sps_video_parameter_set_id
+    let max_sub_layers_minus1 = reader.bits(3)? as u32;
+    let temporal_id_nesting = reader.bit()?;
+
+    // general_profile_space, tier, profile_idc, compatibility flags,
+    // constraint flags and level_idc: 12 bytes kept as they are.
+    let mut general = [0u8; 12];
+    for byte in general.iter_mut() {
+        *byte = reader.bits(8)? as u8;
+    }
+
+    let mut sub_layers = vec![];
+    for _ in 0..max_sub_layers_minus1 {
+        let profile_present = reader.bit()?;
+        let level_present = reader.bit()?;
+        sub_layers.push((profile_present, level_present));
+    }
+    if max_sub_layers_minus1 > 0 {
+        reader.bits(2 * (8 - max_sub_layers_minus1))?;
+    }
+    for (profile_present, level_present) in sub_layers {
+        if profile_present == 1 {
+            reader.bits(88)?;
+        }
+        if level_present == 1 {
+            reader.bits(8)?;
+        }
+    }
+
+    reader.ue()?; // sps_seq_parameter_set_id
+    let chroma_format_idc = reader.ue()?;
+    if chroma_format_idc == 3 {
+        reader.bit()?; // separate_colour_plane_flag
+    }
+    reader.ue()?; // pic_width_in_luma_samples
+    reader.ue()?; // pic_height_in_luma_samples
+    if reader.bit()? == 1 {
+        for _ in 0..4 {
+            reader.ue()?; // conformance window offsets
+        }
+    }
+    let bit_depth_luma_minus8 = reader.ue()?;
+    let bit_depth_chroma_minus8 = reader.ue()?;
+
+    let mut out = vec![1];
+    out.extend_from_slice(&general);
+    out.extend_from_slice(&[
+        0xf0, 0x00, // min_spatial_segmentation_idc
+        0xfc, // parallelismType
+        0xfc | chroma_format_idc as u8,
+        0xf8 | bit_depth_luma_minus8 as u8,
+        0xf8 | bit_depth_chroma_minus8 as u8,
+        0x00, 0x00, // avgFrameRate
+        ((max_sub_layers_minus1 as u8 + 1) << 3) | ((temporal_id_nesting as u8) << 2) | 0x3,
+        3,
+    ]);
+    for (nal_type, list) in [(32u8, &sets.vps), (33, &sets.sps), (34, &sets.pps)] {
+        out.push(0x80 | nal_type);
+        out.extend_from_slice(&(list.len() as u16).to_be_bytes());
+        for nal in list {
+            out.extend_from_slice(&(nal.len() as u16).to_be_bytes());
+            out.extend_from_slice(nal);
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const H264_HIGH_SPS: [u8; 26] = [
+        0x67, 0x64, 0x00, 0x1f, 0xac, 0xd9, 0x40, 0x50, 0x05, 0xbb, 0x01, 0x10, 0x00,
+        0x00, 0x03, 0x00, 0x10, 0x00, 0x00, 0x03, 0x03, 0xc0, 0xf1, 0x83, 0x19, 0x60,
+    ];
+    const H264_PPS: [u8; 6] = [0x68, 0xeb, 0xe3, 0xcb, 0x22, 0xc0];
+
+    // Main 10 with sps_max_sub_layers_minus1 = 2, the first sub-layer
+    // carrying a profile and both a level. Emulation prevention bytes follow
+    // the zero runs of the profile flags.
+    const HEVC_SPS: [u8; 44] = [
+        0x42, 0x01, 0x05, 0x02, 0x20, 0x00, 0x00, 0x03, 0x00, 0x90, 0x00, 0x00, 0x03, 0x00, 0x00,
+        0x03, 0x00, 0x5d, 0xd0, 0x00, 0x02, 0x20, 0x00, 0x00, 0x03, 0x00, 0x90, 0x00, 0x00, 0x03,
+        0x00, 0x00, 0x03, 0x00, 0x5a, 0x57, 0xa0, 0x03, 0xc0, 0x80, 0x10, 0xe7, 0xca, 0xdc,
+    ];
+    const HEVC_VPS: [u8; 4] = [0x40, 0x01, 0x0c, 0x01];
+    const HEVC_PPS: [u8; 7] = [0x44, 0x01, 0xc1, 0x72, 0xb4, 0x62, 0x40];
+
+    fn annexb(nals: &[&[u8]]) -> Vec<u8> {
+        let mut out = vec![];
+        for (i, nal) in nals.iter().enumerate() {
+            // Four-byte start code first, as encoders write, three after.
+            let start_code: &[u8] = if i == 0 { &[0, 0, 0, 1] } else { &[0, 0, 1] };
+            out.extend_from_slice(start_code);
+            out.extend_from_slice(nal);
+        }
+        out
+    }
+
+    #[test]
+    fn start_codes() {
+        let data = [0, 0, 0, 1, 0x09, 0xf0, 0, 0, 1, 0x65, 0x88, 0, 0, 0, 0, 1, 0x41, 0x9a, 0];
+        let nals: Vec<_> = nal_units(&data).collect();
+        assert_eq!(nals, [&[0x09, 0xf0][..], &[0x65, 0x88], &[0x41, 0x9a]]);
+
+        // Empty units and a start code at the very end yield nothing.
+        assert_eq!(nal_units(&[0, 0, 1, 0, 0, 1]).count(), 0);
+        assert_eq!(nal_units(&[0x65, 0x88]).count(), 0);
+    }
+
+    #[test]
+    fn length_prefixed() {
+        let data = [0, 0, 0, 1, 0x67, 0x42, 0, 0, 1, 0x68, 0, 0, 1, 0x65, 0x88, 0x84];
+        assert_eq!(
+            to_length_prefixed(&data).unwrap(),
+            [0, 0, 0, 2, 0x67, 0x42, 0, 0, 0, 1, 0x68, 0, 0, 0, 3, 0x65, 0x88, 0x84]
+        );
+
+        assert_eq!(to_length_prefixed(&[]), Err(BitstreamError::NotAnnexB));
+        assert_eq!(to_length_prefixed(&[0, 0, 0, 3, 0x65, 0x88, 0x84]), Err(BitstreamError::NotAnnexB));
+        assert_eq!(to_length_prefixed(&[0x65, 0, 0, 1, 0x41]), Err(BitstreamError::NotAnnexB));
+    }
+
+    #[test]
+    fn emulation_prevention() {
+        // Only a 3 after two zeros is dropped, and only the first one.
+        let reader = BitReader::new(&[0, 0, 3, 3, 0, 0, 3, 0, 3, 0, 3], "test");
+        assert_eq!(reader.rbsp, [0, 0, 3, 0, 0, 0, 3, 0, 3]);
+    }
+
+    #[test]
+    fn exp_golomb() {
+        // 1, 010, 011, 00100, 0001000
+        let mut reader = BitReader::new(&[0xa6, 0x41, 0x00], "test");
+        let values: Vec<_> = (0..5).map(|_| reader.ue().unwrap()).collect();
+        assert_eq!(values, [0, 1, 2, 3, 7]);
+        assert_eq!(reader.ue(), Err(BitstreamError::Truncated("test")));
+
+        let mut reader = BitReader::new(&[0xb4], "test");
+        assert_eq!(reader.bits(3), Ok(0b101));
+        assert_eq!(reader.bit(), Ok(1));
+        assert_eq!(reader.bits(4), Ok(0b0100));
+        assert_eq!(reader.bit(), Err(BitstreamError::Truncated("test")));
+    }
+
+    #[test]
+    fn parameter_sets() {
+        let data = annexb(&[&[0x09, 0xf0], &H264_HIGH_SPS, &H264_PPS, &[0x65, 0x88]]);
+        let mut sets = ParameterSets::collect(CodecId::H264, &data);
+        assert_eq!(sets.sps, [H264_HIGH_SPS.to_vec()]);
+        assert_eq!(sets.pps, [H264_PPS.to_vec()]);
+        assert!(sets.vps.is_empty());
+
+        // Repeats are not added again.
+        sets.add(CodecId::H264, &data);
+        assert_eq!((sets.sps.len(), sets.pps.len()), (1, 1));
+
+        assert!(ParameterSets::collect(CodecId::AV1, &data).is_empty());
+    }
+
+    #[test]
+    fn avcc_high_profile() {
+        let sets = ParameterSets::collect(CodecId::H264, &annexb(&[&H264_HIGH_SPS, &H264_PPS]));
+        let mut expected = vec![1, 0x64, 0x00, 0x1f, 0xff, 0xe1, 0, 26];
+        expected.extend_from_slice(&H264_HIGH_SPS);
+        expected.extend_from_slice(&[1, 0, 6]);
+        expected.extend_from_slice(&H264_PPS);
+        // 4:2:0, 8 bits.
+        expected.extend_from_slice(&[0xfd, 0xf8, 0xf8, 0]);
+        assert_eq!(avcc(&sets).unwrap(), expected);
+    }
+
+    #[test]
+    fn avcc_other_profiles() {
+        // Baseline has no extension.
+        let sps = [0x67, 0x42, 0xc0, 0x1e, 0xd9];
+        let sets = ParameterSets::collect(CodecId::H264, &annexb(&[&sps, &H264_PPS]));
+        let record = avcc(&sets).unwrap();
+        assert_eq!(record.len(), 6 + 2 + sps.len() + 1 + 2 + H264_PPS.len());
+        assert_eq!(record[..4], [1, 0x42, 0xc0, 0x1e]);
+
+        // High 4:4:4 Predictive: sps_id 0, 4:4:4, no separate planes, 10 bits.
+        let sps = [0x67, 0xf4, 0x00, 0x28, 0x90, 0xd9];
+        let sets = ParameterSets::collect(CodecId::H264, &annexb(&[&sps, &H264_PPS]));
+        let record = avcc(&sets).unwrap();
+        assert_eq!(record.len(), 6 + 2 + sps.len() + 1 + 2 + H264_PPS.len() + 4);
+        assert!(record.ends_with(&[0xff, 0xfa, 0xfa, 0]));
+    }
+
+    #[test]
+    fn avcc_missing_sets() {
+        let sets = ParameterSets::collect(CodecId::H264, &annexb(&[&H264_PPS]));
+        assert_eq!(avcc(&sets), Err(BitstreamError::MissingParameterSet("SPS")));
+        let sets = ParameterSets::collect(CodecId::H264, &annexb(&[&H264_HIGH_SPS]));
+        assert_eq!(avcc(&sets), Err(BitstreamError::MissingParameterSet("PPS")));
+        let sets = ParameterSets::collect(CodecId::H264, &annexb(&[&H264_HIGH_SPS[..4], &H264_PPS]));
+        assert_eq!(avcc(&sets), Err(BitstreamError::Truncated("SPS")));
+    }
+
+    #[test]
+    fn hvcc_sub_layers() {
+        let data = annexb(&[&HEVC_VPS, &HEVC_SPS, &HEVC_PPS, &[0x26, 0x01, 0xaf]]);
+        let sets = ParameterSets::collect(CodecId::HEVC, &data);
+        let record = hvcc(&sets).unwrap();
+
+        let mut expected = vec![1, 0x02, 0x20, 0, 0, 0, 0x90, 0, 0, 0, 0, 0, 0x5d];
+        // 4:2:0, 10 bits, 3 temporal layers nested, four-byte lengths.
+        expected.extend_from_slice(&[0xf0, 0, 0xfc, 0xfd, 0xfa, 0xfa, 0, 0, 0x1f, 3]);
+        for (nal_type, nal) in [(32, &HEVC_VPS[..]), (33, &HEVC_SPS), (34, &HEVC_PPS)] {
+            expected.extend_from_slice(&[0x80 | nal_type, 0, 1, 0, nal.len() as u8]);
+            expected.extend_from_slice(nal);
+        }
+        assert_eq!(record, expected);
+    }
+
+    #[test]
+    fn hvcc_missing_sets() {
+        let sets = ParameterSets::collect(CodecId::HEVC, &annexb(&[&HEVC_SPS, &HEVC_PPS]));
+        assert_eq!(hvcc(&sets), Err(BitstreamError::MissingParameterSet("VPS")));
+        let sets = ParameterSets::collect(CodecId::HEVC, &annexb(&[&HEVC_VPS, &HEVC_SPS[..20], &HEVC_PPS]));
+        assert_eq!(hvcc(&sets), Err(BitstreamError::Truncated("SPS")));
+    }
+}
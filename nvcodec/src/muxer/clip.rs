@@ -0,0 +1,655 @@
+use super::ffmpeg::{AccessUnit, Container, FFmpegMuxer};
+use crate::{
+    demuxer::ffmpeg::{FFmpegDemuxStream, Packet},
+    error::{NVCodecError, NVCodecResult},
+};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Part of the input to copy to `path`, the container following its
+/// extension. Times count from the first frame of the input.
+#[derive(Clone, Debug)]
+pub struct ClipRange {
+    pub start: Duration,
+    pub end: Duration,
+    pub path: PathBuf,
+}
+
+/// What a clip covers once cut at keyframes, in the input's time.
+#[derive(Clone, Debug)]
+pub struct ClipReport {
+    pub path: PathBuf,
+    /// When the keyframe the clip opens with is shown.
+    pub start: Duration,
+    /// Where the last picture written stops being shown.
+    pub end: Duration,
+    pub packets: usize,
+    /// Pictures after the opening keyframe in decode order but shown before
+    /// it, left out because they may reference pictures before the clip.
+    /// Only open GOPs have them.
+    pub leading_dropped: usize,
+}
+
+/// Timestamps and key flag of a packet, all the cutting looks at.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Timing {
+    pts: Option<i64>,
+    dts: Option<i64>,
+    duration: i64,
+    key: bool,
+}
+
+impl From<&Packet> for Timing {
+    fn from(packet: &Packet) -> Self {
+        Self {
+            pts: packet.pts(),
+            dts: packet.dts(),
+            duration: packet.duration(),
+            key: packet.is_key(),
+        }
+    }
+}
+
+impl Timing {
+    fn decode_ts(&self) -> Option<i64> {
+        self.dts.or(self.pts)
+    }
+}
+
+/// The packets of one output from its opening keyframe on, rebased so the
+/// keyframe, the first picture shown once leading pictures are gone, is
+/// shown at zero.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Segment {
+    /// pts of the opening keyframe.
+    start_ts: i64,
+    last_ts: i64,
+    /// Until the next keyframe, packets shown before `start_ts` are leading
+    /// pictures of the opening keyframe.
+    opening_gop: bool,
+    packets: usize,
+    leading_dropped: usize,
+}
+
+impl Segment {
+    /// `start_time` stands in for a keyframe without timestamps.
+    fn new(keyframe: &Timing, start_time: i64) -> Self {
+        let start_ts = keyframe.pts.or(keyframe.dts).unwrap_or(start_time);
+        Self {
+            start_ts,
+            last_ts: start_ts,
+            opening_gop: true,
+            packets: 0,
+            leading_dropped: 0,
+        }
+    }
+
+    /// The packet with rebased timestamps, or `None` for a leading picture.
+    /// The dts of pictures decoded before the keyframe is shown go negative.
+    fn take(&mut self, timing: &Timing) -> Option<Timing> {
+        if timing.key && self.packets > 0 {
+            self.opening_gop = false;
+        }
+        if self.opening_gop && timing.pts.is_some_and(|pts| pts < self.start_ts) {
+            self.leading_dropped += 1;
+            return None;
+        }
+
+        if let Some(pts) = timing.pts {
+            self.last_ts = self.last_ts.max(pts + timing.duration);
+        }
+        self.packets += 1;
+        Some(Timing {
+            pts: timing.pts.map(|pts| pts - self.start_ts),
+            dts: timing.dts.map(|dts| dts - self.start_ts),
+            ..*timing
+        })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum ClipStep {
+    Idle,
+    /// Opens the clip and writes these packets of the GOP, given by index.
+    Open(Vec<(usize, Timing)>),
+    /// Writes the packet.
+    Write(Timing),
+    /// Closes the clip, which ends before the packet.
+    Close(Segment),
+}
+
+enum ClipPhase {
+    Pending,
+    Open(Segment),
+    Done,
+}
+
+/// Decides from timestamps and key flags alone what one clip does with
+/// each packet.
+struct ClipCut {
+    start_ts: i64,
+    end_ts: i64,
+    start_time: i64,
+    phase: ClipPhase,
+}
+
+impl ClipCut {
+    fn new(start_ts: i64, end_ts: i64, start_time: i64) -> Self {
+        Self {
+            start_ts,
+            end_ts,
+            start_time,
+            phase: ClipPhase::Pending,
+        }
+    }
+
+    /// Steps over the last packet of `gop`, which holds the packets from the
+    /// last keyframe on.
+    ///
+    /// The clip opens once a packet of a GOP is shown at or after its start,
+    /// with the GOP's keyframe, and takes every packet decoded before its
+    /// end, so all pictures up to the end are kept plus any reference
+    /// picture shown after it.
+    fn next(&mut self, gop: &[Timing]) -> ClipStep {
+        let Some(timing) = gop.last() else {
+            return ClipStep::Idle;
+        };
+        let end_ts = self.end_ts;
+        let takes = |timing: &Timing| timing.decode_ts().is_none_or(|ts| ts < end_ts);
+
+        match &mut self.phase {
+            ClipPhase::Pending => {
+                if !gop[0].key || timing.pts.is_none_or(|pts| pts < self.start_ts) {
+                    return ClipStep::Idle;
+                }
+                let mut segment = Segment::new(&gop[0], self.start_time);
+                let write = gop
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, timing)| takes(timing))
+                    .filter_map(|(i, timing)| Some((i, segment.take(timing)?)))
+                    .collect();
+                self.phase = ClipPhase::Open(segment);
+                ClipStep::Open(write)
+            }
+            ClipPhase::Open(segment) if takes(timing) => match segment.take(timing) {
+                Some(timing) => ClipStep::Write(timing),
+                None => ClipStep::Idle,
+            },
+            ClipPhase::Open(_) => match std::mem::replace(&mut self.phase, ClipPhase::Done) {
+                ClipPhase::Open(segment) => ClipStep::Close(segment),
+                _ => unreachable!(),
+            },
+            ClipPhase::Done => ClipStep::Idle,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        matches!(self.phase, ClipPhase::Done)
+    }
+}
+
+fn write(muxer: &mut FFmpegMuxer, packet: &Packet, timing: Timing) -> NVCodecResult<()> {
+    let mut unit = AccessUnit::from(packet);
+    unit.pts = timing.pts;
+    unit.dts = timing.dts;
+    muxer.write(unit)
+}
+
+fn open(demuxer: &FFmpegDemuxStream, path: &Path, container: Container) -> NVCodecResult<FFmpegMuxer> {
+    FFmpegMuxer::new(
+        &path,
+        container,
+        demuxer.codec_id,
+        demuxer.width,
+        demuxer.height,
+        demuxer.time_base,
+    )
+}
+
+fn report(demuxer: &FFmpegDemuxStream, path: &Path, segment: Segment) -> ClipReport {
+    ClipReport {
+        path: path.to_path_buf(),
+        start: to_duration(demuxer, segment.start_ts),
+        end: to_duration(demuxer, segment.last_ts),
+        packets: segment.packets,
+        leading_dropped: segment.leading_dropped,
+    }
+}
+
+/// Copies each range of `demuxer` to its own file without decoding, in one
+/// pass over the packets.
+///
+/// A clip opens with the keyframe at or before its start and takes every
+/// packet decoded before its end, so all pictures up to the end are kept,
+/// plus any reference picture shown after it. Leading pictures of the
+/// opening keyframe are dropped, see `ClipReport::leading_dropped`.
+/// Timestamps are rebased so the opening keyframe is shown at zero. With
+/// B-frames the first pictures are then decoded before zero: MP4 and MOV
+/// keep the negative dts with an edit list, while Matroska and MPEG-TS
+/// shift the whole clip so it starts slightly after zero. Ranges may
+/// overlap and come in any order; the reports follow the order of
+/// `ranges`.
+pub fn clip(demuxer: &FFmpegDemuxStream, ranges: &[ClipRange]) -> NVCodecResult<Vec<ClipReport>> {
+    let mut containers = Vec::with_capacity(ranges.len());
+    let mut cuts = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        if range.end <= range.start {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Clip {} ends before it starts", range.path.display()),
+            ).into());
+        }
+        containers.push(container_for(&range.path)?);
+        cuts.push(ClipCut::new(
+            to_timestamp(demuxer, range.start),
+            to_timestamp(demuxer, range.end),
+            demuxer.start_time,
+        ));
+    }
+
+    let mut muxers: Vec<Option<FFmpegMuxer>> = ranges.iter().map(|_| None).collect();
+    let mut reports: Vec<Option<ClipReport>> = ranges.iter().map(|_| None).collect();
+    // Packets from the last keyframe on, for clips that start mid-GOP.
+    let mut gop: Vec<Packet> = vec![];
+    let mut gop_timing: Vec<Timing> = vec![];
+
+    for packet in demuxer.packets() {
+        let packet = packet?;
+        if packet.is_key() {
+            gop.clear();
+            gop_timing.clear();
+        }
+        gop_timing.push(Timing::from(&packet));
+        gop.push(packet);
+
+        for (i, cut) in cuts.iter_mut().enumerate() {
+            match cut.next(&gop_timing) {
+                ClipStep::Idle => {}
+                ClipStep::Open(packets) => {
+                    let muxer = muxers[i].insert(open(demuxer, &ranges[i].path, containers[i])?);
+                    for (index, timing) in packets {
+                        write(muxer, &gop[index], timing)?;
+                    }
+                }
+                ClipStep::Write(timing) => {
+                    write(muxers[i].as_mut().unwrap(), gop.last().unwrap(), timing)?;
+                }
+                ClipStep::Close(segment) => {
+                    muxers[i].take().unwrap().finish()?;
+                    reports[i] = Some(report(demuxer, &ranges[i].path, segment));
+                }
+            }
+        }
+
+        if cuts.iter().all(ClipCut::is_done) {
+            break;
+        }
+    }
+
+    for (i, cut) in cuts.into_iter().enumerate() {
+        if let ClipPhase::Open(segment) = cut.phase {
+            muxers[i].take().unwrap().finish()?;
+            reports[i] = Some(report(demuxer, &ranges[i].path, segment));
+        }
+    }
+    let mut missing = None;
+    let reports = ranges
+        .iter()
+        .zip(reports)
+        .filter_map(|(range, report)| {
+            if report.is_none() {
+                missing.get_or_insert_with(|| range.path.clone());
+            }
+            report
+        })
+        .collect();
+    if let Some(path) = missing {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Clip {} starts after the end of the input", path.display()),
+        ).into());
+    }
+
+    Ok(reports)
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+struct SplitStep {
+    /// Closes the open segment, given by index.
+    close: Option<(usize, Segment)>,
+    /// Then opens this segment.
+    open: Option<usize>,
+    /// Then writes the packet to the open segment.
+    write: Option<Timing>,
+}
+
+/// Decides from timestamps and key flags alone which segment each packet
+/// goes to.
+struct SplitCut {
+    point_ts: Vec<i64>,
+    start_time: i64,
+    open: Option<(usize, Segment)>,
+}
+
+impl SplitCut {
+    fn new(point_ts: Vec<i64>, start_time: i64) -> Self {
+        Self {
+            point_ts,
+            start_time,
+            open: None,
+        }
+    }
+
+    /// A keyframe shown at or after the next split point closes the open
+    /// segment and opens the one it falls in. Segments no keyframe falls in
+    /// are never opened.
+    fn next(&mut self, timing: Timing) -> SplitStep {
+        let mut step = SplitStep::default();
+        if timing.key {
+            let key_ts = timing.pts.or(timing.dts).unwrap_or(self.start_time);
+            let index = segment_of(&self.point_ts, key_ts);
+            if self.open.as_ref().is_none_or(|&(open, _)| index > open) {
+                step.close = self.open.take();
+                step.open = Some(index);
+                self.open = Some((index, Segment::new(&timing, self.start_time)));
+            }
+        }
+        if let Some((_, segment)) = self.open.as_mut() {
+            step.write = segment.take(&timing);
+        }
+        step
+    }
+
+    /// The segment still open at the end of the input.
+    fn finish(self) -> Option<(usize, Segment)> {
+        self.open
+    }
+}
+
+/// Splits `demuxer` at `points` without decoding, in one pass, into files
+/// named by `path_for(index)` for the segment indices `0..=points.len()`.
+///
+/// Each split point moves to the first keyframe at or after it, where the
+/// next segment opens, so every packet goes to exactly one segment; the
+/// reports give the actual bounds. Points with no keyframe between them
+/// collapse onto the same keyframe, and the segments left empty, like any
+/// before the first keyframe or after the last, are not written: there are
+/// fewer reports than segments then, and `ClipReport::path` tells which
+/// were. Leading pictures of the keyframes split at cannot be decoded on
+/// either side and are dropped, as in `clip`, and so are packets before the
+/// first keyframe. Timestamps are rebased as in `clip`.
+pub fn split<F: FnMut(usize) -> PathBuf>(
+    demuxer: &FFmpegDemuxStream,
+    points: &[Duration],
+    mut path_for: F,
+) -> NVCodecResult<Vec<ClipReport>> {
+    if points.windows(2).any(|pair| pair[1] <= pair[0]) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Split points must be increasing",
+        ).into());
+    }
+    let paths: Vec<_> = (0..=points.len()).map(&mut path_for).collect();
+    let containers = paths.iter().map(|path| container_for(path)).collect::<NVCodecResult<Vec<_>>>()?;
+    let point_ts = points.iter().map(|&point| to_timestamp(demuxer, point)).collect();
+
+    let mut cut = SplitCut::new(point_ts, demuxer.start_time);
+    let mut reports = vec![];
+    let mut muxer: Option<FFmpegMuxer> = None;
+    for packet in demuxer.packets() {
+        let packet = packet?;
+        let step = cut.next(Timing::from(&packet));
+        if let Some((index, segment)) = step.close {
+            muxer.take().unwrap().finish()?;
+            reports.push(report(demuxer, &paths[index], segment));
+        }
+        if let Some(index) = step.open {
+            muxer = Some(open(demuxer, &paths[index], containers[index])?);
+        }
+        if let Some(timing) = step.write {
+            write(muxer.as_mut().unwrap(), &packet, timing)?;
+        }
+    }
+
+    if let Some((index, segment)) = cut.finish() {
+        muxer.take().unwrap().finish()?;
+        reports.push(report(demuxer, &paths[index], segment));
+    }
+    if reports.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "No keyframe in the input").into());
+    }
+
+    Ok(reports)
+}
+
+/// The segment a GOP goes to: one past the last split point at or before
+/// its keyframe.
+fn segment_of(point_ts: &[i64], key_ts: i64) -> usize {
+    point_ts.partition_point(|&point| point <= key_ts)
+}
+
+fn container_for(path: &Path) -> NVCodecResult<Container> {
+    Container::from_path(&path).ok_or_else(|| {
+        let msg = format!("No container for {}", path.display());
+        NVCodecError::NotSupported(msg)
+    })
+}
+
+fn to_timestamp(demuxer: &FFmpegDemuxStream, time: Duration) -> i64 {
+    let (num, den) = (demuxer.time_base.numerator() as f64, demuxer.time_base.denominator() as f64);
+    let ts = (time.as_secs_f64() * den / num).round();
+    demuxer.start_time.saturating_add(ts.min(i64::MAX as f64) as i64)
+}
+
+fn to_duration(demuxer: &FFmpegDemuxStream, ts: i64) -> Duration {
+    let (num, den) = (demuxer.time_base.numerator() as f64, demuxer.time_base.denominator() as f64);
+    let offset = ts.saturating_sub(demuxer.start_time).max(0);
+    Duration::from_secs_f64(offset as f64 * num / den)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(pts: i64, dts: i64, key: bool) -> Timing {
+        Timing {
+            pts: Some(pts),
+            dts: Some(dts),
+            duration: 1,
+            key,
+        }
+    }
+
+    /// Three closed GOPs of I P B B P B B in decode order, dts one frame
+    /// behind the first pts.
+    fn closed_gops() -> Vec<Timing> {
+        let gop = [(0, 0), (3, 1), (1, 2), (2, 3), (6, 4), (4, 5), (5, 6)];
+        (0..3)
+            .flat_map(|n| gop.map(|(pts, i)| timing(7 * n + pts, 7 * n + i - 1, i == 0)))
+            .collect()
+    }
+
+    /// I0 P3 B1 B2, then the open GOPs I6 B4 B5 P9 B7 B8 and I12 B10 B11,
+    /// the B-frames after each later keyframe being its leading pictures.
+    fn open_gops() -> Vec<Timing> {
+        let packets = [
+            (0, -1), (3, 0), (1, 1), (2, 2),
+            (6, 3), (4, 4), (5, 5), (9, 6), (7, 7), (8, 8),
+            (12, 9), (10, 10), (11, 11),
+        ];
+        packets.iter().map(|&(pts, dts)| timing(pts, dts, pts % 6 == 0)).collect()
+    }
+
+    fn rebased(timing: &Timing) -> (i64, i64) {
+        (timing.pts.unwrap(), timing.dts.unwrap())
+    }
+
+    /// Feeds `packets` to a clip as `clip` does, returning the timestamps
+    /// written and the segment once closed, or still open at the end.
+    fn run_clip(packets: &[Timing], start_ts: i64, end_ts: i64) -> (Vec<(i64, i64)>, Option<Segment>) {
+        let mut cut = ClipCut::new(start_ts, end_ts, 0);
+        let mut gop = vec![];
+        let mut written = vec![];
+        for &packet in packets {
+            if packet.key {
+                gop.clear();
+            }
+            gop.push(packet);
+            match cut.next(&gop) {
+                ClipStep::Idle => {}
+                ClipStep::Open(packets) => written.extend(packets.iter().map(|(_, timing)| rebased(timing))),
+                ClipStep::Write(timing) => written.push(rebased(&timing)),
+                ClipStep::Close(segment) => return (written, Some(segment)),
+            }
+        }
+        match cut.phase {
+            ClipPhase::Open(segment) => (written, Some(segment)),
+            _ => (written, None),
+        }
+    }
+
+    /// Feeds `packets` to a split as `split` does, returning each segment
+    /// written with its index and timestamps.
+    #[allow(clippy::type_complexity)]
+    fn run_split(packets: &[Timing], point_ts: &[i64]) -> Vec<(usize, Vec<(i64, i64)>, Segment)> {
+        let mut cut = SplitCut::new(point_ts.to_vec(), 0);
+        let mut segments = vec![];
+        let mut written = vec![];
+        for &packet in packets {
+            let step = cut.next(packet);
+            if let Some((index, segment)) = step.close {
+                segments.push((index, std::mem::take(&mut written), segment));
+            }
+            if let Some(timing) = step.write {
+                written.push(rebased(&timing));
+            }
+        }
+        if let Some((index, segment)) = cut.finish() {
+            segments.push((index, written, segment));
+        }
+        segments
+    }
+
+    #[test]
+    fn segments() {
+        let points = [100, 200];
+        // A keyframe at a split point opens the next segment.
+        let keyframes = [0, 50, 100, 150, 230, 300];
+        let segments: Vec<_> = keyframes.iter().map(|&ts| segment_of(&points, ts)).collect();
+        assert_eq!(segments, [0, 0, 1, 1, 2, 2]);
+        assert_eq!(segment_of(&[], 1000), 0);
+    }
+
+    #[test]
+    fn clip_opens_with_the_buffered_gop() {
+        let packets = closed_gops();
+        let mut cut = ClipCut::new(4, 100, 0);
+
+        // Nothing is shown at 4 or later until P6, the fifth packet.
+        for n in 1..5 {
+            assert_eq!(cut.next(&packets[..n]), ClipStep::Idle);
+        }
+        let ClipStep::Open(written) = cut.next(&packets[..5]) else {
+            panic!("clip not opened");
+        };
+        let indices: Vec<_> = written.iter().map(|&(index, _)| index).collect();
+        assert_eq!(indices, [0, 1, 2, 3, 4]);
+        assert_eq!(cut.next(&packets[..6]), ClipStep::Write(timing(4, 4, false)));
+    }
+
+    #[test]
+    fn clip_rebases_on_the_first_picture_shown() {
+        let (written, segment) = run_clip(&closed_gops(), 7, 100);
+
+        // The keyframe is shown at zero and decoded a frame before it.
+        assert_eq!(written[..4], [(0, -1), (3, 0), (1, 1), (2, 2)]);
+        assert_eq!(written.iter().map(|&(pts, _)| pts).min(), Some(0));
+        assert!(written.iter().all(|&(pts, dts)| dts <= pts));
+        assert_eq!(written.len(), 14);
+
+        let segment = segment.unwrap();
+        assert_eq!((segment.start_ts, segment.last_ts, segment.packets), (7, 21, 14));
+    }
+
+    #[test]
+    fn clip_drops_leading_pictures_of_its_keyframe_only() {
+        let (written, segment) = run_clip(&open_gops(), 5, 100);
+
+        // B4 and B5 may reference P3, which is not in the clip; B10 and B11
+        // only reference pictures that are.
+        assert_eq!(written, [(0, -3), (3, 0), (1, 1), (2, 2), (6, 3), (4, 4), (5, 5)]);
+        let segment = segment.unwrap();
+        assert_eq!((segment.start_ts, segment.packets, segment.leading_dropped), (6, 7, 2));
+    }
+
+    #[test]
+    fn clip_closes_at_the_first_packet_decoded_at_its_end() {
+        let (written, segment) = run_clip(&closed_gops(), 0, 3);
+
+        // P3 is kept, shown after the end but referenced by B1 and B2.
+        assert_eq!(written, [(0, -1), (3, 0), (1, 1), (2, 2)]);
+        let segment = segment.unwrap();
+        assert_eq!((segment.packets, segment.last_ts), (4, 4));
+
+        // Packets without timestamps stay with the clip.
+        let mut cut = ClipCut::new(0, 3, 0);
+        cut.next(&[timing(0, -1, true)]);
+        let unknown = Timing::default();
+        assert_eq!(cut.next(&[timing(0, -1, true), unknown]), ClipStep::Write(unknown));
+        assert!(!cut.is_done());
+    }
+
+    #[test]
+    fn clip_after_the_input_never_opens() {
+        assert_eq!(run_clip(&closed_gops(), 21, 30), (vec![], None));
+    }
+
+    #[test]
+    fn split_writes_every_packet_once() {
+        let segments = run_split(&closed_gops(), &[5, 7, 14]);
+
+        let indices: Vec<_> = segments.iter().map(|(index, _, _)| *index).collect();
+        assert_eq!(indices, [0, 2, 3]);
+        for (_, written, segment) in &segments {
+            assert_eq!(written.len(), 7);
+            assert_eq!(written[0], (0, -1));
+            assert_eq!(segment.packets, 7);
+        }
+        assert_eq!(segments[1].2.start_ts, 7);
+    }
+
+    #[test]
+    fn split_collapses_points_without_keyframes_between_them() {
+        // 8, 10 and 12 all move to the keyframe at 14, and 30 is after it.
+        let segments = run_split(&closed_gops(), &[8, 10, 12, 30]);
+        let indices: Vec<_> = segments.iter().map(|(index, _, _)| *index).collect();
+        assert_eq!(indices, [0, 3]);
+        assert_eq!(segments[0].1.len(), 14);
+        assert_eq!(segments[1].1.len(), 7);
+    }
+
+    #[test]
+    fn split_skips_packets_before_the_first_keyframe() {
+        let packets = &closed_gops()[4..];
+        let segments = run_split(packets, &[3]);
+
+        assert_eq!(segments.len(), 1);
+        let (index, written, segment) = &segments[0];
+        assert_eq!(*index, 1);
+        assert_eq!(written.len(), 14);
+        assert_eq!(segment.start_ts, 7);
+    }
+
+    #[test]
+    fn split_drops_leading_pictures_on_both_sides() {
+        let segments = run_split(&open_gops(), &[6]);
+
+        assert_eq!(segments[0].1, [(0, -1), (3, 0), (1, 1), (2, 2)]);
+        assert_eq!(segments[1].1, [(0, -3), (3, 0), (1, 1), (2, 2), (6, 3), (4, 4), (5, 5)]);
+        assert_eq!(segments[1].2.leading_dropped, 2);
+    }
+}
@@ -0,0 +1,247 @@
+use super::{
+    annexb::{self, BitstreamError, ParameterSets},
+    obu,
+};
+use crate::{demuxer::ffmpeg::Packet, encoder::EncodedPacket, error::NVCodecResult};
+use std::{io, path::Path};
+use ffmpeg_next::{
+    codec::{
+        Id as CodecId,
+        packet::{Flags, Packet as AVPacket},
+    },
+    format::context::Output,
+    util::rational::Rational,
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Container {
+    MP4,
+    MKV,
+    MOV,
+    MPEGTS,
+}
+
+impl Container {
+    /// Picks the container from the file extension.
+    pub fn from_path<P: AsRef<Path>>(path: &P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "mp4" | "m4v" => Some(Container::MP4),
+            "mkv" => Some(Container::MKV),
+            "mov" => Some(Container::MOV),
+            "ts" | "m2ts" => Some(Container::MPEGTS),
+            _ => None,
+        }
+    }
+
+    /// FFmpeg muxer name.
+    pub fn format_name(&self) -> &'static str {
+        match self {
+            Container::MP4 => "mp4",
+            Container::MKV => "matroska",
+            Container::MOV => "mov",
+            Container::MPEGTS => "mpegts",
+        }
+    }
+}
+
+/// One coded picture, Annex B for H.264 and HEVC and a temporal unit of
+/// OBUs for AV1, with timestamps in the muxer's input time base.
+#[derive(Clone, Copy, Debug)]
+pub struct AccessUnit<'a> {
+    pub data: &'a [u8],
+    pub pts: Option<i64>,
+    pub dts: Option<i64>,
+    /// 0 when unknown.
+    pub duration: i64,
+    pub key: bool,
+}
+
+impl<'a> From<&'a Packet> for AccessUnit<'a> {
+    fn from(packet: &'a Packet) -> Self {
+        Self {
+            data: packet.data().unwrap_or_default(),
+            pts: packet.pts(),
+            dts: packet.dts(),
+            duration: packet.duration(),
+            key: packet.is_key(),
+        }
+    }
+}
+
+impl<'a> From<&'a EncodedPacket> for AccessUnit<'a> {
+    fn from(packet: &'a EncodedPacket) -> Self {
+        Self {
+            data: &packet.data,
+            pts: Some(packet.pts),
+            dts: Some(packet.dts),
+            duration: 0,
+            key: packet.key,
+        }
+    }
+}
+
+/// Writes a single video stream to a file without re-encoding.
+///
+/// The header goes out with the first access unit. For H.264 and HEVC in
+/// MP4, MOV and Matroska the SPS and PPS (and VPS) become the avcC or hvcC
+/// record, so the header waits for the access unit that completes them, as
+/// keyframes from `FFmpegDemuxStream` and `NVEncoder` do. Samples are then
+/// stored length-prefixed. Likewise the AV1 sequence header becomes the av1C
+/// record. Access units before the record cannot be decoded and are
+/// dropped, see `skipped`. MPEG-TS keeps the stream as it is, and other
+/// codecs are written as they come.
+pub struct FFmpegMuxer {
+    octx: Output,
+    codec_id: CodecId,
+    // Whether the header carries a record built from the stream.
+    record: bool,
+    length_prefixed: bool,
+    // Gathered until they make a complete record.
+    sets: ParameterSets,
+    skipped: u64,
+    input_time_base: Rational,
+    stream_time_base: Rational,
+    header_written: bool,
+    finished: bool,
+}
+
+impl FFmpegMuxer {
+    /// `time_base` is the unit of the timestamps handed to `write`, e.g.
+    /// `FFmpegDemuxStream::time_base` or one over the encoder frame rate.
+    pub fn new<P: AsRef<Path>>(
+        path: &P,
+        container: Container,
+        codec_id: CodecId,
+        width: u32,
+        height: u32,
+        time_base: Rational,
+    ) -> NVCodecResult<Self> {
+        let mut octx = ffmpeg_next::format::output_as(path, container.format_name())?;
+        let mut stream = octx.add_stream(ffmpeg_next::encoder::find(CodecId::None))?;
+        stream.set_time_base(time_base);
+        unsafe {
+            let params = (*stream.as_mut_ptr()).codecpar;
+            (*params).codec_type = ffmpeg_next::ffi::AVMediaType::AVMEDIA_TYPE_VIDEO;
+            (*params).codec_id = codec_id.into();
+            (*params).codec_tag = 0;
+            (*params).width = width as _;
+            (*params).height = height as _;
+        }
+
+        let record = container != Container::MPEGTS
+            && matches!(codec_id, CodecId::H264 | CodecId::HEVC | CodecId::AV1);
+        let length_prefixed = record && codec_id != CodecId::AV1;
+
+        Ok(Self {
+            octx,
+            codec_id,
+            record,
+            length_prefixed,
+            sets: ParameterSets::default(),
+            skipped: 0,
+            input_time_base: time_base,
+            stream_time_base: time_base,
+            header_written: false,
+            finished: false,
+        })
+    }
+
+    pub fn write<'a>(&mut self, unit: impl Into<AccessUnit<'a>>) -> NVCodecResult<()> {
+        let unit = unit.into();
+        if !self.header_written {
+            let extradata = if self.record {
+                self.sets.add(self.codec_id, unit.data);
+                let record = match self.codec_id {
+                    CodecId::AV1 => obu::av1c(unit.data),
+                    CodecId::HEVC => annexb::hvcc(&self.sets),
+                    _ => annexb::avcc(&self.sets),
+                };
+                match record {
+                    Ok(record) => Some(record),
+                    Err(BitstreamError::MissingParameterSet(_)) => {
+                        self.skipped += 1;
+                        return Ok(());
+                    }
+                    Err(err) => return Err(err.into()),
+                }
+            } else {
+                None
+            };
+            self.write_header(extradata)?;
+        }
+
+        let mut packet = if self.length_prefixed {
+            AVPacket::copy(&annexb::to_length_prefixed(unit.data)?)
+        } else {
+            AVPacket::copy(unit.data)
+        };
+        packet.set_pts(unit.pts);
+        packet.set_dts(unit.dts);
+        packet.set_duration(unit.duration);
+        if unit.key {
+            packet.set_flags(Flags::KEY);
+        }
+        packet.set_stream(0);
+        packet.set_position(-1);
+        packet.rescale_ts(self.input_time_base, self.stream_time_base);
+        packet.write_interleaved(&mut self.octx)?;
+        Ok(())
+    }
+
+    /// Access units dropped because the parameter sets, or the AV1 sequence
+    /// header, had not been seen yet.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Flushes interleaved packets and writes the trailer. Dropping the
+    /// muxer does the same but loses any error.
+    pub fn finish(mut self) -> NVCodecResult<()> {
+        self.finished = true;
+        if !self.header_written {
+            let msg = if self.skipped > 0 {
+                "No access unit carried the parameter sets or sequence header"
+            } else {
+                "No packets were written"
+            };
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, msg).into());
+        }
+        self.octx.write_trailer()?;
+        Ok(())
+    }
+
+    fn write_header(&mut self, extradata: Option<Vec<u8>>) -> NVCodecResult<()> {
+        if let Some(extradata) = extradata {
+            unsafe {
+                let params = (*self.octx.stream_mut(0).unwrap().as_mut_ptr()).codecpar;
+                let padding = ffmpeg_next::ffi::AV_INPUT_BUFFER_PADDING_SIZE as usize;
+                let buf = ffmpeg_next::ffi::av_mallocz(extradata.len() + padding) as *mut u8;
+                if buf.is_null() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::OutOfMemory,
+                        "Failed to allocate extradata",
+                    ).into());
+                }
+                std::ptr::copy_nonoverlapping(extradata.as_ptr(), buf, extradata.len());
+                (*params).extradata = buf;
+                (*params).extradata_size = extradata.len() as _;
+            }
+        }
+
+        self.octx.write_header()?;
+        // The muxer may have replaced the time base, e.g. with an MP4
+        // timescale.
+        self.stream_time_base = self.octx.stream(0).unwrap().time_base();
+        self.header_written = true;
+        Ok(())
+    }
+}
+
+impl Drop for FFmpegMuxer {
+    fn drop(&mut self) {
+        if self.header_written && !self.finished {
+            let _ = self.octx.write_trailer();
+        }
+    }
+}
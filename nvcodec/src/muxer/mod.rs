@@ -0,0 +1,4 @@
+pub mod annexb;
+pub mod clip;
+pub mod ffmpeg;
+pub mod obu;
@@ -0,0 +1,385 @@
+use super::annexb::{BitReader, BitstreamError};
+
+const OBU_SEQUENCE_HEADER: u8 = 1;
+
+/// One OBU of an AV1 temporal unit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Obu<'a> {
+    pub obu_type: u8,
+    /// The header byte, and the extension byte if any, without the size.
+    pub header: &'a [u8],
+    pub payload: &'a [u8],
+}
+
+/// OBUs of a temporal unit in the low-overhead format that NVENC and FFmpeg
+/// produce. Only the last OBU may go without a size field. Ends after the
+/// first error.
+pub struct Obus<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Obus<'a> {
+    type Item = Result<Obu<'a>, BitstreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let obu = self.parse();
+        if obu.is_err() {
+            self.data = &[];
+        }
+        Some(obu)
+    }
+}
+
+impl<'a> Obus<'a> {
+    fn parse(&mut self) -> Result<Obu<'a>, BitstreamError> {
+        let data = self.data;
+        let first = data[0];
+        if first & 0x80 != 0 {
+            // obu_forbidden_bit
+            return Err(BitstreamError::NotObus);
+        }
+        let header_len = if first & 0x04 != 0 { 2 } else { 1 };
+        let header = data.get(..header_len).ok_or(BitstreamError::Truncated("OBU header"))?;
+
+        let (payload_len, size_len) = if first & 0x02 != 0 {
+            leb128(&data[header_len..])?
+        } else {
+            (data.len() - header_len, 0)
+        };
+        let start = header_len + size_len;
+        let end = start.checked_add(payload_len).ok_or(BitstreamError::Truncated("OBU"))?;
+        let payload = data.get(start..end).ok_or(BitstreamError::Truncated("OBU"))?;
+        self.data = &data[end..];
+
+        Ok(Obu {
+            obu_type: (first >> 3) & 0x0f,
+            header,
+            payload,
+        })
+    }
+}
+
+pub fn obus(data: &[u8]) -> Obus<'_> {
+    Obus { data }
+}
+
+/// The value and the number of bytes of a `leb128()` field.
+fn leb128(data: &[u8]) -> Result<(usize, usize), BitstreamError> {
+    let mut value = 0u64;
+    for i in 0..8 {
+        let byte = *data.get(i).ok_or(BitstreamError::Truncated("OBU size"))?;
+        value |= ((byte & 0x7f) as u64) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Ok((value as _, i + 1));
+        }
+    }
+    Err(BitstreamError::Truncated("OBU size"))
+}
+
+fn write_leb128(out: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// `uvlc()`, which only timing info uses.
+fn uvlc(reader: &mut BitReader) -> Result<u32, BitstreamError> {
+    let mut zeros = 0;
+    while reader.bit()? == 0 {
+        zeros += 1;
+        if zeros >= 32 {
+            return Ok(u32::MAX);
+        }
+    }
+    Ok(((1u64 << zeros) - 1 + reader.bits(zeros)?) as _)
+}
+
+/// Builds an `AV1CodecConfigurationRecord` (AV1 Codec ISO Media File Format
+/// Binding, 2.3.3) from the sequence header OBU of a temporal unit. The
+/// header is kept as the only config OBU, given a size field if it had none.
+pub fn av1c(data: &[u8]) -> Result<Vec<u8>, BitstreamError> {
+    let obu = obus(data)
+        .find(|obu| !matches!(obu, Ok(obu) if obu.obu_type != OBU_SEQUENCE_HEADER))
+        .ok_or(BitstreamError::MissingParameterSet("sequence header"))??;
+
+    let mut reader = BitReader::raw(obu.payload, "sequence header");
+    let seq_profile = reader.bits(3)? as u8;
+    reader.bit()?; // still_picture
+    let reduced_still_picture_header = reader.bit()? == 1;
+
+    let (seq_level_idx_0, seq_tier_0) = if reduced_still_picture_header {
+        (reader.bits(5)? as u8, 0)
+    } else {
+        let mut decoder_model_info_present = false;
+        let mut buffer_delay_length = 0;
+        if reader.bit()? == 1 {
+            // timing_info: num_units_in_display_tick, time_scale
+            reader.bits(64)?;
+            if reader.bit()? == 1 {
+                uvlc(&mut reader)?; // num_ticks_per_picture_minus_1
+            }
+            decoder_model_info_present = reader.bit()? == 1;
+            if decoder_model_info_present {
+                buffer_delay_length = reader.bits(5)? as u32 + 1;
+                // num_units_in_decoding_tick, buffer_removal_time_length_minus_1,
+                // frame_presentation_time_length_minus_1
+                reader.bits(42)?;
+            }
+        }
+        let initial_display_delay_present = reader.bit()? == 1;
+
+        let mut first = (0, 0);
+        let operating_points = reader.bits(5)? + 1;
+        for i in 0..operating_points {
+            reader.bits(12)?; // operating_point_idc
+            let level = reader.bits(5)? as u8;
+            let tier = if level > 7 { reader.bit()? as u8 } else { 0 };
+            if i == 0 {
+                first = (level, tier);
+            }
+            if decoder_model_info_present && reader.bit()? == 1 {
+                // decoder_buffer_delay, encoder_buffer_delay, low_delay_mode_flag
+                reader.bits(2 * buffer_delay_length)?;
+                reader.bit()?;
+            }
+            if initial_display_delay_present && reader.bit()? == 1 {
+                reader.bits(4)?; // initial_display_delay_minus_1
+            }
+        }
+        first
+    };
+
+    let frame_width_bits = reader.bits(4)? as u32 + 1;
+    let frame_height_bits = reader.bits(4)? as u32 + 1;
+    reader.bits(frame_width_bits + frame_height_bits)?; // max_frame_width/height_minus_1
+    if !reduced_still_picture_header && reader.bit()? == 1 {
+        // delta_frame_id_length_minus_2, additional_frame_id_length_minus_1
+        reader.bits(7)?;
+    }
+    // use_128x128_superblock, enable_filter_intra, enable_intra_edge_filter
+    reader.bits(3)?;
+    if !reduced_still_picture_header {
+        // enable_interintra_compound, enable_masked_compound,
+        // enable_warped_motion, enable_dual_filter
+        reader.bits(4)?;
+        let enable_order_hint = reader.bit()? == 1;
+        if enable_order_hint {
+            reader.bits(2)?; // enable_jnt_comp, enable_ref_frame_mvs
+        }
+        let seq_force_screen_content_tools = if reader.bit()? == 1 { 2 } else { reader.bit()? };
+        if seq_force_screen_content_tools > 0 && reader.bit()? == 0 {
+            reader.bit()?; // seq_force_integer_mv
+        }
+        if enable_order_hint {
+            reader.bits(3)?; // order_hint_bits_minus_1
+        }
+    }
+    reader.bits(3)?; // enable_superres, enable_cdef, enable_restoration
+
+    // color_config()
+    let high_bitdepth = reader.bit()? as u8;
+    let twelve_bit = if seq_profile == 2 && high_bitdepth == 1 { reader.bit()? as u8 } else { 0 };
+    let mono_chrome = if seq_profile == 1 { 0 } else { reader.bit()? as u8 };
+    let (color_primaries, transfer_characteristics, matrix_coefficients) = if reader.bit()? == 1 {
+        (reader.bits(8)?, reader.bits(8)?, reader.bits(8)?)
+    } else {
+        (2, 2, 2)
+    };
+    let (subsampling_x, subsampling_y, chroma_sample_position) = if mono_chrome == 1 {
+        (1, 1, 0)
+    } else if (color_primaries, transfer_characteristics, matrix_coefficients) == (1, 13, 0) {
+        // sRGB
+        (0, 0, 0)
+    } else {
+        reader.bit()?; // color_range
+        let (x, y) = match seq_profile {
+            0 => (1, 1),
+            1 => (0, 0),
+            _ if twelve_bit == 1 => {
+                let x = reader.bit()? as u8;
+                (x, if x == 1 { reader.bit()? as u8 } else { 0 })
+            }
+            _ => (1, 0),
+        };
+        let position = if x == 1 && y == 1 { reader.bits(2)? as u8 } else { 0 };
+        (x, y, position)
+    };
+
+    let mut out = vec![
+        0x81, // marker, version 1
+        seq_profile << 5 | seq_level_idx_0,
+        seq_tier_0 << 7
+            | high_bitdepth << 6
+            | twelve_bit << 5
+            | mono_chrome << 4
+            | subsampling_x << 3
+            | subsampling_y << 2
+            | chroma_sample_position,
+        0, // no initial_presentation_delay
+    ];
+    out.push(obu.header[0] | 0x02);
+    out.extend_from_slice(&obu.header[1..]);
+    write_leb128(&mut out, obu.payload.len());
+    out.extend_from_slice(obu.payload);
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPORAL_DELIMITER: [u8; 2] = [0x12, 0x00];
+
+    /// Writes a sequence header payload bit by bit, ending it with the
+    /// trailing bits.
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        len: usize,
+    }
+
+    impl BitWriter {
+        fn put(&mut self, n: u32, value: u64) -> &mut Self {
+            for i in (0..n).rev() {
+                if self.len / 8 == self.bytes.len() {
+                    self.bytes.push(0);
+                }
+                let bit = ((value >> i) & 1) as u8;
+                *self.bytes.last_mut().unwrap() |= bit << (7 - self.len % 8);
+                self.len += 1;
+            }
+            self
+        }
+
+        fn finish(&mut self) -> Vec<u8> {
+            self.put(1, 1);
+            std::mem::take(&mut self.bytes)
+        }
+    }
+
+    /// An OBU with a size field.
+    fn obu(obu_type: u8, payload: &[u8]) -> Vec<u8> {
+        let mut out = vec![obu_type << 3 | 0x02];
+        write_leb128(&mut out, payload.len());
+        out.extend_from_slice(payload);
+        out
+    }
+
+    /// The part after the operating points shared by the tests: 1920x1080
+    /// with order hints and the usual tool flags.
+    fn frame_size_and_tools(bits: &mut BitWriter) {
+        bits.put(4, 10).put(4, 10); // frame_width/height_bits_minus_1
+        bits.put(11, 1919).put(11, 1079);
+        bits.put(1, 0); // frame_id_numbers_present_flag
+        bits.put(3, 0b011);
+        bits.put(4, 0b1111);
+        bits.put(1, 1); // enable_order_hint
+        bits.put(2, 0b11);
+        bits.put(1, 1); // seq_choose_screen_content_tools
+        bits.put(1, 1); // seq_choose_integer_mv
+        bits.put(3, 6); // order_hint_bits_minus_1
+        bits.put(3, 0b011);
+    }
+
+    #[test]
+    fn main_profile() {
+        let mut bits = BitWriter::default();
+        bits.put(3, 0).put(1, 0).put(1, 0); // profile, still_picture, reduced
+        bits.put(1, 0).put(1, 0); // timing_info_present, initial_display_delay_present
+        bits.put(5, 0); // operating_points_cnt_minus_1
+        bits.put(12, 0).put(5, 8).put(1, 0); // idc, level 4.0, main tier
+        frame_size_and_tools(&mut bits);
+        bits.put(1, 0).put(1, 0); // high_bitdepth, mono_chrome
+        bits.put(1, 1).put(8, 1).put(8, 1).put(8, 1); // BT.709
+        bits.put(1, 0).put(2, 1); // color_range, chroma_sample_position
+        bits.put(1, 0); // separate_uv_delta_q
+        let header = obu(OBU_SEQUENCE_HEADER, &bits.finish());
+
+        let mut data = TEMPORAL_DELIMITER.to_vec();
+        data.extend_from_slice(&header);
+        data.extend_from_slice(&obu(6, &[0xaa, 0xbb]));
+
+        let record = av1c(&data).unwrap();
+        assert_eq!(record[..4], [0x81, 0x08, 0x0d, 0x00]);
+        assert_eq!(record[4..], header);
+    }
+
+    #[test]
+    fn professional_profile_with_decoder_model() {
+        let mut bits = BitWriter::default();
+        bits.put(3, 2).put(1, 0).put(1, 0);
+        bits.put(1, 1); // timing_info_present
+        bits.put(32, 1001).put(32, 60000);
+        bits.put(1, 1).put(3, 0b010); // equal_picture_interval, uvlc() = 1
+        bits.put(1, 1); // decoder_model_info_present
+        bits.put(5, 9).put(32, 1001).put(5, 4).put(5, 4);
+        bits.put(1, 1); // initial_display_delay_present
+        bits.put(5, 1); // two operating points
+        bits.put(12, 0x103).put(5, 13).put(1, 1); // level 5.1, high tier
+        bits.put(1, 1).put(10, 5).put(10, 5).put(1, 0); // operating_parameters_info
+        bits.put(1, 1).put(4, 3); // initial_display_delay_minus_1
+        bits.put(12, 0x101).put(5, 5); // level 3.1, no tier
+        bits.put(1, 0).put(1, 0);
+        frame_size_and_tools(&mut bits);
+        bits.put(1, 1).put(1, 1).put(1, 0); // high_bitdepth, twelve_bit, mono_chrome
+        bits.put(1, 0); // color_description_present
+        bits.put(1, 1).put(1, 1).put(1, 0); // color_range, 4:2:2
+        bits.put(1, 0);
+        let payload = bits.finish();
+
+        // The sequence header closes the temporal unit without a size field.
+        let mut data = TEMPORAL_DELIMITER.to_vec();
+        data.push(OBU_SEQUENCE_HEADER << 3);
+        data.extend_from_slice(&payload);
+
+        let record = av1c(&data).unwrap();
+        assert_eq!(record[..4], [0x81, 0x4d, 0xe8, 0x00]);
+        assert_eq!(record[4..], obu(OBU_SEQUENCE_HEADER, &payload));
+    }
+
+    #[test]
+    fn monochrome_still_picture() {
+        let mut bits = BitWriter::default();
+        bits.put(3, 0).put(1, 1).put(1, 1); // still_picture, reduced
+        bits.put(5, 9); // level 4.1
+        bits.put(4, 10).put(4, 10).put(11, 1919).put(11, 1079);
+        bits.put(3, 0).put(3, 0);
+        bits.put(1, 0).put(1, 1).put(1, 0).put(1, 0); // 8-bit mono, color_range
+        let header = obu(OBU_SEQUENCE_HEADER, &bits.finish());
+
+        let record = av1c(&header).unwrap();
+        assert_eq!(record[..4], [0x81, 0x09, 0x1c, 0x00]);
+    }
+
+    #[test]
+    fn rejects_other_data() {
+        let missing = av1c(&[TEMPORAL_DELIMITER, [0x32, 0x00]].concat());
+        assert_eq!(missing, Err(BitstreamError::MissingParameterSet("sequence header")));
+        assert_eq!(av1c(&[0x0a, 0x05, 0x00]), Err(BitstreamError::Truncated("OBU")));
+        assert_eq!(av1c(&[0x0a, 0x01, 0x00]), Err(BitstreamError::Truncated("sequence header")));
+        assert_eq!(av1c(&[0x0a, 0x80]), Err(BitstreamError::Truncated("OBU size")));
+        assert_eq!(av1c(&[0x92, 0x00]), Err(BitstreamError::NotObus));
+    }
+
+    #[test]
+    fn obu_extension_and_sizes() {
+        let payload = vec![0x55; 200];
+        let mut data = vec![0x34 | 0x04 | 0x02, 0x28];
+        write_leb128(&mut data, payload.len());
+        assert_eq!(data[2..], [0xc8, 0x01]);
+        data.extend_from_slice(&payload);
+
+        let obus = obus(&data).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(obus, [Obu { obu_type: 6, header: &[0x36, 0x28], payload: &payload }]);
+    }
+}
@@ -0,0 +1,704 @@
+use crate::{
+    caps,
+    codec::CuVideoCodecType,
+    cuda::CuStream,
+    decoder::NVDecoder,
+    error::{NVCodecError, NVCodecResult},
+    surface::{BitDepth, ChromaFormat},
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex},
+};
+
+/// What a pooled decoder is set up for. Leases only reuse decoders with an
+/// equal key.
+///
+/// `chroma_format` and `bit_depth` are what the caller expects of its
+/// streams. Creating a decoder for a key fails with `NotSupported` unless
+/// the device decodes that codec, chroma format and bit depth (10-bit for
+/// `BitDepth::Sixteen`) at `max_width` x `max_height`, when set. A stream that differs
+/// from its key still decodes, by re-creating the hardware decoder.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DecoderKey {
+    /// Caller-chosen id of a device registered with `DecoderPool::add_device`.
+    pub device: i32,
+    pub codec: CuVideoCodecType,
+    pub chroma_format: ChromaFormat,
+    pub bit_depth: BitDepth,
+    pub max_width: u32,
+    pub max_height: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DeviceOccupancy {
+    pub device: i32,
+    pub max_sessions: usize,
+    /// Decoders handed out.
+    pub leased: usize,
+    /// Decoders kept alive for reuse. They hold a session too.
+    pub idle: usize,
+    /// Callers queued for a session.
+    pub waiting: usize,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PoolStats {
+    pub devices: Vec<DeviceOccupancy>,
+    /// Leases served by an idle decoder.
+    pub reused: u64,
+    /// Decoders created.
+    pub created: u64,
+    /// Idle decoders dropped to make room for another key.
+    pub evicted: u64,
+    /// Leases that had to queue.
+    pub waited: u64,
+}
+
+/// What the pool needs of its decoders, so the session bookkeeping runs
+/// without a GPU in the tests.
+trait Pooled: Send + Sized + 'static {
+    /// What decoders for a registered device are created from.
+    type Device: Clone;
+
+    fn create(device: &Self::Device, key: &DecoderKey) -> NVCodecResult<Self>;
+
+    /// Readies a returned decoder for the next lease.
+    fn recycle(&mut self, key: &DecoderKey) -> NVCodecResult<()>;
+}
+
+impl Pooled for NVDecoder {
+    type Device = CuStream;
+
+    fn create(stream: &CuStream, key: &DecoderKey) -> NVCodecResult<Self> {
+        let ctx = stream.get_context()?;
+        let _guard = ctx.guard()?;
+
+        let bit_depth = match key.bit_depth {
+            BitDepth::Eight => 8,
+            BitDepth::Sixteen => 10,
+        };
+        let caps = caps::query_current(key.codec, key.chroma_format, bit_depth)?;
+        // An unset size only checks the format.
+        let width = key.max_width.max(caps.min_width);
+        let height = key.max_height.max(caps.min_height);
+        caps.check_size(width, height).map_err(NVCodecError::NotSupported)?;
+
+        let stream = CuStream::new()?;
+        let mut decoder = NVDecoder::new(&stream, key.codec, None, None, false)?;
+        decoder.set_max_size(key.max_width, key.max_height);
+
+        Ok(decoder)
+    }
+
+    fn recycle(&mut self, key: &DecoderKey) -> NVCodecResult<()> {
+        self.flush_for_new_stream(key.codec)
+    }
+}
+
+/// A session slot passed from a returned lease straight to the oldest waiter,
+/// with the decoder that occupied it unless that decoder was lost.
+struct Permit<D> {
+    decoder: Option<(DecoderKey, D)>,
+}
+
+struct DeviceSlot<D: Pooled> {
+    device: D::Device,
+    max_sessions: usize,
+    sessions: usize,
+    leased: usize,
+    waiters: VecDeque<flume::Sender<Permit<D>>>,
+}
+
+struct PoolState<D: Pooled> {
+    devices: HashMap<i32, DeviceSlot<D>>,
+    idle: HashMap<DecoderKey, Vec<D>>,
+    reused: u64,
+    created: u64,
+    evicted: u64,
+    waited: u64,
+}
+
+impl<D: Pooled> Default for PoolState<D> {
+    fn default() -> Self {
+        Self {
+            devices: HashMap::new(),
+            idle: HashMap::new(),
+            reused: 0,
+            created: 0,
+            evicted: 0,
+            waited: 0,
+        }
+    }
+}
+
+enum Acquire<D> {
+    Ready(D),
+    Wait(flume::Receiver<Permit<D>>),
+}
+
+/// Shares a bounded number of NVDEC sessions per device between callers.
+///
+/// Decoders are leased by `DecoderKey` and come back when the lease is
+/// released or dropped, reset for the next stream. A returned decoder is
+/// reused by the next lease with the same key; when a device is at its
+/// session cap, idle decoders of other keys are dropped to make room, and
+/// once none are left callers queue in arrival order.
+#[derive(Clone, Default)]
+pub struct DecoderPool {
+    pool: Pool<NVDecoder>,
+}
+
+impl DecoderPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a device, or updates its cap. Decoders for `device` are
+    /// created in the context of `stream`, each on a new stream of its own.
+    pub fn add_device(&self, device: i32, stream: &CuStream, max_sessions: usize) {
+        self.pool.add_device(device, stream, max_sessions)
+    }
+
+    /// Leases a decoder for `key`, blocking while the device is at its cap.
+    pub fn lease(&self, key: DecoderKey) -> NVCodecResult<DecoderLease> {
+        self.pool.lease(key).map(|lease| DecoderLease { lease })
+    }
+
+    /// Like `lease`, waiting asynchronously. Dropping the future gives up its
+    /// place in the queue.
+    pub async fn lease_async(&self, key: DecoderKey) -> NVCodecResult<DecoderLease> {
+        self.pool.lease_async(key).await.map(|lease| DecoderLease { lease })
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        self.pool.stats()
+    }
+}
+
+/// The sessions and decoders behind `DecoderPool`.
+struct Pool<D: Pooled> {
+    state: Arc<Mutex<PoolState<D>>>,
+}
+
+impl<D: Pooled> Clone for Pool<D> {
+    fn clone(&self) -> Self {
+        Self { state: self.state.clone() }
+    }
+}
+
+impl<D: Pooled> Default for Pool<D> {
+    fn default() -> Self {
+        Self { state: Default::default() }
+    }
+}
+
+impl<D: Pooled> Pool<D> {
+    fn add_device(&self, device: i32, handle: &D::Device, max_sessions: usize) {
+        let mut state = self.state.lock().unwrap();
+        let slot = state.devices.entry(device).or_insert_with(|| DeviceSlot {
+            device: handle.clone(),
+            max_sessions,
+            sessions: 0,
+            leased: 0,
+            waiters: VecDeque::new(),
+        });
+        slot.max_sessions = max_sessions;
+    }
+
+    fn lease(&self, key: DecoderKey) -> NVCodecResult<Lease<D>> {
+        match self.acquire(key)? {
+            Acquire::Ready(decoder) => Ok(self.wrap(key, decoder)),
+            Acquire::Wait(rx) => {
+                let waiter = Waiter { pool: self, device: key.device, rx };
+                let permit = waiter.rx.recv().map_err(|_| NVCodecError::DecoderClosed)?;
+                waiter.redeem(key, permit)
+            }
+        }
+    }
+
+    async fn lease_async(&self, key: DecoderKey) -> NVCodecResult<Lease<D>> {
+        match self.acquire(key)? {
+            Acquire::Ready(decoder) => Ok(self.wrap(key, decoder)),
+            Acquire::Wait(rx) => {
+                let waiter = Waiter { pool: self, device: key.device, rx };
+                let permit = waiter.rx.recv_async().await.map_err(|_| NVCodecError::DecoderClosed)?;
+                waiter.redeem(key, permit)
+            }
+        }
+    }
+
+    fn stats(&self) -> PoolStats {
+        let state = self.state.lock().unwrap();
+
+        let mut devices: Vec<_> = state.devices
+            .iter()
+            .map(|(&device, slot)| DeviceOccupancy {
+                device,
+                max_sessions: slot.max_sessions,
+                leased: slot.leased,
+                idle: slot.sessions - slot.leased,
+                waiting: slot.waiters.iter().filter(|w| !w.is_disconnected()).count(),
+            })
+            .collect();
+        devices.sort_by_key(|occupancy| occupancy.device);
+
+        PoolStats {
+            devices,
+            reused: state.reused,
+            created: state.created,
+            evicted: state.evicted,
+            waited: state.waited,
+        }
+    }
+
+    fn acquire(&self, key: DecoderKey) -> NVCodecResult<Acquire<D>> {
+        let (device, victim) = {
+            let mut state = self.state.lock().unwrap();
+            let state = &mut *state;
+
+            let slot = state.devices.get_mut(&key.device).ok_or_else(|| {
+                NVCodecError::NotSupported(format!("Device {} is not in the pool", key.device))
+            })?;
+
+            // Waiters whose lease was given up no longer hold a place.
+            slot.waiters.retain(|w| !w.is_disconnected());
+
+            if !slot.waiters.is_empty() {
+                return Ok(Acquire::Wait(wait(state, key.device)));
+            }
+
+            if let Some(decoder) = state.idle.get_mut(&key).and_then(Vec::pop) {
+                slot.leased += 1;
+                state.reused += 1;
+                return Ok(Acquire::Ready(decoder));
+            }
+
+            let victim = if slot.sessions < slot.max_sessions {
+                slot.sessions += 1;
+                None
+            } else if let Some(victim) = state.idle
+                .iter_mut()
+                .find(|(k, decoders)| k.device == key.device && !decoders.is_empty())
+                .and_then(|(_, decoders)| decoders.pop())
+            {
+                state.evicted += 1;
+                Some(victim)
+            } else {
+                return Ok(Acquire::Wait(wait(state, key.device)));
+            };
+
+            slot.leased += 1;
+            (slot.device.clone(), victim)
+        };
+
+        // Destroying and creating decoders takes a while, so it happens with
+        // the session reserved but the pool unlocked.
+        drop(victim);
+        self.create_leased(key, &device).map(Acquire::Ready)
+    }
+
+    /// Creates a decoder for a session already counted as leased, giving the
+    /// session up again if that fails.
+    fn create_leased(&self, key: DecoderKey, device: &D::Device) -> NVCodecResult<D> {
+        let res = D::create(device, &key);
+
+        let mut state = self.state.lock().unwrap();
+        match res {
+            Ok(decoder) => {
+                state.created += 1;
+                Ok(decoder)
+            }
+            Err(err) => {
+                if let Some(slot) = state.devices.get_mut(&key.device) {
+                    slot.leased -= 1;
+                }
+                release(&mut state, key.device, Permit { decoder: None });
+                Err(err)
+            }
+        }
+    }
+
+    fn wrap(&self, key: DecoderKey, decoder: D) -> Lease<D> {
+        Lease { pool: self.clone(), key, decoder: Some(decoder) }
+    }
+}
+
+/// Queues a waiter for a session on `device`. Called with the pool locked.
+fn wait<D: Pooled>(state: &mut PoolState<D>, device: i32) -> flume::Receiver<Permit<D>> {
+    let (tx, rx) = flume::bounded(1);
+    state.devices.get_mut(&device).unwrap().waiters.push_back(tx);
+    state.waited += 1;
+    rx
+}
+
+/// Hands `permit`'s session to the oldest live waiter on `device`, or parks
+/// its decoder, or gives the session up. Called with the pool locked.
+fn release<D: Pooled>(state: &mut PoolState<D>, device: i32, mut permit: Permit<D>) {
+    let Some(slot) = state.devices.get_mut(&device) else {
+        return;
+    };
+
+    while let Some(waiter) = slot.waiters.pop_front() {
+        match waiter.send(permit) {
+            Ok(()) => {
+                slot.leased += 1;
+                return;
+            }
+            Err(flume::SendError(returned)) => permit = returned,
+        }
+    }
+
+    match permit.decoder {
+        Some((key, decoder)) => state.idle.entry(key).or_default().push(decoder),
+        None => slot.sessions -= 1,
+    }
+}
+
+/// A queued lease. Passes on a permit that arrives after the caller gave up.
+struct Waiter<'a, D: Pooled> {
+    pool: &'a Pool<D>,
+    device: i32,
+    rx: flume::Receiver<Permit<D>>,
+}
+
+impl<D: Pooled> Waiter<'_, D> {
+    fn redeem(self, key: DecoderKey, permit: Permit<D>) -> NVCodecResult<Lease<D>> {
+        let pool = self.pool;
+        drop(self);
+
+        match permit.decoder {
+            Some((permit_key, decoder)) if permit_key == key => {
+                pool.state.lock().unwrap().reused += 1;
+                Ok(pool.wrap(key, decoder))
+            }
+            other => {
+                let device = {
+                    let mut state = pool.state.lock().unwrap();
+                    if other.is_some() {
+                        state.evicted += 1;
+                    }
+                    state.devices[&key.device].device.clone()
+                };
+                drop(other);
+
+                pool.create_leased(key, &device).map(|decoder| pool.wrap(key, decoder))
+            }
+        }
+    }
+}
+
+impl<D: Pooled> Drop for Waiter<'_, D> {
+    fn drop(&mut self) {
+        // `release` sends with the pool locked, so nothing can arrive between
+        // this check and the receiver going away.
+        let mut state = self.pool.state.lock().unwrap();
+        if let Ok(permit) = self.rx.try_recv() {
+            if let Some(slot) = state.devices.get_mut(&self.device) {
+                slot.leased -= 1;
+            }
+            release(&mut state, self.device, permit);
+        }
+    }
+}
+
+/// A decoder on loan from a `DecoderPool`.
+///
+/// `release` flushes the decoder, ending its frame stream after the last
+/// frame, then returns it for the next stream. Dropping the lease does the
+/// same, logging a failed flush instead of returning it.
+pub struct DecoderLease {
+    lease: Lease<NVDecoder>,
+}
+
+impl DecoderLease {
+    pub fn key(&self) -> DecoderKey {
+        self.lease.key
+    }
+
+    /// Returns the decoder to the pool. A decoder that fails to flush is
+    /// dropped, freeing its session, and the error is returned.
+    pub fn release(self) -> NVCodecResult<()> {
+        self.lease.release()
+    }
+}
+
+impl Deref for DecoderLease {
+    type Target = NVDecoder;
+
+    fn deref(&self) -> &NVDecoder {
+        self.lease.decoder.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for DecoderLease {
+    fn deref_mut(&mut self) -> &mut NVDecoder {
+        self.lease.decoder.as_mut().unwrap()
+    }
+}
+
+struct Lease<D: Pooled> {
+    pool: Pool<D>,
+    key: DecoderKey,
+    decoder: Option<D>,
+}
+
+impl<D: Pooled> Lease<D> {
+    fn release(mut self) -> NVCodecResult<()> {
+        self.give_back()
+    }
+
+    fn give_back(&mut self) -> NVCodecResult<()> {
+        let Some(mut decoder) = self.decoder.take() else {
+            return Ok(());
+        };
+        let res = decoder.recycle(&self.key);
+        let decoder = match res {
+            Ok(()) => Some((self.key, decoder)),
+            Err(_) => None,
+        };
+
+        let mut state = self.pool.state.lock().unwrap();
+        if let Some(slot) = state.devices.get_mut(&self.key.device) {
+            slot.leased -= 1;
+        }
+        release(&mut state, self.key.device, Permit { decoder });
+
+        res
+    }
+}
+
+impl<D: Pooled> Drop for Lease<D> {
+    fn drop(&mut self) {
+        if let Err(err) = self.give_back() {
+            tracing::debug!("Dropping pooled decoder that failed to flush: {:?}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicBool, AtomicU32, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    #[derive(Default)]
+    struct FakeDevice {
+        next_id: AtomicU32,
+        fail_create: AtomicBool,
+        fail_recycle: AtomicBool,
+    }
+
+    /// Stands in for a decoder; `id` tells which one was created when.
+    struct FakeDecoder {
+        id: u32,
+        device: Arc<FakeDevice>,
+    }
+
+    impl Pooled for FakeDecoder {
+        type Device = Arc<FakeDevice>;
+
+        fn create(device: &Arc<FakeDevice>, _: &DecoderKey) -> NVCodecResult<Self> {
+            if device.fail_create.load(Ordering::SeqCst) {
+                return Err(NVCodecError::NotSupported("create".to_string()));
+            }
+            let id = device.next_id.fetch_add(1, Ordering::SeqCst);
+            Ok(FakeDecoder { id, device: device.clone() })
+        }
+
+        fn recycle(&mut self, _: &DecoderKey) -> NVCodecResult<()> {
+            if self.device.fail_recycle.load(Ordering::SeqCst) {
+                return Err(NVCodecError::DecodeError);
+            }
+            Ok(())
+        }
+    }
+
+    fn pool(max_sessions: usize) -> (Pool<FakeDecoder>, Arc<FakeDevice>) {
+        let pool = Pool::default();
+        let device = Arc::new(FakeDevice::default());
+        pool.add_device(0, &device, max_sessions);
+        (pool, device)
+    }
+
+    fn key(codec: CuVideoCodecType) -> DecoderKey {
+        DecoderKey {
+            device: 0,
+            codec,
+            chroma_format: ChromaFormat::YUV420,
+            bit_depth: BitDepth::Eight,
+            max_width: 0,
+            max_height: 0,
+        }
+    }
+
+    fn occupancy(pool: &Pool<FakeDecoder>) -> DeviceOccupancy {
+        pool.stats().devices[0]
+    }
+
+    /// Waits for `waiting` callers to queue on the device.
+    fn wait_for_waiters(pool: &Pool<FakeDecoder>, waiting: usize) {
+        assert!((0..500).any(|_| {
+            thread::sleep(Duration::from_millis(10));
+            occupancy(pool).waiting == waiting
+        }));
+    }
+
+    /// Leases `key` on another thread, reporting the decoder id, then holds
+    /// the lease until told to let go.
+    fn lease_in_thread(
+        pool: &Pool<FakeDecoder>,
+        key: DecoderKey,
+        leased: flume::Sender<u32>,
+    ) -> flume::Sender<()> {
+        let (release_tx, release_rx) = flume::bounded::<()>(0);
+        let pool = pool.clone();
+        thread::spawn(move || {
+            let lease = pool.lease(key).unwrap();
+            leased.send(lease.decoder.as_ref().unwrap().id).unwrap();
+            let _ = release_rx.recv();
+        });
+        release_tx
+    }
+
+    #[test]
+    fn rejects_unknown_devices() {
+        let (pool, _) = pool(1);
+        let res = pool.lease(DecoderKey { device: 1, ..key(CuVideoCodecType::H264) });
+        assert!(matches!(res, Err(NVCodecError::NotSupported(_))));
+    }
+
+    #[test]
+    fn reuses_a_released_decoder() {
+        let (pool, _) = pool(2);
+
+        let lease = pool.lease(key(CuVideoCodecType::H264)).unwrap();
+        assert_eq!(occupancy(&pool).leased, 1);
+        lease.release().unwrap();
+        assert_eq!((occupancy(&pool).leased, occupancy(&pool).idle), (0, 1));
+
+        let lease = pool.lease(key(CuVideoCodecType::H264)).unwrap();
+        assert_eq!(lease.decoder.as_ref().unwrap().id, 0);
+        let stats = pool.stats();
+        assert_eq!((stats.created, stats.reused), (1, 1));
+    }
+
+    #[test]
+    fn returns_the_decoder_on_drop() {
+        let (pool, _) = pool(1);
+
+        drop(pool.lease(key(CuVideoCodecType::H264)).unwrap());
+        assert_eq!((occupancy(&pool).leased, occupancy(&pool).idle), (0, 1));
+    }
+
+    #[test]
+    fn evicts_idle_decoders_of_other_keys_at_the_cap() {
+        let (pool, _) = pool(1);
+
+        drop(pool.lease(key(CuVideoCodecType::H264)).unwrap());
+        let lease = pool.lease(key(CuVideoCodecType::HEVC)).unwrap();
+        assert_eq!(lease.decoder.as_ref().unwrap().id, 1);
+
+        let stats = pool.stats();
+        assert_eq!((stats.created, stats.evicted), (2, 1));
+        assert_eq!((stats.devices[0].leased, stats.devices[0].idle), (1, 0));
+    }
+
+    #[test]
+    fn release_reports_a_failed_flush_and_frees_the_session() {
+        let (pool, device) = pool(1);
+
+        let lease = pool.lease(key(CuVideoCodecType::H264)).unwrap();
+        device.fail_recycle.store(true, Ordering::SeqCst);
+        assert!(matches!(lease.release(), Err(NVCodecError::DecodeError)));
+        assert_eq!((occupancy(&pool).leased, occupancy(&pool).idle), (0, 0));
+
+        // The session is free, so the next lease creates a decoder at once.
+        let lease = pool.lease(key(CuVideoCodecType::H264)).unwrap();
+        assert_eq!(lease.decoder.as_ref().unwrap().id, 1);
+    }
+
+    #[test]
+    fn gives_the_session_up_when_creation_fails() {
+        let (pool, device) = pool(1);
+
+        device.fail_create.store(true, Ordering::SeqCst);
+        assert!(pool.lease(key(CuVideoCodecType::H264)).is_err());
+        assert_eq!(occupancy(&pool), DeviceOccupancy { device: 0, max_sessions: 1, ..Default::default() });
+
+        device.fail_create.store(false, Ordering::SeqCst);
+        assert!(pool.lease(key(CuVideoCodecType::H264)).is_ok());
+    }
+
+    #[test]
+    fn caps_sessions_and_serves_waiters_in_order() {
+        let (pool, _) = pool(1);
+        let (leased_tx, leased_rx) = flume::unbounded();
+
+        let first = pool.lease(key(CuVideoCodecType::H264)).unwrap();
+
+        let release_hevc = lease_in_thread(&pool, key(CuVideoCodecType::HEVC), leased_tx.clone());
+        wait_for_waiters(&pool, 1);
+        let release_h264 = lease_in_thread(&pool, key(CuVideoCodecType::H264), leased_tx);
+        wait_for_waiters(&pool, 2);
+        assert!(leased_rx.try_recv().is_err());
+        assert_eq!(occupancy(&pool).leased, 1);
+
+        // The oldest waiter goes first, although the decoder is for the other key.
+        drop(first);
+        assert_eq!(leased_rx.recv_timeout(Duration::from_secs(5)).unwrap(), 1);
+        assert!(leased_rx.try_recv().is_err());
+        assert_eq!((occupancy(&pool).leased, occupancy(&pool).waiting), (1, 1));
+
+        drop(release_hevc);
+        assert_eq!(leased_rx.recv_timeout(Duration::from_secs(5)).unwrap(), 2);
+        drop(release_h264);
+
+        let stats = pool.stats();
+        assert_eq!((stats.created, stats.evicted, stats.waited), (3, 2, 2));
+        assert!(stats.devices[0].leased + stats.devices[0].idle <= 1);
+    }
+
+    #[test]
+    fn queues_behind_waiters_even_with_an_idle_decoder() {
+        let (pool, _) = pool(2);
+        let (leased_tx, leased_rx) = flume::unbounded();
+
+        let h264 = pool.lease(key(CuVideoCodecType::H264)).unwrap();
+        let hevc = pool.lease(key(CuVideoCodecType::HEVC)).unwrap();
+        let release_waiter = lease_in_thread(&pool, key(CuVideoCodecType::VP9), leased_tx.clone());
+        wait_for_waiters(&pool, 1);
+
+        // Returning the H.264 decoder hands its session to the VP9 waiter, so
+        // a later H.264 lease waits for the HEVC one.
+        drop(h264);
+        assert_eq!(leased_rx.recv_timeout(Duration::from_secs(5)).unwrap(), 2);
+        let release_late = lease_in_thread(&pool, key(CuVideoCodecType::H264), leased_tx);
+        wait_for_waiters(&pool, 1);
+
+        drop(hevc);
+        assert_eq!(leased_rx.recv_timeout(Duration::from_secs(5)).unwrap(), 3);
+        drop(release_waiter);
+        drop(release_late);
+    }
+
+    #[test]
+    fn a_waiter_giving_up_passes_its_place_on() {
+        let (pool, _) = pool(1);
+
+        let first = pool.lease(key(CuVideoCodecType::H264)).unwrap();
+        let mut abandoned = Box::pin(pool.lease_async(key(CuVideoCodecType::HEVC)));
+        assert!(futures::FutureExt::now_or_never(abandoned.as_mut()).is_none());
+        assert_eq!(occupancy(&pool).waiting, 1);
+        drop(abandoned);
+        assert_eq!(occupancy(&pool).waiting, 0);
+
+        drop(first);
+        let lease = pool.lease(key(CuVideoCodecType::H264)).unwrap();
+        assert_eq!(lease.decoder.as_ref().unwrap().id, 0);
+    }
+}
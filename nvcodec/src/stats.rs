@@ -0,0 +1,172 @@
+use crate::{ffi, surface::ChromaFormat};
+use std::time::Duration;
+
+const LATENCY_BUCKETS: usize = 24;
+
+/// Decode-to-display latencies in power-of-two microsecond buckets.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LatencyHistogram {
+    /// `counts[i]` holds latencies below `bucket_bound(i)` that did not fit an
+    /// earlier bucket. The last bucket is open ended.
+    pub counts: [u64; LATENCY_BUCKETS],
+    pub count: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency: Duration) {
+        let micros = latency.as_micros();
+        let bucket = (u128::BITS - micros.leading_zeros()) as usize;
+        self.counts[bucket.min(LATENCY_BUCKETS - 1)] += 1;
+        self.count += 1;
+        self.total += latency;
+        self.max = self.max.max(latency);
+    }
+
+    /// Exclusive upper bound of bucket `i`, `None` for the last one.
+    pub fn bucket_bound(i: usize) -> Option<Duration> {
+        (i < LATENCY_BUCKETS - 1).then(|| Duration::from_micros(1 << i))
+    }
+
+    pub fn mean(&self) -> Option<Duration> {
+        (self.count > 0).then(|| {
+            Duration::from_nanos((self.total.as_nanos() / self.count as u128) as u64)
+        })
+    }
+
+    /// Upper bound of the bucket holding the `q` quantile, `q` in `[0, 1]`.
+    /// The maximum when that is the last bucket.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let rank = ((self.count as f64 * q).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (i, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(Self::bucket_bound(i).unwrap_or(self.max).min(self.max));
+            }
+        }
+
+        Some(self.max)
+    }
+}
+
+/// Counters of an `NVDecoder` since it was created.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DecoderStats {
+    pub packets_in: u64,
+    pub bytes_in: u64,
+    pub pictures_decoded: u64,
+    pub pictures_displayed: u64,
+    /// Pictures decoded but never output: orphaned by a re-created decoder,
+    /// discarded by `NVDecoder::reset`, or failed.
+    pub frames_dropped: u64,
+    /// Pictures NVDEC decoded with concealed errors, also counted as dropped.
+    pub frames_concealed: u64,
+    pub reconfigurations: u64,
+    pub recreations: u64,
+    /// Most frames ever waiting to be taken from the frame channel.
+    pub queue_high_water: usize,
+    pub decode_to_display: LatencyHistogram,
+    /// Decode surfaces holding pictures not yet displayed.
+    pub surfaces_in_use: usize,
+    /// Decode surfaces of the current hardware decoder.
+    pub decode_surfaces: u32,
+}
+
+/// Something an `NVDecoder` did, for feeding a metrics backend through
+/// `NVDecoder::set_event_hook`.
+#[derive(Clone, Debug)]
+pub enum DecoderEvent {
+    SequenceHeader {
+        codec: ffi::cudaVideoCodec,
+        coded_width: u32,
+        coded_height: u32,
+        chroma_format: Option<ChromaFormat>,
+        bit_depth: u8,
+        min_decode_surfaces: u8,
+    },
+    Reconfigured,
+    Recreated,
+    FrameDropped,
+    FrameConcealed,
+    FrameDisplayed { latency: Duration },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(micros: u64) -> Duration {
+        Duration::from_micros(micros)
+    }
+
+    fn histogram(latencies: &[Duration]) -> LatencyHistogram {
+        let mut histogram = LatencyHistogram::default();
+        for &latency in latencies {
+            histogram.record(latency);
+        }
+        histogram
+    }
+
+    #[test]
+    fn bucket_bounds_double() {
+        assert_eq!(LatencyHistogram::bucket_bound(0), Some(micros(1)));
+        assert_eq!(LatencyHistogram::bucket_bound(10), Some(micros(1024)));
+        assert_eq!(LatencyHistogram::bucket_bound(LATENCY_BUCKETS - 2), Some(micros(1 << 22)));
+        assert_eq!(LatencyHistogram::bucket_bound(LATENCY_BUCKETS - 1), None);
+    }
+
+    #[test]
+    fn records_latencies_below_their_bucket_bound() {
+        let histogram = histogram(&[micros(1), micros(511), micros(512)]);
+        assert_eq!(histogram.counts[1], 1);
+        assert_eq!(histogram.counts[9], 1);
+        assert_eq!(histogram.counts[10], 1);
+        assert_eq!(histogram.count, 3);
+        assert_eq!(histogram.max, micros(512));
+    }
+
+    #[test]
+    fn zero_latency_fills_the_first_bucket() {
+        let histogram = histogram(&[Duration::ZERO, Duration::from_nanos(999)]);
+        assert_eq!(histogram.counts[0], 2);
+        assert_eq!(histogram.mean(), Some(Duration::from_nanos(499)));
+        // The first bucket's bound, clamped to the largest latency seen.
+        assert_eq!(histogram.quantile(0.5), Some(Duration::from_nanos(999)));
+    }
+
+    #[test]
+    fn the_last_bucket_is_open() {
+        let histogram = histogram(&[micros(1 << 22), Duration::from_secs(3600)]);
+        assert_eq!(histogram.counts[LATENCY_BUCKETS - 1], 2);
+        assert_eq!(histogram.quantile(0.5), Some(Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn quantile_ends() {
+        let histogram = histogram(&[micros(3), micros(100), micros(5000)]);
+        // q = 0 takes the first latency, q = 1 the last.
+        assert_eq!(histogram.quantile(0.0), Some(micros(4)));
+        assert_eq!(histogram.quantile(1.0), Some(micros(5000)));
+        assert_eq!(histogram.quantile(0.5), Some(micros(128)));
+    }
+
+    #[test]
+    fn quantile_is_clamped_to_the_max() {
+        let histogram = histogram(&[micros(300)]);
+        assert_eq!(histogram.quantile(0.5), Some(micros(300)));
+        assert_eq!(histogram.quantile(1.0), Some(micros(300)));
+    }
+
+    #[test]
+    fn empty_histogram_has_no_statistics() {
+        let histogram = LatencyHistogram::default();
+        assert_eq!(histogram.mean(), None);
+        assert_eq!(histogram.quantile(0.5), None);
+    }
+}
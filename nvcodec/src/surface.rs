@@ -6,4 +6,445 @@ pub enum VideoSurfaceFormat {
     P016 = ffi::cudaVideoSurfaceFormat_enum_cudaVideoSurfaceFormat_P016 as isize,
     YUV444 = ffi::cudaVideoSurfaceFormat_enum_cudaVideoSurfaceFormat_YUV444 as isize,
     YUV444_16Bit = ffi::cudaVideoSurfaceFormat_enum_cudaVideoSurfaceFormat_YUV444_16Bit as isize,
+    /// 4:2:2 surfaces, from Video Codec SDK 13.0.
+    #[cfg(nvcodec_sdk_13_0)]
+    NV16 = ffi::cudaVideoSurfaceFormat_enum_cudaVideoSurfaceFormat_NV16 as isize,
+    #[cfg(nvcodec_sdk_13_0)]
+    P216 = ffi::cudaVideoSurfaceFormat_enum_cudaVideoSurfaceFormat_P216 as isize,
+    /// Luma only, decoded into an NV12 surface whose chroma is not copied out.
+    Y8,
+    /// Luma only, decoded into a P016 surface whose chroma is not copied out.
+    Y16,
+}
+
+impl VideoSurfaceFormat {
+    /// The native surface format for a stream's chroma format and bit depth,
+    /// if the SDK has one. 4:2:2 needs SDK 13.0.
+    pub fn for_chroma_format(chroma_format: ChromaFormat, bit_depth_minus8: u32) -> Option<Self> {
+        let high_bit_depth = bit_depth_minus8 > 0;
+        let format = match chroma_format {
+            ChromaFormat::Monochrome if high_bit_depth => VideoSurfaceFormat::Y16,
+            ChromaFormat::Monochrome => VideoSurfaceFormat::Y8,
+            ChromaFormat::YUV420 if high_bit_depth => VideoSurfaceFormat::P016,
+            ChromaFormat::YUV420 => VideoSurfaceFormat::NV12,
+            #[cfg(nvcodec_sdk_13_0)]
+            ChromaFormat::YUV422 if high_bit_depth => VideoSurfaceFormat::P216,
+            #[cfg(nvcodec_sdk_13_0)]
+            ChromaFormat::YUV422 => VideoSurfaceFormat::NV16,
+            #[cfg(not(nvcodec_sdk_13_0))]
+            ChromaFormat::YUV422 => return None,
+            ChromaFormat::YUV444 if high_bit_depth => VideoSurfaceFormat::YUV444_16Bit,
+            ChromaFormat::YUV444 => VideoSurfaceFormat::YUV444,
+        };
+        Some(format)
+    }
+
+    /// The surface format NVDEC is asked to produce.
+    pub fn cuvid_format(&self) -> ffi::cudaVideoSurfaceFormat {
+        match self {
+            VideoSurfaceFormat::Y8 => VideoSurfaceFormat::NV12 as _,
+            VideoSurfaceFormat::Y16 => VideoSurfaceFormat::P016 as _,
+            _ => *self as _,
+        }
+    }
+
+    /// Whether `nOutputFormatMask` from `cuvidGetDecoderCaps` allows this format.
+    pub fn is_supported_by(&self, output_format_mask: u16) -> bool {
+        output_format_mask & (1 << self.cuvid_format()) != 0
+    }
+
+    pub fn bytes_per_sample(&self) -> u32 {
+        match self {
+            VideoSurfaceFormat::NV12 |
+            VideoSurfaceFormat::YUV444 |
+            VideoSurfaceFormat::Y8 => 1,
+            #[cfg(nvcodec_sdk_13_0)]
+            VideoSurfaceFormat::NV16 => 1,
+            VideoSurfaceFormat::P016 |
+            VideoSurfaceFormat::YUV444_16Bit |
+            VideoSurfaceFormat::Y16 => 2,
+            #[cfg(nvcodec_sdk_13_0)]
+            VideoSurfaceFormat::P216 => 2,
+        }
+    }
+
+    /// Number of chroma planes following the luma plane. Semi-planar formats
+    /// store interleaved UV in a single plane.
+    pub fn chroma_plane_count(&self) -> u32 {
+        match self {
+            VideoSurfaceFormat::Y8 | VideoSurfaceFormat::Y16 => 0,
+            VideoSurfaceFormat::NV12 | VideoSurfaceFormat::P016 => 1,
+            #[cfg(nvcodec_sdk_13_0)]
+            VideoSurfaceFormat::NV16 | VideoSurfaceFormat::P216 => 1,
+            VideoSurfaceFormat::YUV444 | VideoSurfaceFormat::YUV444_16Bit => 2,
+        }
+    }
+
+    /// Rows in each chroma plane for a luma plane of `luma_height` rows.
+    pub fn chroma_height(&self, luma_height: u32) -> u32 {
+        match self {
+            VideoSurfaceFormat::Y8 | VideoSurfaceFormat::Y16 => 0,
+            VideoSurfaceFormat::NV12 | VideoSurfaceFormat::P016 => luma_height.div_ceil(2),
+            #[cfg(nvcodec_sdk_13_0)]
+            VideoSurfaceFormat::NV16 | VideoSurfaceFormat::P216 => luma_height,
+            VideoSurfaceFormat::YUV444 | VideoSurfaceFormat::YUV444_16Bit => luma_height,
+        }
+    }
+
+    /// Horizontal and vertical chroma subsampling factors of the surface.
+    pub fn chroma_subsampling(&self) -> (u32, u32) {
+        match self {
+            VideoSurfaceFormat::NV12 | VideoSurfaceFormat::P016 => (2, 2),
+            #[cfg(nvcodec_sdk_13_0)]
+            VideoSurfaceFormat::NV16 | VideoSurfaceFormat::P216 => (2, 1),
+            VideoSurfaceFormat::YUV444 |
+            VideoSurfaceFormat::YUV444_16Bit |
+            VideoSurfaceFormat::Y8 |
+            VideoSurfaceFormat::Y16 => (1, 1),
+        }
+    }
+
+    /// Rows of the whole surface: luma followed by every chroma plane.
+    pub fn buffer_height(&self, luma_height: u32) -> u32 {
+        luma_height + self.chroma_height(luma_height) * self.chroma_plane_count()
+    }
+
+    /// Planes of a surface of `width` x `height` luma samples whose rows are
+    /// `pitch` bytes apart, in memory order.
+    pub fn planes(&self, width: usize, height: usize, pitch: usize) -> Vec<PlaneLayout> {
+        let bytes_per_sample = self.bytes_per_sample() as usize;
+        let (sx, sy) = self.chroma_subsampling();
+        let chroma_width = width.div_ceil(sx as usize);
+        let chroma_height = self.chroma_height(height as _) as usize;
+
+        let luma = PlaneLayout {
+            offset: 0,
+            pitch,
+            width,
+            height,
+            bytes_per_sample,
+            components: 1,
+            subsampling: (1, 1),
+        };
+        let chroma = PlaneLayout {
+            offset: pitch * height,
+            pitch,
+            width: chroma_width,
+            height: chroma_height,
+            bytes_per_sample,
+            components: 1,
+            subsampling: (sx, sy),
+        };
+
+        match self.chroma_plane_count() {
+            0 => vec![luma],
+            1 => vec![luma, PlaneLayout { components: 2, ..chroma }],
+            _ => vec![
+                luma,
+                chroma,
+                PlaneLayout { offset: chroma.offset + pitch * chroma_height, ..chroma },
+            ],
+        }
+    }
+
+    pub fn bit_depth(&self) -> BitDepth {
+        match self.bytes_per_sample() {
+            1 => BitDepth::Eight,
+            _ => BitDepth::Sixteen,
+        }
+    }
+
+    /// The same layout with samples of `bit_depth`.
+    pub fn with_bit_depth(&self, bit_depth: BitDepth) -> Self {
+        match (self, bit_depth) {
+            (VideoSurfaceFormat::NV12 | VideoSurfaceFormat::P016, BitDepth::Eight) => VideoSurfaceFormat::NV12,
+            (VideoSurfaceFormat::NV12 | VideoSurfaceFormat::P016, BitDepth::Sixteen) => VideoSurfaceFormat::P016,
+            #[cfg(nvcodec_sdk_13_0)]
+            (VideoSurfaceFormat::NV16 | VideoSurfaceFormat::P216, BitDepth::Eight) => VideoSurfaceFormat::NV16,
+            #[cfg(nvcodec_sdk_13_0)]
+            (VideoSurfaceFormat::NV16 | VideoSurfaceFormat::P216, BitDepth::Sixteen) => VideoSurfaceFormat::P216,
+            (VideoSurfaceFormat::YUV444 | VideoSurfaceFormat::YUV444_16Bit, BitDepth::Eight) => VideoSurfaceFormat::YUV444,
+            (VideoSurfaceFormat::YUV444 | VideoSurfaceFormat::YUV444_16Bit, BitDepth::Sixteen) => VideoSurfaceFormat::YUV444_16Bit,
+            (VideoSurfaceFormat::Y8 | VideoSurfaceFormat::Y16, BitDepth::Eight) => VideoSurfaceFormat::Y8,
+            (VideoSurfaceFormat::Y8 | VideoSurfaceFormat::Y16, BitDepth::Sixteen) => VideoSurfaceFormat::Y16,
+        }
+    }
+
+    fn fallbacks(&self) -> &'static [VideoSurfaceFormat] {
+        match self {
+            VideoSurfaceFormat::Y8 | VideoSurfaceFormat::Y16 => &[
+                VideoSurfaceFormat::Y8,
+                VideoSurfaceFormat::Y16,
+            ],
+            _ => &[
+                VideoSurfaceFormat::NV12,
+                VideoSurfaceFormat::P016,
+                VideoSurfaceFormat::YUV444,
+                VideoSurfaceFormat::YUV444_16Bit,
+            ],
+        }
+    }
+}
+
+/// Where one plane of a surface lives and how its samples are laid out.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PlaneLayout {
+    /// Bytes from the start of the surface to the first row of the plane.
+    pub offset: usize,
+    /// Bytes between the starts of consecutive rows.
+    pub pitch: usize,
+    /// Sample positions per row.
+    pub width: usize,
+    /// Rows.
+    pub height: usize,
+    pub bytes_per_sample: usize,
+    /// Components interleaved at each sample position, 2 for the UV plane of NV12.
+    pub components: usize,
+    /// Horizontal and vertical subsampling relative to luma.
+    pub subsampling: (u32, u32),
+}
+
+impl PlaneLayout {
+    /// Bytes spanned by the plane, without padding after the last row.
+    pub fn size(&self) -> usize {
+        if self.height == 0 {
+            return 0;
+        }
+        (self.height - 1) * self.pitch + self.width * self.bytes_per_sample * self.components
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BitDepth {
+    Eight,
+    Sixteen,
+}
+
+/// The output surface a caller would like instead of the stream's native one.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SurfacePreference {
+    /// Surface layout to output, e.g. `NV12` for 4:2:2 or 4:4:4 sources.
+    pub format: Option<VideoSurfaceFormat>,
+    /// Sample size to output, applied on top of `format`.
+    pub bit_depth: Option<BitDepth>,
+}
+
+impl SurfacePreference {
+    /// Picks the surface NVDEC decodes into and the surface handed to the caller.
+    ///
+    /// They only differ when an 8-bit output was asked for but the hardware
+    /// can only produce its 16-bit twin, in which case samples are truncated
+    /// after decode. Requests the hardware cannot honour at all fall back to
+    /// the native format, then to the first supported format.
+    pub fn resolve(
+        &self,
+        native: VideoSurfaceFormat,
+        output_format_mask: u16,
+    ) -> Option<(VideoSurfaceFormat, VideoSurfaceFormat)> {
+        let mut requested = self.format.unwrap_or(native);
+        if let Some(bit_depth) = self.bit_depth {
+            requested = requested.with_bit_depth(bit_depth);
+        }
+
+        if requested.is_supported_by(output_format_mask) {
+            return Some((requested, requested));
+        }
+
+        let wide = requested.with_bit_depth(BitDepth::Sixteen);
+        if requested.bit_depth() == BitDepth::Eight && wide.is_supported_by(output_format_mask) {
+            return Some((wide, requested));
+        }
+
+        std::iter::once(native)
+            .chain(native.fallbacks().iter().copied())
+            .find(|format| format.is_supported_by(output_format_mask))
+            .map(|format| (format, format))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ChromaFormat {
+    Monochrome = ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_Monochrome as isize,
+    YUV420 = ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_420 as isize,
+    YUV422 = ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_422 as isize,
+    YUV444 = ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_444 as isize,
+}
+
+impl ChromaFormat {
+    pub fn from_raw(chroma_format: ffi::cudaVideoChromaFormat) -> Option<Self> {
+        match chroma_format {
+            ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_Monochrome => Some(ChromaFormat::Monochrome),
+            ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_420 => Some(ChromaFormat::YUV420),
+            ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_422 => Some(ChromaFormat::YUV422),
+            ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_444 => Some(ChromaFormat::YUV444),
+            _ => None,
+        }
+    }
+
+    pub fn raw(&self) -> ffi::cudaVideoChromaFormat {
+        match self {
+            ChromaFormat::Monochrome => ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_Monochrome,
+            ChromaFormat::YUV420 => ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_420,
+            ChromaFormat::YUV422 => ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_422,
+            ChromaFormat::YUV444 => ffi::cudaVideoChromaFormat_enum_cudaVideoChromaFormat_444,
+        }
+    }
+
+    /// Horizontal and vertical chroma subsampling factors.
+    pub fn subsampling(&self) -> (u32, u32) {
+        match self {
+            ChromaFormat::Monochrome => (1, 1),
+            ChromaFormat::YUV420 => (2, 2),
+            ChromaFormat::YUV422 => (2, 1),
+            ChromaFormat::YUV444 => (1, 1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mask(formats: &[VideoSurfaceFormat]) -> u16 {
+        formats.iter().fold(0, |mask, format| mask | 1 << format.cuvid_format())
+    }
+
+    fn prefer(format: Option<VideoSurfaceFormat>, bit_depth: Option<BitDepth>) -> SurfacePreference {
+        SurfacePreference { format, bit_depth }
+    }
+
+    #[test]
+    fn with_bit_depth_keeps_the_layout() {
+        use VideoSurfaceFormat::*;
+
+        for (eight, sixteen) in [(NV12, P016), (YUV444, YUV444_16Bit), (Y8, Y16)] {
+            for format in [eight, sixteen] {
+                assert_eq!(format.with_bit_depth(BitDepth::Eight), eight);
+                assert_eq!(format.with_bit_depth(BitDepth::Sixteen), sixteen);
+            }
+            assert_eq!(eight.bit_depth(), BitDepth::Eight);
+            assert_eq!(sixteen.bit_depth(), BitDepth::Sixteen);
+        }
+    }
+
+    #[test]
+    fn native_format_follows_chroma_and_depth() {
+        use VideoSurfaceFormat::*;
+
+        let cases = [
+            (ChromaFormat::Monochrome, Y8, Y16),
+            (ChromaFormat::YUV420, NV12, P016),
+            (ChromaFormat::YUV444, YUV444, YUV444_16Bit),
+        ];
+        for (chroma_format, eight, sixteen) in cases {
+            assert_eq!(VideoSurfaceFormat::for_chroma_format(chroma_format, 0), Some(eight));
+            assert_eq!(VideoSurfaceFormat::for_chroma_format(chroma_format, 2), Some(sixteen));
+            assert_eq!(VideoSurfaceFormat::for_chroma_format(chroma_format, 4), Some(sixteen));
+        }
+    }
+
+    #[cfg(nvcodec_sdk_13_0)]
+    #[test]
+    fn native_422_format() {
+        assert_eq!(VideoSurfaceFormat::for_chroma_format(ChromaFormat::YUV422, 0), Some(VideoSurfaceFormat::NV16));
+        assert_eq!(VideoSurfaceFormat::for_chroma_format(ChromaFormat::YUV422, 2), Some(VideoSurfaceFormat::P216));
+    }
+
+    #[cfg(not(nvcodec_sdk_13_0))]
+    #[test]
+    fn no_422_format_before_sdk_13() {
+        assert_eq!(VideoSurfaceFormat::for_chroma_format(ChromaFormat::YUV422, 0), None);
+        assert_eq!(VideoSurfaceFormat::for_chroma_format(ChromaFormat::YUV422, 2), None);
+    }
+
+    #[cfg(nvcodec_sdk_13_0)]
+    #[test]
+    fn with_bit_depth_keeps_422() {
+        use VideoSurfaceFormat::*;
+
+        assert_eq!(P216.with_bit_depth(BitDepth::Eight), NV16);
+        assert_eq!(NV16.with_bit_depth(BitDepth::Sixteen), P216);
+    }
+
+    #[test]
+    fn native_format_by_default() {
+        use VideoSurfaceFormat::*;
+
+        let resolved = SurfacePreference::default().resolve(P016, mask(&[NV12, P016]));
+        assert_eq!(resolved, Some((P016, P016)));
+    }
+
+    #[test]
+    fn requested_format_when_supported() {
+        use VideoSurfaceFormat::*;
+
+        let preference = prefer(Some(NV12), None);
+        assert_eq!(preference.resolve(YUV444, mask(&[NV12, YUV444])), Some((NV12, NV12)));
+    }
+
+    #[test]
+    fn bit_depth_applies_on_top_of_format() {
+        use VideoSurfaceFormat::*;
+
+        let preference = prefer(Some(YUV444), Some(BitDepth::Sixteen));
+        let resolved = preference.resolve(NV12, mask(&[NV12, YUV444, YUV444_16Bit]));
+        assert_eq!(resolved, Some((YUV444_16Bit, YUV444_16Bit)));
+
+        let preference = prefer(None, Some(BitDepth::Eight));
+        assert_eq!(preference.resolve(P016, mask(&[NV12, P016])), Some((NV12, NV12)));
+    }
+
+    #[test]
+    fn eight_bit_truncated_from_sixteen_bit_surface() {
+        use VideoSurfaceFormat::*;
+
+        let preference = prefer(None, Some(BitDepth::Eight));
+        assert_eq!(preference.resolve(P016, mask(&[P016])), Some((P016, NV12)));
+
+        let preference = prefer(Some(YUV444), None);
+        assert_eq!(preference.resolve(NV12, mask(&[NV12, YUV444_16Bit])), Some((YUV444_16Bit, YUV444)));
+    }
+
+    #[test]
+    fn luma_only_truncated_from_p016() {
+        use VideoSurfaceFormat::*;
+
+        let preference = prefer(Some(Y8), None);
+        assert_eq!(preference.resolve(P016, mask(&[P016])), Some((Y16, Y8)));
+    }
+
+    #[test]
+    fn sixteen_bit_is_never_widened_from_eight() {
+        use VideoSurfaceFormat::*;
+
+        let preference = prefer(None, Some(BitDepth::Sixteen));
+        assert_eq!(preference.resolve(NV12, mask(&[NV12])), Some((NV12, NV12)));
+    }
+
+    #[test]
+    fn unsupported_request_falls_back_to_native() {
+        use VideoSurfaceFormat::*;
+
+        let preference = prefer(Some(YUV444), None);
+        assert_eq!(preference.resolve(P016, mask(&[NV12, P016])), Some((P016, P016)));
+    }
+
+    #[test]
+    fn unsupported_native_falls_back_in_order() {
+        use VideoSurfaceFormat::*;
+
+        let resolved = SurfacePreference::default().resolve(YUV444_16Bit, mask(&[P016, YUV444]));
+        assert_eq!(resolved, Some((P016, P016)));
+
+        let resolved = SurfacePreference::default().resolve(Y16, mask(&[NV12]));
+        assert_eq!(resolved, Some((Y8, Y8)));
+    }
+
+    #[test]
+    fn nothing_supported() {
+        use VideoSurfaceFormat::*;
+
+        assert_eq!(SurfacePreference::default().resolve(NV12, 0), None);
+        assert_eq!(prefer(Some(Y8), None).resolve(Y8, mask(&[YUV444])), None);
+    }
 }
@@ -0,0 +1,351 @@
+use crate::{
+    codec::CuVideoCodecType,
+    color::ColorDescription,
+    cuda::CuStream,
+    decoder::{DecodedFrame, DisplayArea, NVDecoder, Size},
+    demuxer::ffmpeg::Packet,
+    error::{NVCodecError, NVCodecResult},
+    geometry::OutputRequest,
+    surface::SurfacePreference,
+};
+use futures::stream::Stream;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    thread,
+};
+
+enum Command {
+    Decode(Option<Packet>),
+    SetOutput(Option<DisplayArea>, Option<Size>),
+    SetSurfacePreference(SurfacePreference),
+    SetColorOverride(ColorDescription),
+}
+
+/// An `NVDecoder` running on a dedicated thread.
+///
+/// `cuvidParseVideoData` blocks while the parser callbacks decode, map and
+/// copy frames, so calling `NVDecoder::decode` from an async task stalls the
+/// executor. Here the parser, and the `CuContext` it binds, live on a worker
+/// thread: packets go in through `send`, frames come out of the `Stream`
+/// impl, and both queues are bounded so a slow consumer holds back the
+/// producer instead of buffering frames on the GPU. Works on any executor.
+///
+/// The frame stream ends after the end-of-stream packet has been decoded and
+/// every frame delivered, or when the worker stops.
+pub struct ThreadedDecoder {
+    commands: flume::Sender<Command>,
+    frames: flume::r#async::RecvStream<'static, NVCodecResult<DecodedFrame>>,
+}
+
+impl ThreadedDecoder {
+    /// Creates the decoder on its worker thread, blocking until it is ready.
+    ///
+    /// `queue_depth` bounds both the packets waiting to be parsed and the
+    /// frames waiting to be consumed.
+    pub fn new(
+        stream: &CuStream,
+        codec_type: CuVideoCodecType,
+        display_area: Option<DisplayArea>,
+        target_size: Option<Size>,
+        keyframe_only: bool,
+        queue_depth: usize,
+    ) -> NVCodecResult<Self> {
+        let stream = stream.clone();
+        let (commands, frames) = start(
+            move || NVDecoder::new(&stream, codec_type, display_area, target_size, keyframe_only),
+            queue_depth,
+        )?;
+
+        Ok(Self {
+            commands,
+            frames: frames.into_stream(),
+        })
+    }
+
+    /// Queues a packet for decoding, waiting while the queue is full.
+    ///
+    /// `None`, or a packet without data, flushes the decoder and ends the
+    /// frame stream once the remaining frames have been delivered.
+    pub async fn send(&self, packet: Option<Packet>) -> NVCodecResult<()> {
+        self.command(Command::Decode(packet)).await
+    }
+
+    /// See `NVDecoder::set_output`.
+    pub async fn set_output(
+        &self,
+        display_area: Option<DisplayArea>,
+        target_size: Option<Size>,
+    ) -> NVCodecResult<()> {
+        // Reject a bad request here rather than on the frame stream.
+        OutputRequest::new(display_area, target_size)?;
+
+        self.command(Command::SetOutput(display_area, target_size)).await
+    }
+
+    /// See `NVDecoder::set_surface_preference`.
+    pub async fn set_surface_preference(&self, preference: SurfacePreference) -> NVCodecResult<()> {
+        self.command(Command::SetSurfacePreference(preference)).await
+    }
+
+    /// See `NVDecoder::set_color_override`.
+    pub async fn set_color_override(&self, color: ColorDescription) -> NVCodecResult<()> {
+        self.command(Command::SetColorOverride(color)).await
+    }
+
+    async fn command(&self, command: Command) -> NVCodecResult<()> {
+        self.commands
+            .send_async(command)
+            .await
+            .map_err(|_| NVCodecError::DecoderClosed)
+    }
+}
+
+/// What the worker thread drives. Implemented by `NVDecoder`, and by a fake
+/// in the tests so the command loop runs without a GPU.
+trait Worker {
+    type Frame: Send + 'static;
+
+    fn decode(&mut self, packet: Option<&Packet>) -> NVCodecResult<()>;
+
+    fn set_output(&mut self, display_area: Option<DisplayArea>, target_size: Option<Size>) -> NVCodecResult<()>;
+
+    fn set_surface_preference(&mut self, preference: SurfacePreference);
+
+    fn set_color_override(&mut self, color: ColorDescription);
+
+    /// Frames output so far, without waiting for more.
+    fn frames(&self) -> flume::TryIter<'_, NVCodecResult<Self::Frame>>;
+}
+
+impl Worker for NVDecoder {
+    type Frame = DecodedFrame;
+
+    fn decode(&mut self, packet: Option<&Packet>) -> NVCodecResult<()> {
+        NVDecoder::decode(self, packet)
+    }
+
+    fn set_output(&mut self, display_area: Option<DisplayArea>, target_size: Option<Size>) -> NVCodecResult<()> {
+        NVDecoder::set_output(self, display_area, target_size)
+    }
+
+    fn set_surface_preference(&mut self, preference: SurfacePreference) {
+        NVDecoder::set_surface_preference(self, preference)
+    }
+
+    fn set_color_override(&mut self, color: ColorDescription) {
+        NVDecoder::set_color_override(self, color)
+    }
+
+    fn frames(&self) -> flume::TryIter<'_, NVCodecResult<DecodedFrame>> {
+        self.receiver.try_iter()
+    }
+}
+
+/// Creates a worker with `init` on a new thread, blocking until it is ready,
+/// and returns the command and frame queues.
+///
+/// The thread stops after the end-of-stream packet has been decoded and its
+/// frames delivered, once every command sender is gone, or once the frame
+/// receiver is gone. The frame queue disconnects when it stops.
+#[allow(clippy::type_complexity)]
+fn start<W, F>(
+    init: F,
+    queue_depth: usize,
+) -> NVCodecResult<(flume::Sender<Command>, flume::Receiver<NVCodecResult<W::Frame>>)>
+where
+    W: Worker,
+    F: FnOnce() -> NVCodecResult<W> + Send + 'static,
+{
+    let (command_tx, command_rx) = flume::bounded::<Command>(queue_depth);
+    let (frame_tx, frame_rx) = flume::bounded::<NVCodecResult<W::Frame>>(queue_depth);
+    let (init_tx, init_rx) = flume::bounded::<NVCodecResult<()>>(1);
+
+    thread::spawn(move || {
+        let mut worker = match init() {
+            Ok(worker) => {
+                let _ = init_tx.send(Ok(()));
+                worker
+            }
+            Err(e) => {
+                let _ = init_tx.send(Err(e));
+                return;
+            }
+        };
+
+        for command in command_rx.iter() {
+            let eos = match command {
+                Command::Decode(packet) => {
+                    let eos = packet.as_ref().and_then(|p| p.data()).is_none();
+                    if let Err(e) = worker.decode(packet.as_ref()) {
+                        if frame_tx.send(Err(e)).is_err() {
+                            return;
+                        }
+                    }
+                    eos
+                }
+                Command::SetOutput(display_area, target_size) => {
+                    if let Err(e) = worker.set_output(display_area, target_size) {
+                        if frame_tx.send(Err(e)).is_err() {
+                            return;
+                        }
+                    }
+                    false
+                }
+                Command::SetSurfacePreference(preference) => {
+                    worker.set_surface_preference(preference);
+                    false
+                }
+                Command::SetColorOverride(color) => {
+                    worker.set_color_override(color);
+                    false
+                }
+            };
+
+            // Blocks while the consumer is behind, which in turn holds
+            // back `send`.
+            for frame in worker.frames() {
+                if frame_tx.send(frame).is_err() {
+                    return;
+                }
+            }
+
+            if eos {
+                return;
+            }
+        }
+    });
+
+    init_rx.recv().map_err(|_| NVCodecError::DecoderClosed)??;
+
+    Ok((command_tx, frame_rx))
+}
+
+impl Stream for ThreadedDecoder {
+    type Item = NVCodecResult<DecodedFrame>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.frames).poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Outputs two frames on end of stream and rejects every output request.
+    /// `alive` disconnects once the worker has been dropped.
+    struct FakeWorker {
+        tx: flume::Sender<NVCodecResult<u32>>,
+        rx: flume::Receiver<NVCodecResult<u32>>,
+        _alive: flume::Sender<()>,
+    }
+
+    impl Worker for FakeWorker {
+        type Frame = u32;
+
+        fn decode(&mut self, packet: Option<&Packet>) -> NVCodecResult<()> {
+            assert!(packet.is_none());
+            self.tx.send(Ok(1)).unwrap();
+            self.tx.send(Ok(2)).unwrap();
+            Ok(())
+        }
+
+        fn set_output(&mut self, _: Option<DisplayArea>, _: Option<Size>) -> NVCodecResult<()> {
+            Err(NVCodecError::NotSupported("output".to_string()))
+        }
+
+        fn set_surface_preference(&mut self, _: SurfacePreference) {}
+
+        fn set_color_override(&mut self, _: ColorDescription) {}
+
+        fn frames(&self) -> flume::TryIter<'_, NVCodecResult<u32>> {
+            self.rx.try_iter()
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn start_fake(
+        queue_depth: usize,
+    ) -> (flume::Sender<Command>, flume::Receiver<NVCodecResult<u32>>, flume::Receiver<()>) {
+        let (alive_tx, alive_rx) = flume::bounded(1);
+        let (commands, frames) = start(
+            move || {
+                let (tx, rx) = flume::unbounded();
+                Ok(FakeWorker { tx, rx, _alive: alive_tx })
+            },
+            queue_depth,
+        )
+        .unwrap();
+
+        (commands, frames, alive_rx)
+    }
+
+    fn stopped(alive: &flume::Receiver<()>) -> bool {
+        matches!(alive.recv_timeout(Duration::from_secs(5)), Err(flume::RecvTimeoutError::Disconnected))
+    }
+
+    /// Whether the worker dropped its end of the command queue, polling since
+    /// that happens after the worker itself is dropped.
+    fn closed(commands: &flume::Sender<Command>) -> bool {
+        (0..500).any(|_| {
+            thread::sleep(Duration::from_millis(10));
+            commands.is_disconnected()
+        })
+    }
+
+    #[test]
+    fn reports_init_errors() {
+        let res = start::<FakeWorker, _>(|| Err(NVCodecError::NotSupported("codec".to_string())), 1);
+        assert!(matches!(res, Err(NVCodecError::NotSupported(_))));
+    }
+
+    #[test]
+    fn ends_the_frame_stream_after_end_of_stream() {
+        let (commands, frames, alive) = start_fake(4);
+
+        commands.send(Command::SetColorOverride(ColorDescription::default())).unwrap();
+        commands.send(Command::Decode(None)).unwrap();
+
+        let frames = frames.iter().map(Result::unwrap).collect::<Vec<_>>();
+        assert_eq!(frames, [1, 2]);
+        assert!(stopped(&alive));
+        assert!(closed(&commands));
+    }
+
+    #[test]
+    fn forwards_command_errors_on_the_frame_stream() {
+        let (commands, frames, _alive) = start_fake(4);
+
+        commands.send(Command::SetOutput(None, None)).unwrap();
+        commands.send(Command::Decode(None)).unwrap();
+
+        let frames = frames.iter().collect::<Vec<_>>();
+        assert_eq!(frames.len(), 3);
+        assert!(matches!(frames[0], Err(NVCodecError::NotSupported(_))));
+        assert!(matches!(frames[1..], [Ok(1), Ok(2)]));
+    }
+
+    #[test]
+    fn stops_when_the_decoder_is_dropped() {
+        let (commands, frames, alive) = start_fake(4);
+
+        commands.send(Command::SetSurfacePreference(SurfacePreference::default())).unwrap();
+        drop(commands);
+
+        assert!(stopped(&alive));
+        assert_eq!(frames.iter().count(), 0);
+    }
+
+    #[test]
+    fn stops_when_the_frame_stream_is_dropped() {
+        let (commands, frames, alive) = start_fake(1);
+
+        drop(frames);
+        commands.send(Command::Decode(None)).unwrap();
+
+        assert!(stopped(&alive));
+        assert!(closed(&commands));
+    }
+}
@@ -0,0 +1,126 @@
+//! Demuxes a Motion JPEG AVI written on the fly. Needs FFmpeg at runtime:
+//! `cargo test --features ffmpeg-tests`.
+#![cfg(feature = "ffmpeg-tests")]
+
+use ffmpeg_next::codec::Id as CodecId;
+use nvcodec::{codec::CuVideoCodecType, demuxer::ffmpeg::FFmpegDemuxStream};
+use std::path::PathBuf;
+
+const FRAMES: u32 = 3;
+
+/// An 8x8 grey baseline JPEG as AVI MJPEG stores it: an `AVI1` APP0 and no
+/// Huffman tables, relying on the standard ones.
+fn frame() -> Vec<u8> {
+    let mut jpeg = vec![0xff, 0xd8];
+    // APP0 "AVI1"
+    jpeg.extend_from_slice(&[0xff, 0xe0, 0x00, 0x10]);
+    jpeg.extend_from_slice(b"AVI1");
+    jpeg.extend_from_slice(&[0; 10]);
+    // DQT, all ones
+    jpeg.extend_from_slice(&[0xff, 0xdb, 0x00, 0x43, 0x00]);
+    jpeg.extend_from_slice(&[1; 64]);
+    // SOF0, 8x8, one component
+    jpeg.extend_from_slice(&[0xff, 0xc0, 0x00, 0x0b, 8, 0, 8, 0, 8, 1, 1, 0x11, 0]);
+    // SOS, then one block: DC difference 0 and end of block, padded with ones
+    jpeg.extend_from_slice(&[0xff, 0xda, 0x00, 0x08, 1, 1, 0x00, 0, 63, 0]);
+    jpeg.push(0b0010_1011);
+    jpeg.extend_from_slice(&[0xff, 0xd9]);
+    jpeg
+}
+
+fn chunk(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut out = id.to_vec();
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+    out
+}
+
+fn list(kind: &[u8; 4], items: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = kind.to_vec();
+    for item in items {
+        data.extend_from_slice(item);
+    }
+    chunk(b"LIST", &data)
+}
+
+fn words(values: &[u32]) -> Vec<u8> {
+    values.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+fn write_avi() -> PathBuf {
+    let frame = frame();
+    let fourcc = |tag: &[u8; 4]| u32::from_le_bytes(*tag);
+
+    // dwMicroSecPerFrame, dwMaxBytesPerSec, dwPaddingGranularity, dwFlags
+    // (AVIF_HASINDEX), dwTotalFrames, dwInitialFrames, dwStreams,
+    // dwSuggestedBufferSize, dwWidth, dwHeight, dwReserved[4]
+    let avih = words(&[40_000, 0, 0, 0x10, FRAMES, 0, 1, 0, 8, 8, 0, 0, 0, 0]);
+    // fccType, fccHandler, dwFlags, wPriority and wLanguage, dwInitialFrames,
+    // dwScale, dwRate, dwStart, dwLength, dwSuggestedBufferSize, dwQuality,
+    // dwSampleSize, rcFrame
+    let mut strh = words(&[fourcc(b"vids"), fourcc(b"MJPG"), 0, 0, 0, 1, 25, 0, FRAMES, 0, u32::MAX, 0]);
+    strh.extend_from_slice(&[0, 0, 0, 0, 8, 0, 8, 0]);
+    // BITMAPINFOHEADER
+    let mut strf = words(&[40, 8, 8]);
+    strf.extend_from_slice(&[1, 0, 24, 0]);
+    strf.extend_from_slice(&words(&[fourcc(b"MJPG"), 8 * 8 * 3, 0, 0, 0, 0]));
+
+    let hdrl = list(b"hdrl", &[
+        chunk(b"avih", &avih),
+        list(b"strl", &[chunk(b"strh", &strh), chunk(b"strf", &strf)]),
+    ]);
+
+    let frames: Vec<_> = (0..FRAMES).map(|_| chunk(b"00dc", &frame)).collect();
+    // Offsets count from the `movi` tag.
+    let mut idx1 = vec![];
+    let mut offset = 4;
+    for data in &frames {
+        idx1.extend_from_slice(&words(&[fourcc(b"00dc"), 0x10, offset, frame.len() as u32]));
+        offset += data.len() as u32;
+    }
+    let movi = list(b"movi", &frames);
+
+    let mut riff = b"AVI ".to_vec();
+    riff.extend_from_slice(&hdrl);
+    riff.extend_from_slice(&movi);
+    riff.extend_from_slice(&chunk(b"idx1", &idx1));
+
+    let path = std::env::temp_dir().join(format!("nvcodec-mjpeg-{}.avi", std::process::id()));
+    std::fs::write(&path, chunk(b"RIFF", &riff)).unwrap();
+    path
+}
+
+fn find(data: &[u8], marker: u8) -> Option<usize> {
+    data.windows(2).position(|w| w == [0xff, marker])
+}
+
+#[test]
+fn mjpeg_avi_demuxes_to_complete_jpeg() {
+    ffmpeg_next::init().unwrap();
+    let path = write_avi();
+    let demuxer = FFmpegDemuxStream::new(&path).unwrap();
+
+    assert_eq!(demuxer.codec_id, CodecId::MJPEG);
+    assert_eq!(CuVideoCodecType::try_from(demuxer.codec_id).unwrap(), CuVideoCodecType::JPEG);
+    assert_eq!((demuxer.width, demuxer.height), (8, 8));
+
+    let packets: Vec<_> = demuxer.packets().collect::<Result<_, _>>().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(packets.len(), FRAMES as usize);
+
+    for packet in &packets {
+        let data = packet.data().unwrap();
+        assert_eq!(&data[..2], [0xff, 0xd8], "SOI first");
+        assert_eq!(&data[data.len() - 2..], [0xff, 0xd9], "EOI last");
+
+        let dht = find(data, 0xc4).expect("standard Huffman tables added");
+        let dqt = find(data, 0xdb).expect("quantization table kept");
+        let sof = find(data, 0xc0).expect("frame header kept");
+        let sos = find(data, 0xda).expect("scan kept");
+        assert!(dht < sos && dqt < sof && sof < sos);
+        assert!(packet.is_key());
+    }
+}
@@ -0,0 +1,91 @@
+//! Muxes synthetic Annex B H.264 with B-frames to MP4 and demuxes it back.
+//! Needs FFmpeg at runtime: `cargo test --features ffmpeg-tests`.
+#![cfg(feature = "ffmpeg-tests")]
+
+use ffmpeg_next::{codec::Id as CodecId, util::rational::Rational};
+use nvcodec::{
+    demuxer::ffmpeg::FFmpegDemuxStream,
+    muxer::ffmpeg::{AccessUnit, Container, FFmpegMuxer},
+};
+
+// High profile, level 3.1, 1280x720.
+const SPS: [u8; 26] = [
+    0x67, 0x64, 0x00, 0x1f, 0xac, 0xd9, 0x40, 0x50, 0x05, 0xbb, 0x01, 0x10, 0x00,
+    0x00, 0x03, 0x00, 0x10, 0x00, 0x00, 0x03, 0x03, 0xc0, 0xf1, 0x83, 0x19, 0x60,
+];
+const PPS: [u8; 6] = [0x68, 0xeb, 0xe3, 0xcb, 0x22, 0xc0];
+const IDR: [u8; 4] = [0x65, 0x88, 0x84, 0x21];
+const NON_IDR: [u8; 4] = [0x41, 0x9a, 0x02, 0x04];
+
+/// (pts, dts, key) in decode order: two GOPs of I P B B P B B in 1/25 s,
+/// dts running one frame behind so that dts <= pts.
+const FRAMES: [(i64, i64, bool); 9] = [
+    (0, -1, true),
+    (3, 0, false),
+    (1, 1, false),
+    (2, 2, false),
+    (6, 3, false),
+    (4, 4, false),
+    (5, 5, false),
+    (7, 6, true),
+    (8, 7, false),
+];
+
+fn annexb(nals: &[&[u8]]) -> Vec<u8> {
+    let mut out = vec![];
+    for nal in nals {
+        out.extend_from_slice(&[0, 0, 0, 1]);
+        out.extend_from_slice(nal);
+    }
+    out
+}
+
+/// `ts` in `time_base` as a number of 1/25 s frames.
+fn frames(ts: i64, time_base: Rational) -> i64 {
+    ts * time_base.numerator() as i64 * 25 / time_base.denominator() as i64
+}
+
+#[test]
+fn h264_mp4_round_trip() {
+    ffmpeg_next::init().unwrap();
+    let path = std::env::temp_dir().join(format!("nvcodec-mux-{}.mp4", std::process::id()));
+
+    let mut muxer = FFmpegMuxer::new(&path, Container::MP4, CodecId::H264, 1280, 720, Rational::new(1, 25)).unwrap();
+    // Cannot be decoded without the parameter sets, so it is dropped.
+    let leading = annexb(&[&NON_IDR]);
+    muxer
+        .write(AccessUnit { data: &leading, pts: Some(-1), dts: Some(-2), duration: 1, key: false })
+        .unwrap();
+    assert_eq!(muxer.skipped(), 1);
+
+    for (pts, dts, key) in FRAMES {
+        let data = if key { annexb(&[&SPS, &PPS, &IDR]) } else { annexb(&[&NON_IDR]) };
+        muxer
+            .write(AccessUnit { data: &data, pts: Some(pts), dts: Some(dts), duration: 1, key })
+            .unwrap();
+    }
+    muxer.finish().unwrap();
+
+    let demuxer = FFmpegDemuxStream::new(&path).unwrap();
+    assert_eq!(demuxer.codec_id, CodecId::H264);
+    assert_eq!((demuxer.width, demuxer.height), (1280, 720));
+    let time_base = demuxer.time_base;
+
+    let packets: Vec<_> = demuxer.packets().collect::<Result<_, _>>().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(packets.len(), FRAMES.len());
+
+    // An edit list may shift the whole timeline; the first picture says by
+    // how much.
+    let shift = frames(packets[0].pts().unwrap(), time_base) - FRAMES[0].0;
+    for (packet, &(pts, dts, key)) in packets.iter().zip(&FRAMES) {
+        assert_eq!(frames(packet.pts().unwrap(), time_base) - shift, pts);
+        assert_eq!(frames(packet.dts().unwrap(), time_base) - shift, dts);
+        assert_eq!(packet.is_key(), key, "pts {}", pts);
+
+        // Back to Annex B, the slice intact.
+        let slice = if key { &IDR } else { &NON_IDR };
+        let data = packet.data().unwrap();
+        assert!(data.windows(slice.len() + 3).any(|w| w[..3] == [0, 0, 1] && w[3..] == *slice));
+    }
+}